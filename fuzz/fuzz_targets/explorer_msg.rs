@@ -0,0 +1,197 @@
+//! Feeds arbitrary explorer messages, interleaved with Sunrays, to
+//! [process_explorer_msg] and checks every answer against its request.
+//!
+//! The planet state can only be built by `common_game`, so each input runs
+//! on a real Orbitron planet: [Harness] takes over the planet's first
+//! Sunray and plays the whole input from there, on the fuzzer's thread.
+#![no_main]
+use std::sync::{LazyLock, Mutex};
+
+use arbitrary::{Arbitrary, Unstructured};
+use common_game::components::forge::Forge;
+use common_game::components::planet::{DummyPlanetState, PlanetAI, PlanetState};
+use common_game::components::resource::{BasicResourceType, Combinator, Generator};
+use common_game::components::rocket::Rocket;
+use common_game::components::sunray::Sunray;
+use common_game::protocols::orchestrator_planet::OrchestratorToPlanet;
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+use crossbeam_channel::unbounded;
+use libfuzzer_sys::fuzz_target;
+use orbitron::{Orbitron, create_planet_with_ai, process_explorer_msg, process_sunray};
+
+const PLANET_ID: u32 = 1;
+
+/// The forge is a process-wide singleton, so every input shares this one.
+static FORGE: LazyLock<Mutex<Forge>> =
+    LazyLock::new(|| Mutex::new(Forge::new().expect("forge already created")));
+
+const BASIC_RESOURCES: [BasicResourceType; 4] = [
+    BasicResourceType::Hydrogen,
+    BasicResourceType::Oxygen,
+    BasicResourceType::Carbon,
+    BasicResourceType::Silicon,
+];
+
+/// One event reaching the planet.
+#[derive(Debug)]
+enum Step {
+    Sunray,
+    Explorer(ExplorerToPlanet),
+}
+
+/// `ExplorerToPlanet` is foreign, so the impl lives on [Step]. Combine
+/// requests are left out: their inputs are resources only a planet can
+/// make.
+impl<'a> Arbitrary<'a> for Step {
+    fn arbitrary(u: &mut Unstructured<'a>) -> arbitrary::Result<Self> {
+        // A handful of explorers, so per-explorer state gets reused
+        let explorer_id = u.int_in_range(1..=4)?;
+        let msg = match u.int_in_range(0..=4)? {
+            0 => return Ok(Step::Sunray),
+            1 => ExplorerToPlanet::SupportedResourceRequest { explorer_id },
+            2 => ExplorerToPlanet::SupportedCombinationRequest { explorer_id },
+            3 => ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id,
+                resource: *u.choose(&BASIC_RESOURCES)?,
+            },
+            _ => ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id },
+        };
+        Ok(Step::Explorer(msg))
+    }
+}
+
+/// Planet AI running `steps` on the Orbitron it wraps.
+struct Harness {
+    ai: Orbitron,
+    steps: Vec<Step>,
+}
+
+impl Harness {
+    fn play(&mut self, state: &mut PlanetState, generator: &Generator, combinator: &Combinator) {
+        for step in std::mem::take(&mut self.steps) {
+            let msg = match step {
+                Step::Sunray => {
+                    let sunray = FORGE.lock().unwrap().generate_sunray();
+                    process_sunray(&mut self.ai, state, generator, combinator, sunray);
+                    continue;
+                }
+                Step::Explorer(msg) => msg,
+            };
+            let request = format!("{msg:?}");
+            let requested = match msg {
+                ExplorerToPlanet::GenerateResourceRequest { resource, .. } => Some(resource),
+                _ => None,
+            };
+            let response = process_explorer_msg(&mut self.ai, state, generator, combinator, msg)
+                .unwrap_or_else(|| panic!("no answer to {request}"));
+            check(&request, requested, &response, state);
+        }
+    }
+}
+
+/// Checks that `response` answers `request` and stays within the planet.
+fn check(
+    request: &str,
+    requested: Option<BasicResourceType>,
+    response: &PlanetToExplorer,
+    state: &PlanetState,
+) {
+    let answers = match response {
+        PlanetToExplorer::SupportedResourceResponse { .. } => "SupportedResourceRequest",
+        PlanetToExplorer::SupportedCombinationResponse { .. } => "SupportedCombinationRequest",
+        PlanetToExplorer::GenerateResourceResponse { resource } => {
+            if let (Some(resource), Some(requested)) = (resource, requested) {
+                assert_eq!(resource.get_type(), requested, "wrong resource for {request}");
+            }
+            "GenerateResourceRequest"
+        }
+        PlanetToExplorer::AvailableEnergyCellResponse { available_cells } => {
+            assert!(
+                *available_cells as usize <= state.cells_count(),
+                "{available_cells} cells available out of {}",
+                state.cells_count()
+            );
+            "AvailableEnergyCellRequest"
+        }
+        other => panic!("{other:?} answering {request}"),
+    };
+    assert!(request.starts_with(answers), "{response:?} answering {request}");
+}
+
+impl PlanetAI for Harness {
+    fn handle_sunray(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        sunray: Sunray,
+    ) {
+        process_sunray(&mut self.ai, state, generator, combinator, sunray);
+        self.play(state, generator, combinator);
+    }
+
+    fn handle_internal_state_req(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+    ) -> DummyPlanetState {
+        self.ai.handle_internal_state_req(state, generator, combinator)
+    }
+
+    fn handle_explorer_msg(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        msg: ExplorerToPlanet,
+    ) -> Option<PlanetToExplorer> {
+        process_explorer_msg(&mut self.ai, state, generator, combinator, msg)
+    }
+
+    fn handle_asteroid(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+    ) -> Option<Rocket> {
+        self.ai.handle_asteroid(state, generator, combinator)
+    }
+
+    fn on_start(&mut self, state: &PlanetState, generator: &Generator, combinator: &Combinator) {
+        self.ai.on_start(state, generator, combinator);
+    }
+
+    fn on_stop(&mut self, state: &PlanetState, generator: &Generator, combinator: &Combinator) {
+        self.ai.on_stop(state, generator, combinator);
+    }
+}
+
+fuzz_target!(|steps: Vec<Step>| {
+    let (to_planet, from_orchestrator) = unbounded();
+    let (to_orchestrator, _from_planet) = unbounded();
+    let (_to_planet_explorers, from_explorer) = unbounded();
+    let harness = Harness {
+        ai: Orbitron::new(PLANET_ID),
+        steps,
+    };
+    let mut planet = create_planet_with_ai(
+        from_orchestrator,
+        to_orchestrator,
+        from_explorer,
+        PLANET_ID,
+        Box::new(harness),
+    );
+
+    // Everything is queued up front: the planet loop runs on this thread
+    // and returns once it gets to KillPlanet.
+    let sunray = FORGE.lock().unwrap().generate_sunray();
+    for msg in [
+        OrchestratorToPlanet::StartPlanetAI,
+        OrchestratorToPlanet::Sunray(sunray),
+        OrchestratorToPlanet::KillPlanet,
+    ] {
+        to_planet.send(msg).unwrap();
+    }
+    planet.run().unwrap();
+});
@@ -1 +1,16 @@
+#[cfg(feature = "checkpoint")]
+pub mod checkpoint;
+pub mod config;
+pub mod error;
+pub(crate) mod handlers;
+pub(crate) mod logging;
 pub mod orbitron;
+pub(crate) mod recipes;
+mod registry;
+mod relay;
+mod rng;
+mod stats;
+pub mod strategy;
+#[cfg(feature = "metrics")]
+mod timing;
+mod watchdog;
@@ -0,0 +1,517 @@
+//! # Orbitron – Checkpoints
+//!
+//! Only built with the `checkpoint` feature. [Orbitron::save_checkpoint]
+//! writes the configuration, the lifetime counters, the state of the random
+//! generator and the charge of every energy cell to a JSON file, and
+//! [Orbitron::load_checkpoint] builds a new AI from it for a long simulation
+//! to carry on, drawing the same numbers the saved AI would have drawn.
+//!
+//! Every file starts with a format name and a [CHECKPOINT_VERSION], checked
+//! before anything else is read, so a checkpoint written by another version
+//! is reported as such instead of being half restored.
+//!
+//! Cell charges are put back on the [PlanetState] given to the loader.
+//! Charging a cell takes a [Sunray], and only the orchestrator's forge makes
+//! them, so the loader also takes a source of Sunrays, e.g.
+//! `|| forge.generate_sunray()`. A planet only lends its state mutably to its
+//! AI, so the loader is meant to be called from a handler of a wrapping
+//! [PlanetAI](common_game::components::planet::PlanetAI).
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Write};
+use std::path::Path;
+
+use common_game::components::planet::PlanetState;
+use common_game::components::resource::ComplexResourceType;
+use common_game::components::sunray::Sunray;
+use common_game::utils::ID;
+use serde::{Deserialize, Serialize};
+
+use crate::ai::config::OrbitronConfig;
+use crate::ai::orbitron::{Lifecycle, Orbitron};
+use crate::ai::rng::Rng;
+use crate::ai::stats::{RefusalReason, RoundStats};
+
+/// Format name at the start of every checkpoint.
+const FORMAT: &str = "orbitron-checkpoint";
+
+/// Version of the checkpoint layout, bumped whenever it changes.
+pub const CHECKPOINT_VERSION: u32 = 3;
+
+/// Everything [Orbitron::save_checkpoint] writes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub format: String,
+    pub version: u32,
+    pub planet_id: ID,
+    pub config: OrbitronConfig,
+    pub counters: Counters,
+    /// State of the random generator, so weighted suggestions carry on
+    /// where they stopped instead of restarting from the seed.
+    pub rng_state: u64,
+    /// Whether each energy cell was charged, by index.
+    pub cells: Vec<bool>,
+}
+
+/// Lifetime counters of the AI, as found in the
+/// [OrbitronSnapshot](crate::ai::stats::OrbitronSnapshot).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Counters {
+    pub lifecycle: Lifecycle,
+    pub tick: u64,
+    pub handled: u64,
+    #[serde(with = "by_name")]
+    pub complex_generated: HashMap<ComplexResourceType, u64>,
+    pub explorer_messages: u64,
+    pub explorer_requests: HashMap<String, u64>,
+    pub refusal_reasons: HashMap<RefusalReason, u64>,
+    pub asteroids_faced: u64,
+    pub asteroids_survived: u64,
+    pub last_asteroid_tick: Option<u64>,
+    pub suspected_duplicate_sunrays: u64,
+    pub protocol_anomalies: u64,
+    pub memory_pressure_events: u64,
+    pub charge_events: HashMap<usize, u64>,
+    pub sunrays_wasted: u64,
+    pub generations_succeeded: u64,
+    pub generations_failed: u64,
+    pub generator_errors: u64,
+    pub generator_retries_succeeded: u64,
+    pub messages_after_destruction: u64,
+    pub current_round: u32,
+    pub rounds: Vec<RoundStats>,
+}
+
+impl Checkpoint {
+    /// Reads the checkpoint at `path`, failing with
+    /// [InvalidData](io::ErrorKind::InvalidData) when it isn't one or was
+    /// written with another [CHECKPOINT_VERSION].
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let value: serde_json::Value = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        if value.get("format").and_then(|format| format.as_str()) != Some(FORMAT) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} is not an Orbitron checkpoint", path.display()),
+            ));
+        }
+        let version = value.get("version").and_then(|version| version.as_u64());
+        if version != Some(u64::from(CHECKPOINT_VERSION)) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "unsupported checkpoint version {}, expected {CHECKPOINT_VERSION}",
+                    version.map_or("-".into(), |version| version.to_string())
+                ),
+            ));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+impl Orbitron {
+    /// Writes the configuration, the lifetime counters, the random generator
+    /// and the charge of the cells of `state` to `path`, see [Checkpoint].
+    pub fn save_checkpoint(&self, state: &PlanetState, path: &Path) -> io::Result<()> {
+        let checkpoint = Checkpoint {
+            format: FORMAT.into(),
+            version: CHECKPOINT_VERSION,
+            planet_id: state.id(),
+            config: self.config(),
+            counters: Counters {
+                lifecycle: self.lifecycle,
+                tick: self.tick,
+                handled: self.logical_time,
+                complex_generated: self.complex_generated.clone(),
+                explorer_messages: self.explorer_msgs,
+                explorer_requests: self.explorer_requests.clone(),
+                refusal_reasons: self.refusal_reasons.clone(),
+                asteroids_faced: self.asteroids_faced,
+                asteroids_survived: self.asteroids_survived,
+                last_asteroid_tick: self.last_asteroid_tick,
+                suspected_duplicate_sunrays: self.suspected_duplicate_sunrays,
+                protocol_anomalies: self.protocol_anomalies,
+                memory_pressure_events: self.memory_pressure_events,
+                charge_events: self.charge_events.clone(),
+                sunrays_wasted: self.sunrays_wasted,
+                generations_succeeded: self.generations_succeeded,
+                generations_failed: self.generations_failed,
+                generator_errors: self.generator_errors,
+                generator_retries_succeeded: self.generator_retries_succeeded,
+                messages_after_destruction: self.messages_after_destruction,
+                current_round: self.current_round,
+                rounds: self.round_stats(),
+            },
+            rng_state: self.rng.state(),
+            cells: state.cells_iter().map(|cell| cell.is_charged()).collect(),
+        };
+        let mut file = BufWriter::new(File::create(path)?);
+        serde_json::to_writer_pretty(&mut file, &checkpoint)?;
+        file.flush()
+    }
+
+    /// Builds an AI with the configuration, counters and random generator
+    /// saved at `path`, and charges or discharges the cells of `state` to
+    /// match the saved ones, taking a Sunray from `sunray` for each cell to
+    /// charge.
+    ///
+    /// It waits for `StartPlanetAI` like a new one, unless the planet was
+    /// destroyed. Fails with [InvalidData](io::ErrorKind::InvalidData),
+    /// leaving `state` untouched, when `state` hasn't as many cells as the
+    /// saved planet.
+    pub fn load_checkpoint(
+        path: &Path,
+        state: &mut PlanetState,
+        mut sunray: impl FnMut() -> Sunray,
+    ) -> io::Result<Orbitron> {
+        let Checkpoint {
+            planet_id,
+            config,
+            counters,
+            rng_state,
+            cells,
+            ..
+        } = Checkpoint::read(path)?;
+        if cells.len() != state.cells_count() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "checkpoint has {} energy cells, the planet has {}",
+                    cells.len(),
+                    state.cells_count()
+                ),
+            ));
+        }
+        for (cell, charged) in state.cells_iter_mut().zip(cells) {
+            if charged && !cell.is_charged() {
+                cell.charge(sunray());
+            } else if !charged && cell.is_charged() {
+                // can't fail, the cell is charged
+                let _ = cell.discharge();
+            }
+        }
+        let mut ai = Orbitron::with_config(planet_id, config);
+        ai.rng = Rng::from_state(rng_state);
+        if counters.lifecycle == Lifecycle::Destroyed {
+            ai.lifecycle = Lifecycle::Destroyed;
+        }
+        ai.tick = counters.tick;
+        ai.logical_time = counters.handled;
+        ai.complex_generated = counters.complex_generated;
+        ai.explorer_msgs = counters.explorer_messages;
+        ai.explorer_requests = counters.explorer_requests;
+        ai.refusal_reasons = counters.refusal_reasons;
+        ai.asteroids_faced = counters.asteroids_faced;
+        ai.asteroids_survived = counters.asteroids_survived;
+        ai.last_asteroid_tick = counters.last_asteroid_tick;
+        ai.suspected_duplicate_sunrays = counters.suspected_duplicate_sunrays;
+        ai.protocol_anomalies = counters.protocol_anomalies;
+        ai.memory_pressure_events = counters.memory_pressure_events;
+        ai.charge_events = counters.charge_events;
+        ai.sunrays_wasted = counters.sunrays_wasted;
+        ai.generations_succeeded = counters.generations_succeeded;
+        ai.generations_failed = counters.generations_failed;
+        ai.generator_errors = counters.generator_errors;
+        ai.generator_retries_succeeded = counters.generator_retries_succeeded;
+        ai.messages_after_destruction = counters.messages_after_destruction;
+        ai.current_round = counters.current_round;
+        ai.round = RoundStats {
+            round: counters.current_round,
+            ..Default::default()
+        };
+        ai.rounds = VecDeque::from(counters.rounds);
+        Ok(ai)
+    }
+}
+
+/// Maps keyed by a `common_game` type, which doesn't implement serde,
+/// stored by the type's name, and sets of such types in [set](by_name::set).
+pub(crate) mod by_name {
+    use std::collections::HashMap;
+    use std::fmt::Debug;
+    use std::hash::Hash;
+
+    use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+    use common_game::logging::EventType;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::ai::config::EVENT_TYPES;
+    use crate::ai::handlers::explorer::{BASIC_RESOURCES, COMPLEX_RESOURCES};
+
+    pub(crate) trait Named: Copy + Eq + Hash + Debug + 'static {
+        const ALL: &'static [Self];
+    }
+
+    impl Named for BasicResourceType {
+        const ALL: &'static [Self] = &BASIC_RESOURCES;
+    }
+
+    impl Named for ComplexResourceType {
+        const ALL: &'static [Self] = &COMPLEX_RESOURCES;
+    }
+
+    impl Named for EventType {
+        const ALL: &'static [Self] = &EVENT_TYPES;
+    }
+
+    fn from_name<K: Named, E: Error>(name: &str) -> Result<K, E> {
+        K::ALL
+            .iter()
+            .find(|key| format!("{key:?}") == name)
+            .copied()
+            .ok_or_else(|| E::custom(format!("unknown name {name}")))
+    }
+
+    pub(crate) fn serialize<S: Serializer, K: Named, V: Serialize>(
+        map: &HashMap<K, V>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(map.iter().map(|(key, value)| (format!("{key:?}"), value)))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>, K: Named, V: Deserialize<'de>>(
+        deserializer: D,
+    ) -> Result<HashMap<K, V>, D::Error> {
+        HashMap::<String, V>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, value)| Ok((from_name(&name)?, value)))
+            .collect()
+    }
+
+    /// Sets, stored as the sorted list of their names.
+    pub(crate) mod set {
+        use std::collections::HashSet;
+
+        use serde::{Deserialize, Deserializer, Serializer};
+
+        use super::{Named, from_name};
+
+        pub(crate) fn serialize<S: Serializer, K: Named>(
+            set: &HashSet<K>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            let mut names: Vec<String> = set.iter().map(|key| format!("{key:?}")).collect();
+            names.sort();
+            serializer.collect_seq(names)
+        }
+
+        pub(crate) fn deserialize<'de, D: Deserializer<'de>, K: Named>(
+            deserializer: D,
+        ) -> Result<HashSet<K>, D::Error> {
+            Vec::<String>::deserialize(deserializer)?
+                .iter()
+                .map(|name| from_name(name))
+                .collect()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::components::planet::{DummyPlanetState, Planet, PlanetAI, PlanetType};
+    use common_game::components::resource::{BasicResourceType, Combinator, Generator};
+    use common_game::components::rocket::Rocket;
+    use common_game::logging::Payload;
+    use common_game::protocols::orchestrator_planet::OrchestratorToPlanet;
+    use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+    use crossbeam_channel::{Sender, unbounded};
+
+    use super::*;
+    use crate::testkit::{self, TestPlanet, fixtures};
+
+    fn checkpoint_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("orbitron-{name}-{}.json", std::process::id()))
+    }
+
+    /// AI charging a cell with every Sunray and running an action on the
+    /// planet's state at the first `InternalStateRequest`.
+    struct OnStateRequest<F, T> {
+        action: Option<F>,
+        result: Sender<T>,
+    }
+
+    impl<F: FnOnce(&mut PlanetState) -> T + Send, T: Send> PlanetAI for OnStateRequest<F, T> {
+        fn handle_sunray(
+            &mut self,
+            state: &mut PlanetState,
+            _generator: &Generator,
+            _combinator: &Combinator,
+            sunray: Sunray,
+        ) {
+            state.charge_cell(sunray);
+        }
+
+        fn handle_internal_state_req(
+            &mut self,
+            state: &mut PlanetState,
+            _generator: &Generator,
+            _combinator: &Combinator,
+        ) -> DummyPlanetState {
+            if let Some(action) = self.action.take() {
+                let _ = self.result.send(action(state));
+            }
+            state.to_dummy()
+        }
+
+        fn handle_explorer_msg(
+            &mut self,
+            _state: &mut PlanetState,
+            _generator: &Generator,
+            _combinator: &Combinator,
+            _msg: ExplorerToPlanet,
+        ) -> Option<PlanetToExplorer> {
+            None
+        }
+
+        fn handle_asteroid(
+            &mut self,
+            _state: &mut PlanetState,
+            _generator: &Generator,
+            _combinator: &Combinator,
+        ) -> Option<Rocket> {
+            None
+        }
+    }
+
+    /// Runs `action` on the state of a new Type B planet once it has been
+    /// given `sunrays` Sunrays.
+    fn on_planet_state<T: Send + 'static>(
+        sunrays: usize,
+        action: impl FnOnce(&mut PlanetState) -> T + Send + 'static,
+    ) -> T {
+        let (result, results) = unbounded();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            let ai = OnStateRequest {
+                action: Some(action),
+                result,
+            };
+            Planet::new(
+                fixtures::FIXTURE_PLANET_ID,
+                PlanetType::B,
+                Box::new(ai),
+                fixtures::gen_rules().to_vec(),
+                fixtures::comb_rules().to_vec(),
+                (rx_orch, tx_orch),
+                rx_expl,
+            )
+            .unwrap()
+        });
+        planet.start();
+        for _ in 0..sunrays {
+            planet.sunray();
+        }
+        planet.send(OrchestratorToPlanet::InternalStateRequest);
+        planet.recv();
+        results.recv_timeout(testkit::TIMEOUT).unwrap()
+    }
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 0,
+            generation_weights: HashMap::from([(BasicResourceType::Oxygen, 3)]),
+            ..Default::default()
+        };
+        let mut ai = Orbitron::with_config(fixtures::FIXTURE_PLANET_ID, config.clone());
+        ai.start(fixtures::FIXTURE_PLANET_ID);
+        ai.on_tick();
+        ai.on_tick();
+        ai.refuse(RefusalReason::RateLimited, &mut Payload::new());
+        ai.complex_generated.insert(ComplexResourceType::Water, 2);
+        ai.charge_events.insert(0, 2);
+        ai.generations_succeeded = 1;
+        ai.end_round();
+
+        let path = checkpoint_path("round-trip");
+        let saving = path.clone();
+        let ai = on_planet_state(1, move |state| {
+            ai.save_checkpoint(state, &saving).unwrap();
+            ai
+        });
+        let restoring = path.clone();
+        let (loaded, charged) = on_planet_state(0, move |state| {
+            let loaded = Orbitron::load_checkpoint(&restoring, state, testkit::sunray).unwrap();
+            (loaded, state.cells_iter().next().unwrap().is_charged())
+        });
+        let cells = Checkpoint::read(&path).unwrap().cells;
+        std::fs::remove_file(&path).unwrap();
+
+        let (saved, restored) = (
+            ai.snapshot(fixtures::FIXTURE_PLANET_ID),
+            loaded.snapshot(fixtures::FIXTURE_PLANET_ID),
+        );
+        assert_eq!(restored.config, config);
+        assert_eq!(restored.lifecycle, Lifecycle::Created);
+        assert_eq!(restored.tick, 2);
+        assert_eq!(restored.refusal_reasons, saved.refusal_reasons);
+        assert_eq!(restored.complex_generated, saved.complex_generated);
+        assert_eq!(restored.charge_events, saved.charge_events);
+        assert_eq!(restored.generations_succeeded, 1);
+        assert_eq!(restored.round_stats, saved.round_stats);
+        assert_eq!(loaded.current_round, 2);
+        assert_eq!(cells, [true]);
+        assert!(charged);
+    }
+
+    #[test]
+    fn test_checkpoint_discharges_cells_saved_empty() {
+        let path = checkpoint_path("empty-cells");
+        Orbitron::new(fixtures::FIXTURE_PLANET_ID)
+            .save_checkpoint(fixtures::type_b().state(), &path)
+            .unwrap();
+        let restoring = path.clone();
+        let charged = on_planet_state(1, move |state| {
+            Orbitron::load_checkpoint(&restoring, state, testkit::sunray).unwrap();
+            state.cells_iter().next().unwrap().is_charged()
+        });
+        std::fs::remove_file(&path).unwrap();
+        assert!(!charged);
+    }
+
+    #[test]
+    fn test_checkpoint_resumes_rng() {
+        let mut ai = Orbitron::new(fixtures::FIXTURE_PLANET_ID);
+        for _ in 0..5 {
+            ai.rng.next_u64();
+        }
+
+        let path = checkpoint_path("rng");
+        ai.save_checkpoint(fixtures::type_b().state(), &path)
+            .unwrap();
+        let restoring = path.clone();
+        let mut loaded = on_planet_state(0, move |state| {
+            Orbitron::load_checkpoint(&restoring, state, testkit::sunray).unwrap()
+        });
+        std::fs::remove_file(&path).unwrap();
+
+        let saved: Vec<u64> = (0..10).map(|_| ai.rng.next_u64()).collect();
+        let restored: Vec<u64> = (0..10).map(|_| loaded.rng.next_u64()).collect();
+        assert_eq!(restored, saved);
+        let reseeded = Orbitron::new(fixtures::FIXTURE_PLANET_ID).rng.next_u64();
+        assert_ne!(restored[0], reseeded);
+    }
+
+    #[test]
+    fn test_checkpoint_of_other_version_rejected() {
+        let path = checkpoint_path("version");
+        Orbitron::new(1)
+            .save_checkpoint(fixtures::type_b().state(), &path)
+            .unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, text.replace("\"version\": 3", "\"version\": 0")).unwrap();
+
+        let restoring = path.clone();
+        let loaded = on_planet_state(0, move |state| {
+            Orbitron::load_checkpoint(&restoring, state, testkit::sunray)
+        });
+        std::fs::remove_file(&path).unwrap();
+        let Err(err) = loaded else {
+            panic!("checkpoint of version 0 loaded");
+        };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "unsupported checkpoint version 0, expected 3"
+        );
+    }
+}
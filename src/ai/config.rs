@@ -0,0 +1,985 @@
+//! # Orbitron – AI Configuration
+//!
+//! This module holds [OrbitronConfig], the set of tunable options that
+//! shape how the Orbitron AI makes its decisions.
+//!
+//! `OrbitronConfig::default()` always reproduces the stock tournament
+//! behavior, so callers only need to touch the fields they care about.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::time::Duration;
+
+use common_game::components::resource::BasicResourceType;
+use common_game::logging::{Channel, EventType, Payload};
+use common_game::utils::ID;
+
+/// How the AI answers a combination request for a recipe it doesn't have.
+///
+/// Whatever the policy, the inputs are never spent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum UnknownRecipePolicy {
+    /// Hands the inputs back with a `There isn't a recipe for ...` error.
+    #[default]
+    Reject,
+    /// Sends no answer at all. The protocol can only return the inputs
+    /// inside an answer, so the explorer doesn't get them back.
+    Ignore,
+    /// Hands the inputs back with an `UnsupportedRecipe requested=...
+    /// supported=[...]` error, listing the recipes the planet does have.
+    Structured,
+}
+
+impl UnknownRecipePolicy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnknownRecipePolicy::Reject => "reject",
+            UnknownRecipePolicy::Ignore => "ignore",
+            UnknownRecipePolicy::Structured => "structured",
+        }
+    }
+}
+
+/// Which empty cell a Sunray charges.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum ChargeOrder {
+    /// The one `common_game` picks: the first empty cell.
+    #[default]
+    Default,
+    /// The first empty cell after the last one charged, wrapping around,
+    /// so cells spent and recharged in turn share the wear.
+    RoundRobin,
+}
+
+impl ChargeOrder {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ChargeOrder::Default => "default",
+            ChargeOrder::RoundRobin => "round_robin",
+        }
+    }
+}
+
+/// What ends a round of the game, as far as the planet can tell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundBoundary {
+    /// Each `InternalStateRequest` ends the round it arrives in.
+    #[default]
+    OnInternalStateRequest,
+    /// A round lasts this many Sunrays; `0` never ends it.
+    OnEveryNSunrays(u32),
+}
+
+impl fmt::Display for RoundBoundary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RoundBoundary::OnInternalStateRequest => f.write_str("internal_state_request"),
+            RoundBoundary::OnEveryNSunrays(n) => write!(f, "every_{n}_sunrays"),
+        }
+    }
+}
+
+/// How much of a [LogCategory] the AI logs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum Verbosity {
+    /// Nothing.
+    Off,
+    /// Errors and warnings only.
+    Warning,
+    /// Every event, whatever its channel.
+    #[default]
+    Info,
+}
+
+impl Verbosity {
+    /// Whether an event on `channel` is logged.
+    pub fn allows(self, channel: Channel) -> bool {
+        match self {
+            Verbosity::Off => false,
+            Verbosity::Warning => matches!(channel, Channel::Error | Channel::Warning),
+            Verbosity::Info => true,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Verbosity::Off => "off",
+            Verbosity::Warning => "warning",
+            Verbosity::Info => "info",
+        }
+    }
+}
+
+/// Groups of log events sharing a [Verbosity].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogCategory {
+    /// Start, stop, destruction and the startup banner.
+    Lifecycle,
+    /// Sunrays and internal state requests.
+    OrchestratorTraffic,
+    /// Explorer requests and the answers that carry no error.
+    ExplorerTraffic,
+    /// Refusals, failed requests, back-off, evictions and protocol anomalies.
+    Failures,
+    /// Asteroids, the damage they do and the defensive mode they trigger.
+    Asteroid,
+}
+
+impl LogCategory {
+    pub const ALL: [LogCategory; 5] = [
+        LogCategory::Lifecycle,
+        LogCategory::OrchestratorTraffic,
+        LogCategory::ExplorerTraffic,
+        LogCategory::Failures,
+        LogCategory::Asteroid,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogCategory::Lifecycle => "lifecycle",
+            LogCategory::OrchestratorTraffic => "orchestrator_traffic",
+            LogCategory::ExplorerTraffic => "explorer_traffic",
+            LogCategory::Failures => "failures",
+            LogCategory::Asteroid => "asteroid",
+        }
+    }
+}
+
+/// Every [EventType], the default of [OrbitronConfig::enabled_event_types].
+pub const EVENT_TYPES: [EventType; 12] = [
+    EventType::MessagePlanetToOrchestrator,
+    EventType::MessagePlanetToExplorer,
+    EventType::MessageOrchestratorToExplorer,
+    EventType::MessageOrchestratorToPlanet,
+    EventType::MessageExplorerToPlanet,
+    EventType::MessageExplorerToOrchestrator,
+    EventType::InternalPlanetAction,
+    EventType::InternalExplorerAction,
+    EventType::InternalOrchestratorAction,
+    EventType::UserToPlanet,
+    EventType::UserToExplorer,
+    EventType::UserToOrchestrator,
+];
+
+/// [Verbosity] of every [LogCategory]; the default logs everything.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct LogVerbosity {
+    pub lifecycle: Verbosity,
+    pub orchestrator_traffic: Verbosity,
+    pub explorer_traffic: Verbosity,
+    pub failures: Verbosity,
+    pub asteroid: Verbosity,
+}
+
+impl LogVerbosity {
+    pub fn of(&self, category: LogCategory) -> Verbosity {
+        match category {
+            LogCategory::Lifecycle => self.lifecycle,
+            LogCategory::OrchestratorTraffic => self.orchestrator_traffic,
+            LogCategory::ExplorerTraffic => self.explorer_traffic,
+            LogCategory::Failures => self.failures,
+            LogCategory::Asteroid => self.asteroid,
+        }
+    }
+
+    /// Whether an event of `category` on `channel` is logged.
+    pub fn allows(&self, category: LogCategory, channel: Channel) -> bool {
+        self.of(category).allows(channel)
+    }
+
+    /// `category:verbosity` for the categories matching `keep`.
+    fn list(&self, keep: impl Fn(Verbosity) -> bool) -> String {
+        let entries: Vec<String> = LogCategory::ALL
+            .iter()
+            .filter(|category| keep(self.of(**category)))
+            .map(|category| format!("{}:{}", category.as_str(), self.of(*category).as_str()))
+            .collect();
+        entries.join(",")
+    }
+}
+
+/// Default of [OrbitronConfig::post_mortem_history].
+const DEFAULT_POST_MORTEM_HISTORY: usize = 50;
+
+/// Default of [OrbitronConfig::max_tracked_explorers].
+const DEFAULT_MAX_TRACKED_EXPLORERS: usize = 1024;
+
+/// Default of [OrbitronConfig::explorer_silence_threshold].
+const DEFAULT_EXPLORER_SILENCE_THRESHOLD: Option<u64> = Some(100);
+
+/// Default of [OrbitronConfig::explorer_horizon].
+const DEFAULT_EXPLORER_HORIZON: Option<u64> = Some(4096);
+
+/// Default of [OrbitronConfig::round_history].
+const DEFAULT_ROUND_HISTORY: usize = 16;
+
+/// Options controlling the decisions taken by the Orbitron AI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct OrbitronConfig {
+    /// Number of Sunrays the planet must receive after an asteroid before
+    /// it leaves defensive mode.
+    ///
+    /// Defensive mode follows an asteroid the planet survived by launching a
+    /// rocket, or a drop below [defensive_charge_threshold](Self::defensive_charge_threshold).
+    /// A planet without a rocket is destroyed by an asteroid, so on a
+    /// rocketless planet only the threshold triggers it.
+    ///
+    /// While in defensive mode the AI refuses explorer requests that would
+    /// spend an energy cell. A value of `0` disables defensive mode.
+    pub defensive_sunrays: u32,
+
+    /// Charged cells below which the planet enters defensive mode, checked
+    /// after every Sunray and every spent cell. Ignored while the planet has
+    /// a rocket. A value of `0` disables this trigger.
+    pub defensive_charge_threshold: u32,
+
+    /// Explorers allowed to use the planet. `None` allows everyone.
+    pub allowed_explorers: Option<HashSet<ID>>,
+
+    /// Explorers never served, even when listed in `allowed_explorers`.
+    pub denied_explorers: HashSet<ID>,
+
+    /// Whether explorers that are not allowed still get answers to
+    /// read-only requests (supported recipes, available energy cells).
+    pub serve_read_only_to_disallowed: bool,
+
+    /// Consecutive failed combinations after which an explorer is put in
+    /// back-off: further combination requests are refused without reaching
+    /// the combinator. A value of `0` disables back-off.
+    pub combine_backoff_threshold: u32,
+
+    /// Number of messages handled, from any channel, after an explorer's
+    /// last failed combination that clear its back-off.
+    pub combine_backoff_reset: u32,
+
+    /// Maximum number of resource generations per tick; further generate
+    /// requests in the same tick get an empty `GenerateResourceResponse`.
+    /// Only generations that spent a cell count. `usize::MAX` means no cap.
+    ///
+    /// A tick starts with every Sunray the planet receives.
+    pub max_inflight: usize,
+
+    /// Maximum number of complex resources produced per tick; further
+    /// combine requests in the same tick are refused with a `Throttled`
+    /// error before reaching the combinator. `usize::MAX` means no cap.
+    pub max_combines_per_tick: usize,
+
+    /// Whether an explorer asking for Hydrogen while at least two cells are
+    /// charged gets its next Oxygen request past the `max_inflight` cap, so
+    /// the pair needed for Water comes in two back-to-back exchanges.
+    pub auto_pair: bool,
+
+    /// Number of generated resources the planet may hold in escrow.
+    ///
+    /// With escrow enabled a generated resource is kept by the planet and
+    /// handed over on the explorer's next request for the same type, which
+    /// doesn't spend another cell, even in defensive mode. When full, the
+    /// oldest held resource is discarded. A value of `0` disables escrow.
+    ///
+    /// The generating request itself is answered with no resource, like a
+    /// refusal: `GenerateResourceResponse` only carries an `Option`, so the
+    /// protocol has no way to say "held for you". Explorers relying on
+    /// escrow ask again for the same type; the planet logs the hold as
+    /// `Escrow: Held for explorer`.
+    pub escrow_capacity: usize,
+
+    /// Seed of the generator behind every randomized decision of the AI.
+    ///
+    /// `None` derives the seed from the planet id, so two planets don't make
+    /// identical choices while each run of the same planet stays reproducible.
+    pub rng_seed: Option<u64>,
+
+    /// Relative weights used when the planet picks a basic resource on its
+    /// own: in [Orbitron::generate_any](crate::Orbitron::generate_any), and
+    /// for the alternative it suggests, with [Self::suggest_alternatives],
+    /// for a resource it can't generate. Supported resources missing from the
+    /// map weigh `0`; when every weight is `0` the pick is uniform. An empty
+    /// map suggests the closest supported resource instead.
+    #[cfg_attr(feature = "checkpoint", serde(with = "crate::ai::checkpoint::by_name"))]
+    pub generation_weights: HashMap<BasicResourceType, u32>,
+
+    /// Number of queued explorer messages above which every handled message
+    /// logs a backlog warning. A value of `0` disables the warning.
+    pub explorer_backlog_warning: usize,
+
+    /// Number of handled messages kept for inspection; once full the oldest
+    /// entry is overwritten. A value of `0` disables the history.
+    pub event_history: usize,
+
+    /// Number of handled messages kept, in a compact form, to be dumped to
+    /// the log on every asteroid. A value of `0` disables the dump.
+    pub post_mortem_history: usize,
+
+    /// Whether two Sunrays handled with no other message in between are
+    /// flagged as a possible duplicate. The second Sunray still charges a
+    /// cell; it is only logged and counted.
+    pub flag_duplicate_sunrays: bool,
+
+    /// Whether `AvailableEnergyCellResponse` leaves out one charged cell for
+    /// every explorer message still queued on the planet, so explorers don't
+    /// race for a cell an earlier request will spend.
+    ///
+    /// The queue can't be inspected without taking messages off it, so every
+    /// queued message counts, read-only ones included: while explorers are
+    /// only asking questions the planet may report fewer cells than it could
+    /// actually spend.
+    pub reserve_cells_for_queued: bool,
+
+    /// Whether a request for a basic resource the planet can't generate
+    /// records the closest supported one as a suggestion for the explorer.
+    /// The request itself is still answered with no resource.
+    pub suggest_alternatives: bool,
+
+    /// Whether a generation the generator fails is tried once more with the
+    /// next charged cell, for generators failing transiently.
+    pub retry_on_generator_error: bool,
+
+    /// Number of ticks a charged cell may stay unused; an older charge leaks
+    /// away at the next Sunray. `None` keeps charges forever.
+    pub cell_decay_ticks: Option<u32>,
+
+    /// Number of charged cells an asteroid discharges even when a rocket
+    /// deflects it. A value of `0` leaves the cells untouched.
+    pub asteroid_damage: usize,
+
+    /// Number of explorers the AI keeps per-explorer state for (back-off,
+    /// fast track, suggestions, premature contact warnings). A message from
+    /// one more explorer evicts the least recently seen one, which loses
+    /// that state, and logs a warning. `usize::MAX` means no cap.
+    ///
+    /// Other collections are bounded on their own: escrow by
+    /// `escrow_capacity`, the history by `event_history`, and the rest by
+    /// the number of cells or resource types.
+    pub max_tracked_explorers: usize,
+
+    /// Number of handled messages an explorer id may stay silent before a
+    /// change in its traffic flags it as possibly reused by another
+    /// explorer: an id that only sent read-only requests and comes back
+    /// with a generation or combination request is logged once and marked
+    /// suspicious in the `ExplorerRegistry`. `None` never flags an id.
+    pub explorer_silence_threshold: Option<u64>,
+
+    /// Number of handled messages after which the state kept for a silent
+    /// explorer is dropped, checked every 256 messages. `None` keeps it
+    /// until the explorer is evicted by `max_tracked_explorers`.
+    pub explorer_horizon: Option<u64>,
+
+    /// Whether answers to explorers registered in the AI's `ExplorerSenders`
+    /// are held and sent together at the next Sunray, in the order they were
+    /// produced. A stop, an asteroid or dropping the AI sends them right away
+    /// too, so no answer waits longer than one tick. Other explorers are
+    /// answered immediately.
+    ///
+    /// The planet loop keeps the explorers' senders to itself, so only
+    /// explorers registered by hand with `ExplorerSenders::register` are
+    /// batched. A planet from `create_planet_with_config` with this set and
+    /// nothing registered answers every explorer immediately.
+    pub batch_responses: bool,
+
+    /// How combination requests for a recipe the planet doesn't have are
+    /// answered.
+    pub unknown_recipe_policy: UnknownRecipePolicy,
+
+    /// Whether Hydrogen and Oxygen are kept balanced for Water.
+    ///
+    /// The AI tracks how many of each it handed out and didn't get back
+    /// through a Water combination; a request for the element ahead of the
+    /// other by more than `water_imbalance_threshold` is refused, and the
+    /// other element is suggested instead.
+    pub balance_for_water: bool,
+
+    /// Lead one of Hydrogen and Oxygen may take over the other before
+    /// `balance_for_water` refuses it.
+    pub water_imbalance_threshold: u64,
+
+    /// How long the run loop may go without handling a message while
+    /// explorer messages wait before the watchdog logs an error. `None`
+    /// disables the watchdog. The explorer queue is only visible once
+    /// watched, see `Orbitron::watch_explorer_queue`.
+    pub watchdog_timeout: Option<Duration>,
+
+    /// How much of each [LogCategory] is logged. Events filtered out are
+    /// never built on the busiest paths.
+    pub log_verbosity: LogVerbosity,
+
+    /// Types of the log events emitted, the others are dropped. An event
+    /// must also pass `log_verbosity` to be logged. The default holds every
+    /// [EventType].
+    #[cfg_attr(
+        feature = "checkpoint",
+        serde(with = "crate::ai::checkpoint::by_name::set")
+    )]
+    pub enabled_event_types: HashSet<EventType>,
+
+    /// Which empty cell each Sunray charges.
+    pub charge_order: ChargeOrder,
+
+    /// Whether error texts sent to explorers start with the error's stable
+    /// code, e.g. `E1: No charged energy cell found`. Off by default, so
+    /// explorers matching on the plain texts keep working.
+    pub error_codes: bool,
+
+    /// Charged cells below which the planet warns, at the start of a tick,
+    /// that it is low on energy. The protocol has no message asking the
+    /// orchestrator for Sunrays, so the warning is all it does. A value of
+    /// `0` disables it.
+    pub low_energy_threshold: u32,
+
+    /// What ends a round, see [Orbitron::round_stats](crate::Orbitron::round_stats).
+    /// Every log event is tagged with the current round.
+    pub round_boundary: RoundBoundary,
+
+    /// Number of ended rounds whose statistics are kept, oldest dropped
+    /// first.
+    pub round_history: usize,
+
+    /// Whether generation and combination requests are only decided, not
+    /// served: no cell is spent, the answer is always empty and the decision
+    /// is recorded instead. For replaying explorer traffic while balancing;
+    /// only exists with the `dry-run` feature.
+    #[cfg(feature = "dry-run")]
+    pub dry_run: bool,
+}
+
+impl OrbitronConfig {
+    /// Default options, logging only lifecycle changes, failures and
+    /// asteroids: message traffic is off.
+    pub fn quiet() -> Self {
+        Self {
+            log_verbosity: LogVerbosity {
+                orchestrator_traffic: Verbosity::Off,
+                explorer_traffic: Verbosity::Off,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Default options, logging every event. This is also what
+    /// `default()` does; the preset states it explicitly.
+    pub fn verbose() -> Self {
+        Self {
+            log_verbosity: LogVerbosity {
+                lifecycle: Verbosity::Info,
+                orchestrator_traffic: Verbosity::Info,
+                explorer_traffic: Verbosity::Info,
+                failures: Verbosity::Info,
+                asteroid: Verbosity::Info,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Seed actually used by the AI of planet `planet_id`.
+    pub fn effective_seed(&self, planet_id: ID) -> u64 {
+        self.rng_seed.unwrap_or(u64::from(planet_id))
+    }
+
+    /// Whether requests from `explorer_id` may be served.
+    pub fn is_explorer_allowed(&self, explorer_id: ID) -> bool {
+        !self.denied_explorers.contains(&explorer_id)
+            && self
+                .allowed_explorers
+                .as_ref()
+                .is_none_or(|allowed| allowed.contains(&explorer_id))
+    }
+}
+
+/// `key:weight` for every weighted resource, in canonical order.
+fn weight_list(weights: &HashMap<BasicResourceType, u32>) -> String {
+    let weights: Vec<String> = [
+        BasicResourceType::Hydrogen,
+        BasicResourceType::Oxygen,
+        BasicResourceType::Carbon,
+        BasicResourceType::Silicon,
+    ]
+    .iter()
+    .filter_map(|resource| {
+        weights
+            .get(resource)
+            .map(|weight| format!("{resource:?}:{weight}"))
+    })
+    .collect();
+    weights.join(",")
+}
+
+/// `usize::MAX` as `unlimited`, any other cap as is.
+fn cap(value: usize) -> String {
+    if value == usize::MAX {
+        "unlimited".into()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Every option of `config` as its own payload entry, named after the field,
+/// plus the planet id and the seed actually used.
+///
+/// The AI logs it the first time it is started; a new field doesn't build
+/// until it is listed here.
+pub fn config_payload(config: &OrbitronConfig, planet_id: ID) -> Payload {
+    let OrbitronConfig {
+        defensive_sunrays,
+        defensive_charge_threshold,
+        allowed_explorers,
+        denied_explorers,
+        serve_read_only_to_disallowed,
+        combine_backoff_threshold,
+        combine_backoff_reset,
+        max_inflight,
+        max_combines_per_tick,
+        auto_pair,
+        escrow_capacity,
+        rng_seed,
+        generation_weights,
+        explorer_backlog_warning,
+        event_history,
+        post_mortem_history,
+        flag_duplicate_sunrays,
+        reserve_cells_for_queued,
+        suggest_alternatives,
+        retry_on_generator_error,
+        cell_decay_ticks,
+        asteroid_damage,
+        max_tracked_explorers,
+        explorer_silence_threshold,
+        explorer_horizon,
+        batch_responses,
+        unknown_recipe_policy,
+        balance_for_water,
+        water_imbalance_threshold,
+        watchdog_timeout,
+        log_verbosity,
+        enabled_event_types,
+        charge_order,
+        error_codes,
+        low_energy_threshold,
+        round_boundary,
+        round_history,
+        #[cfg(feature = "dry-run")]
+        dry_run,
+    } = config;
+
+    let entries = [
+        ("planet_id", planet_id.to_string()),
+        ("defensive_sunrays", defensive_sunrays.to_string()),
+        (
+            "defensive_charge_threshold",
+            defensive_charge_threshold.to_string(),
+        ),
+        (
+            "allowed_explorers",
+            allowed_explorers.as_ref().map_or("all".into(), |allowed| {
+                format!("[{}]", explorer_list(allowed))
+            }),
+        ),
+        (
+            "denied_explorers",
+            format!("[{}]", explorer_list(denied_explorers)),
+        ),
+        (
+            "serve_read_only_to_disallowed",
+            serve_read_only_to_disallowed.to_string(),
+        ),
+        (
+            "combine_backoff_threshold",
+            combine_backoff_threshold.to_string(),
+        ),
+        ("combine_backoff_reset", combine_backoff_reset.to_string()),
+        ("max_inflight", cap(*max_inflight)),
+        ("max_combines_per_tick", cap(*max_combines_per_tick)),
+        ("auto_pair", auto_pair.to_string()),
+        ("escrow_capacity", escrow_capacity.to_string()),
+        (
+            "rng_seed",
+            rng_seed.map_or("auto".into(), |seed| seed.to_string()),
+        ),
+        (
+            "effective_seed",
+            config.effective_seed(planet_id).to_string(),
+        ),
+        (
+            "generation_weights",
+            format!("[{}]", weight_list(generation_weights)),
+        ),
+        (
+            "explorer_backlog_warning",
+            explorer_backlog_warning.to_string(),
+        ),
+        ("event_history", event_history.to_string()),
+        ("post_mortem_history", post_mortem_history.to_string()),
+        ("flag_duplicate_sunrays", flag_duplicate_sunrays.to_string()),
+        (
+            "reserve_cells_for_queued",
+            reserve_cells_for_queued.to_string(),
+        ),
+        ("suggest_alternatives", suggest_alternatives.to_string()),
+        (
+            "retry_on_generator_error",
+            retry_on_generator_error.to_string(),
+        ),
+        (
+            "cell_decay_ticks",
+            cell_decay_ticks.map_or("never".into(), |ticks| ticks.to_string()),
+        ),
+        ("asteroid_damage", asteroid_damage.to_string()),
+        ("max_tracked_explorers", cap(*max_tracked_explorers)),
+        (
+            "explorer_silence_threshold",
+            explorer_silence_threshold.map_or("never".into(), |silence| silence.to_string()),
+        ),
+        (
+            "explorer_horizon",
+            explorer_horizon.map_or("never".into(), |horizon| horizon.to_string()),
+        ),
+        ("batch_responses", batch_responses.to_string()),
+        (
+            "unknown_recipe_policy",
+            unknown_recipe_policy.as_str().into(),
+        ),
+        ("balance_for_water", balance_for_water.to_string()),
+        (
+            "water_imbalance_threshold",
+            water_imbalance_threshold.to_string(),
+        ),
+        (
+            "watchdog_timeout",
+            watchdog_timeout.map_or("never".into(), |timeout| {
+                format!("{}ms", timeout.as_millis())
+            }),
+        ),
+        ("log_verbosity", log_verbosity.list(|_| true)),
+        ("enabled_event_types", event_type_list(enabled_event_types)),
+        ("charge_order", charge_order.as_str().into()),
+        ("error_codes", error_codes.to_string()),
+        ("low_energy_threshold", low_energy_threshold.to_string()),
+        ("round_boundary", round_boundary.to_string()),
+        ("round_history", round_history.to_string()),
+        #[cfg(feature = "dry-run")]
+        ("dry_run", dry_run.to_string()),
+    ];
+    entries
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value))
+        .collect()
+}
+
+/// Sorted, comma separated explorer ids.
+fn explorer_list(explorers: &HashSet<ID>) -> String {
+    let mut ids: Vec<&ID> = explorers.iter().collect();
+    ids.sort();
+    let ids: Vec<String> = ids.iter().map(|id| id.to_string()).collect();
+    ids.join(",")
+}
+
+/// `all`, or the sorted names of `event_types`.
+fn event_type_list(event_types: &HashSet<EventType>) -> String {
+    if EVENT_TYPES
+        .iter()
+        .all(|event_type| event_types.contains(event_type))
+    {
+        return "all".into();
+    }
+    let mut names: Vec<&EventType> = event_types.iter().collect();
+    names.sort();
+    let names: Vec<String> = names.iter().map(|name| format!("{name:?}")).collect();
+    format!("[{}]", names.join(","))
+}
+
+/// One line of `key=value` pairs, e.g.
+/// `defensive_sunrays=2 backoff=3/10 max_inflight=unlimited escrow=0 ...`.
+impl fmt::Display for OrbitronConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "defensive_sunrays={} backoff={}/{}",
+            self.defensive_sunrays, self.combine_backoff_threshold, self.combine_backoff_reset
+        )?;
+        if self.defensive_charge_threshold > 0 {
+            write!(f, " defensive_below={}", self.defensive_charge_threshold)?;
+        }
+        write!(f, " max_inflight={}", cap(self.max_inflight))?;
+        if self.max_combines_per_tick != usize::MAX {
+            write!(f, " max_combines={}", self.max_combines_per_tick)?;
+        }
+        if self.auto_pair {
+            f.write_str(" auto_pair")?;
+        }
+        write!(f, " escrow={}", self.escrow_capacity)?;
+        match self.rng_seed {
+            None => f.write_str(" seed=auto")?,
+            Some(seed) => write!(f, " seed={seed}")?,
+        }
+        write!(f, " history={}", self.event_history)?;
+        if self.post_mortem_history != DEFAULT_POST_MORTEM_HISTORY {
+            write!(f, " post_mortem={}", self.post_mortem_history)?;
+        }
+
+        match &self.allowed_explorers {
+            None => f.write_str(" allowed=all")?,
+            Some(allowed) => write!(f, " allowed=[{}]", explorer_list(allowed))?,
+        }
+        if !self.denied_explorers.is_empty() {
+            write!(f, " denied=[{}]", explorer_list(&self.denied_explorers))?;
+        }
+        if self.serve_read_only_to_disallowed {
+            f.write_str(" read_only_for_all")?;
+        }
+
+        if !self.generation_weights.is_empty() {
+            write!(f, " weights=[{}]", weight_list(&self.generation_weights))?;
+        }
+        if self.explorer_backlog_warning > 0 {
+            write!(f, " backlog_warning={}", self.explorer_backlog_warning)?;
+        }
+        if self.flag_duplicate_sunrays {
+            f.write_str(" flag_duplicate_sunrays")?;
+        }
+        if self.reserve_cells_for_queued {
+            f.write_str(" reserve_cells_for_queued")?;
+        }
+        if self.suggest_alternatives {
+            f.write_str(" suggest_alternatives")?;
+        }
+        if self.retry_on_generator_error {
+            f.write_str(" retry_on_generator_error")?;
+        }
+        if let Some(ticks) = self.cell_decay_ticks {
+            write!(f, " cell_decay={ticks}")?;
+        }
+        if self.asteroid_damage > 0 {
+            write!(f, " asteroid_damage={}", self.asteroid_damage)?;
+        }
+        if self.max_tracked_explorers != DEFAULT_MAX_TRACKED_EXPLORERS {
+            write!(f, " max_explorers={}", cap(self.max_tracked_explorers))?;
+        }
+        if self.explorer_silence_threshold != DEFAULT_EXPLORER_SILENCE_THRESHOLD {
+            match self.explorer_silence_threshold {
+                None => f.write_str(" silence=never")?,
+                Some(silence) => write!(f, " silence={silence}")?,
+            }
+        }
+        if self.explorer_horizon != DEFAULT_EXPLORER_HORIZON {
+            match self.explorer_horizon {
+                None => f.write_str(" horizon=never")?,
+                Some(horizon) => write!(f, " horizon={horizon}")?,
+            }
+        }
+        if self.batch_responses {
+            f.write_str(" batch_responses")?;
+        }
+        if self.unknown_recipe_policy != UnknownRecipePolicy::Reject {
+            write!(f, " unknown_recipe={}", self.unknown_recipe_policy.as_str())?;
+        }
+        if self.balance_for_water {
+            write!(f, " water_balance={}", self.water_imbalance_threshold)?;
+        }
+        if let Some(timeout) = self.watchdog_timeout {
+            write!(f, " watchdog={}ms", timeout.as_millis())?;
+        }
+        if self.log_verbosity != LogVerbosity::default() {
+            let lowered = self
+                .log_verbosity
+                .list(|verbosity| verbosity != Verbosity::Info);
+            write!(f, " log=[{lowered}]")?;
+        }
+        if !EVENT_TYPES
+            .iter()
+            .all(|event_type| self.enabled_event_types.contains(event_type))
+        {
+            write!(f, " events={}", event_type_list(&self.enabled_event_types))?;
+        }
+        if self.charge_order != ChargeOrder::Default {
+            write!(f, " charge_order={}", self.charge_order.as_str())?;
+        }
+        if self.error_codes {
+            f.write_str(" error_codes")?;
+        }
+        if self.low_energy_threshold > 0 {
+            write!(f, " low_energy={}", self.low_energy_threshold)?;
+        }
+        if self.round_boundary != RoundBoundary::default() {
+            write!(f, " rounds={}", self.round_boundary)?;
+        }
+        if self.round_history != DEFAULT_ROUND_HISTORY {
+            write!(f, " round_history={}", self.round_history)?;
+        }
+        #[cfg(feature = "dry-run")]
+        if self.dry_run {
+            f.write_str(" dry_run")?;
+        }
+        Ok(())
+    }
+}
+
+impl Default for OrbitronConfig {
+    fn default() -> Self {
+        Self {
+            defensive_sunrays: 2,
+            defensive_charge_threshold: 0,
+            allowed_explorers: None,
+            denied_explorers: HashSet::new(),
+            serve_read_only_to_disallowed: false,
+            combine_backoff_threshold: 3,
+            combine_backoff_reset: 10,
+            max_inflight: usize::MAX,
+            max_combines_per_tick: usize::MAX,
+            auto_pair: false,
+            escrow_capacity: 0,
+            rng_seed: None,
+            generation_weights: HashMap::new(),
+            explorer_backlog_warning: 0,
+            event_history: 64,
+            post_mortem_history: DEFAULT_POST_MORTEM_HISTORY,
+            flag_duplicate_sunrays: false,
+            reserve_cells_for_queued: false,
+            suggest_alternatives: false,
+            retry_on_generator_error: false,
+            cell_decay_ticks: None,
+            asteroid_damage: 0,
+            max_tracked_explorers: DEFAULT_MAX_TRACKED_EXPLORERS,
+            explorer_silence_threshold: DEFAULT_EXPLORER_SILENCE_THRESHOLD,
+            explorer_horizon: DEFAULT_EXPLORER_HORIZON,
+            batch_responses: false,
+            unknown_recipe_policy: UnknownRecipePolicy::Reject,
+            balance_for_water: false,
+            water_imbalance_threshold: 2,
+            watchdog_timeout: None,
+            log_verbosity: LogVerbosity::default(),
+            enabled_event_types: HashSet::from(EVENT_TYPES),
+            charge_order: ChargeOrder::Default,
+            error_codes: false,
+            low_energy_threshold: 0,
+            round_boundary: RoundBoundary::default(),
+            round_history: DEFAULT_ROUND_HISTORY,
+            #[cfg(feature = "dry-run")]
+            dry_run: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_display() {
+        assert_eq!(
+            OrbitronConfig::default().to_string(),
+            "defensive_sunrays=2 backoff=3/10 max_inflight=unlimited escrow=0 seed=auto \
+             history=64 allowed=all"
+        );
+    }
+
+    #[test]
+    fn test_custom_config_display() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 0,
+            allowed_explorers: Some(HashSet::new()),
+            denied_explorers: HashSet::from([9, 3]),
+            serve_read_only_to_disallowed: true,
+            max_inflight: 4,
+            generation_weights: HashMap::from([
+                (BasicResourceType::Oxygen, 9),
+                (BasicResourceType::Hydrogen, 1),
+            ]),
+            explorer_backlog_warning: 10,
+            max_tracked_explorers: 64,
+            watchdog_timeout: Some(Duration::from_millis(250)),
+            ..Default::default()
+        };
+        assert_eq!(
+            config.to_string(),
+            "defensive_sunrays=0 backoff=3/10 max_inflight=4 escrow=0 seed=auto history=64 \
+             allowed=[] denied=[3,9] read_only_for_all weights=[Hydrogen:1,Oxygen:9] \
+             backlog_warning=10 max_explorers=64 watchdog=250ms"
+        );
+    }
+
+    #[test]
+    fn test_config_payload_lists_every_field() {
+        // Update this list along with OrbitronConfig and config_payload.
+        let mut expected = vec![
+            "planet_id",
+            "defensive_sunrays",
+            "defensive_charge_threshold",
+            "allowed_explorers",
+            "denied_explorers",
+            "serve_read_only_to_disallowed",
+            "combine_backoff_threshold",
+            "combine_backoff_reset",
+            "max_inflight",
+            "max_combines_per_tick",
+            "auto_pair",
+            "escrow_capacity",
+            "rng_seed",
+            "effective_seed",
+            "generation_weights",
+            "explorer_backlog_warning",
+            "event_history",
+            "post_mortem_history",
+            "flag_duplicate_sunrays",
+            "reserve_cells_for_queued",
+            "suggest_alternatives",
+            "retry_on_generator_error",
+            "cell_decay_ticks",
+            "asteroid_damage",
+            "max_tracked_explorers",
+            "explorer_silence_threshold",
+            "explorer_horizon",
+            "batch_responses",
+            "unknown_recipe_policy",
+            "balance_for_water",
+            "water_imbalance_threshold",
+            "watchdog_timeout",
+            "log_verbosity",
+            "enabled_event_types",
+            "charge_order",
+            "error_codes",
+            "low_energy_threshold",
+            "round_boundary",
+            "round_history",
+        ];
+        #[cfg(feature = "dry-run")]
+        expected.push("dry_run");
+        expected.sort();
+
+        let payload = config_payload(&OrbitronConfig::default(), 7);
+        let keys: Vec<&str> = payload.keys().map(String::as_str).collect();
+        assert_eq!(keys, expected);
+        assert_eq!(payload["rng_seed"], "auto");
+        assert_eq!(payload["effective_seed"], "7");
+        assert_eq!(payload["max_inflight"], "unlimited");
+        assert_eq!(
+            payload["log_verbosity"],
+            "lifecycle:info,orchestrator_traffic:info,explorer_traffic:info,failures:info,\
+             asteroid:info"
+        );
+    }
+
+    #[test]
+    fn test_verbosity_presets() {
+        assert_eq!(OrbitronConfig::verbose(), OrbitronConfig::default());
+        let quiet = OrbitronConfig::quiet();
+        assert_eq!(
+            quiet.to_string(),
+            "defensive_sunrays=2 backoff=3/10 max_inflight=unlimited escrow=0 seed=auto \
+             history=64 allowed=all log=[orchestrator_traffic:off,explorer_traffic:off]"
+        );
+        assert!(
+            !quiet
+                .log_verbosity
+                .allows(LogCategory::ExplorerTraffic, Channel::Error)
+        );
+        assert!(
+            quiet
+                .log_verbosity
+                .allows(LogCategory::Failures, Channel::Debug)
+        );
+    }
+}
@@ -0,0 +1,287 @@
+//! # Orbitron – Errors
+//!
+//! [OrbitronError] lists every reason the Orbitron AI may turn down or fail
+//! an explorer request. The planet/explorer protocol only carries strings,
+//! so errors are converted with [ToString] right before being sent; the
+//! [Display](fmt::Display) text is exactly what explorers receive.
+//!
+//! Each error also has a numeric [code](OrbitronError::code), stable across
+//! versions, for explorers that would rather not match on text. With
+//! [OrbitronConfig::error_codes](super::config::OrbitronConfig::error_codes)
+//! the text explorers receive starts with it, e.g.
+//! `E1: No charged energy cell found`, and [error_code] reads it back.
+use std::fmt;
+
+use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+
+/// Text explorers receive when the planet has no energy cell at all.
+pub(crate) const NO_CELLS_ERROR: &str = "Planet has no energy cells";
+
+/// Why the Orbitron AI turned down or failed an explorer request.
+///
+/// There is no error for wrong ingredients: each
+/// [ComplexResourceRequest](common_game::components::resource::ComplexResourceRequest)
+/// variant carries the exact input types of its recipe, so an explorer can't
+/// send e.g. two Oxygen for Water. Anything the combinator still rejects is a
+/// [GeneratorFailure](OrbitronError::GeneratorFailure).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrbitronError {
+    /// No energy cell is charged.
+    NoChargedCell,
+    /// The planet has no energy cell at all.
+    NoCells,
+    /// The planet has no recipe for the requested complex resource.
+    UnsupportedCombination(ComplexResourceType),
+    /// Same as `UnsupportedCombination`, in a form meant to be parsed, see
+    /// [UnknownRecipePolicy::Structured](super::config::UnknownRecipePolicy::Structured).
+    UnsupportedRecipe {
+        requested: ComplexResourceType,
+        supported: Vec<ComplexResourceType>,
+    },
+    /// The combinator refused the inputs.
+    GeneratorFailure(String),
+    /// Cells are kept for the planet's own survival.
+    Defensive,
+    /// The AI is not running.
+    Stopped,
+    /// The explorer failed too many combinations in a row.
+    BackedOff,
+    /// The per-tick combination limit was reached.
+    RateLimited,
+    /// The explorer is not allowed on this planet.
+    NotAllowed,
+    /// Generation and combination are paused.
+    Paused,
+    /// The planet's strategy declined the request.
+    Declined,
+    /// The planet was destroyed by an asteroid.
+    Destroyed,
+    /// The planet has no recipe for the requested basic resource.
+    UnsupportedResource(BasicResourceType),
+    /// Serving would widen the gap with the other element of Water, see
+    /// [OrbitronConfig::balance_for_water](super::config::OrbitronConfig::balance_for_water).
+    /// Carries the element that is behind.
+    ProductionImbalance(BasicResourceType),
+}
+
+impl OrbitronError {
+    /// Stable code of the error. Codes are never reused or renumbered; new
+    /// errors get the next free one.
+    ///
+    /// | Code | Error |
+    /// |------|-------|
+    /// | 1 | `NoChargedCell` |
+    /// | 2 | `UnsupportedCombination`, `UnsupportedRecipe`, `UnsupportedResource` |
+    /// | 3 | `GeneratorFailure` (bad inputs) |
+    /// | 4 | `NoCells` |
+    /// | 5 | `Defensive` |
+    /// | 6 | `Stopped` |
+    /// | 7 | `BackedOff` |
+    /// | 8 | `RateLimited` |
+    /// | 9 | `NotAllowed` |
+    /// | 10 | `Paused` |
+    /// | 11 | `Declined` |
+    /// | 12 | `Destroyed` |
+    /// | 13 | `ProductionImbalance` |
+    pub const fn code(&self) -> u16 {
+        match self {
+            OrbitronError::NoChargedCell => 1,
+            OrbitronError::UnsupportedCombination(_)
+            | OrbitronError::UnsupportedRecipe { .. }
+            | OrbitronError::UnsupportedResource(_) => 2,
+            OrbitronError::GeneratorFailure(_) => 3,
+            OrbitronError::NoCells => 4,
+            OrbitronError::Defensive => 5,
+            OrbitronError::Stopped => 6,
+            OrbitronError::BackedOff => 7,
+            OrbitronError::RateLimited => 8,
+            OrbitronError::NotAllowed => 9,
+            OrbitronError::Paused => 10,
+            OrbitronError::Declined => 11,
+            OrbitronError::Destroyed => 12,
+            OrbitronError::ProductionImbalance(_) => 13,
+        }
+    }
+
+    /// Text sent to explorers, prefixed with the [code](OrbitronError::code)
+    /// when `with_code` is set.
+    pub(crate) fn protocol_text(&self, with_code: bool) -> String {
+        if with_code {
+            format!("E{}: {self}", self.code())
+        } else {
+            self.to_string()
+        }
+    }
+
+    /// Variant name, used in counters and log payloads.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            OrbitronError::NoChargedCell => "NoChargedCell",
+            OrbitronError::NoCells => "NoCells",
+            OrbitronError::UnsupportedCombination(_) => "UnsupportedCombination",
+            OrbitronError::UnsupportedRecipe { .. } => "UnsupportedRecipe",
+            OrbitronError::GeneratorFailure(_) => "GeneratorFailure",
+            OrbitronError::Defensive => "Defensive",
+            OrbitronError::Stopped => "Stopped",
+            OrbitronError::BackedOff => "BackedOff",
+            OrbitronError::RateLimited => "RateLimited",
+            OrbitronError::NotAllowed => "NotAllowed",
+            OrbitronError::Paused => "Paused",
+            OrbitronError::Declined => "Declined",
+            OrbitronError::Destroyed => "Destroyed",
+            OrbitronError::UnsupportedResource(_) => "UnsupportedResource",
+            OrbitronError::ProductionImbalance(_) => "ProductionImbalance",
+        }
+    }
+}
+
+impl fmt::Display for OrbitronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OrbitronError::NoChargedCell => f.write_str("No charged energy cell found"),
+            OrbitronError::NoCells => f.write_str(NO_CELLS_ERROR),
+            OrbitronError::UnsupportedCombination(recipe) => {
+                write!(f, "There isn't a recipe for {recipe:?}")
+            }
+            OrbitronError::UnsupportedRecipe {
+                requested,
+                supported,
+            } => {
+                let supported: Vec<String> = supported
+                    .iter()
+                    .map(|recipe| format!("{recipe:?}"))
+                    .collect();
+                write!(
+                    f,
+                    "UnsupportedRecipe requested={requested:?} supported=[{}]",
+                    supported.join(",")
+                )
+            }
+            OrbitronError::GeneratorFailure(reason) => f.write_str(reason),
+            OrbitronError::Defensive => f.write_str("Planet is in defensive mode"),
+            OrbitronError::Stopped => f.write_str("Planet AI is stopped"),
+            OrbitronError::BackedOff => {
+                f.write_str("Too many failed combination attempts, back off")
+            }
+            OrbitronError::RateLimited => {
+                f.write_str("Throttled: combination limit reached for this tick")
+            }
+            OrbitronError::NotAllowed => f.write_str("Explorer not allowed"),
+            OrbitronError::Paused => f.write_str("Resource generation is paused"),
+            OrbitronError::Declined => f.write_str("Request declined by the planet"),
+            OrbitronError::Destroyed => f.write_str("Planet has been destroyed"),
+            OrbitronError::UnsupportedResource(resource) => {
+                write!(f, "There isn't a recipe for {resource:?}")
+            }
+            OrbitronError::ProductionImbalance(behind) => {
+                write!(f, "Production imbalance, {behind:?} is behind")
+            }
+        }
+    }
+}
+
+impl std::error::Error for OrbitronError {}
+
+/// Code at the start of an error text sent with
+/// [OrbitronConfig::error_codes](super::config::OrbitronConfig::error_codes),
+/// `None` for a text without one.
+pub fn error_code(text: &str) -> Option<u16> {
+    let (code, _) = text.strip_prefix('E')?.split_once(": ")?;
+    code.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_matches_protocol_strings() {
+        let cases = [
+            (OrbitronError::NoChargedCell, "No charged energy cell found"),
+            (OrbitronError::NoCells, "Planet has no energy cells"),
+            (
+                OrbitronError::UnsupportedCombination(ComplexResourceType::Diamond),
+                "There isn't a recipe for Diamond",
+            ),
+            (
+                OrbitronError::UnsupportedRecipe {
+                    requested: ComplexResourceType::Diamond,
+                    supported: vec![ComplexResourceType::Water, ComplexResourceType::Robot],
+                },
+                "UnsupportedRecipe requested=Diamond supported=[Water,Robot]",
+            ),
+            (
+                OrbitronError::GeneratorFailure("cell is empty".into()),
+                "cell is empty",
+            ),
+            (OrbitronError::Defensive, "Planet is in defensive mode"),
+            (OrbitronError::Stopped, "Planet AI is stopped"),
+            (
+                OrbitronError::BackedOff,
+                "Too many failed combination attempts, back off",
+            ),
+            (
+                OrbitronError::RateLimited,
+                "Throttled: combination limit reached for this tick",
+            ),
+            (OrbitronError::NotAllowed, "Explorer not allowed"),
+            (OrbitronError::Paused, "Resource generation is paused"),
+            (OrbitronError::Declined, "Request declined by the planet"),
+            (OrbitronError::Destroyed, "Planet has been destroyed"),
+            (
+                OrbitronError::UnsupportedResource(BasicResourceType::Carbon),
+                "There isn't a recipe for Carbon",
+            ),
+            (
+                OrbitronError::ProductionImbalance(BasicResourceType::Oxygen),
+                "Production imbalance, Oxygen is behind",
+            ),
+        ];
+        for (err, text) in cases {
+            assert_eq!(err.to_string(), text, "{}", err.kind());
+        }
+    }
+
+    #[test]
+    fn test_error_codes_round_trip() {
+        let cases = [
+            (OrbitronError::NoChargedCell, 1),
+            (
+                OrbitronError::UnsupportedCombination(ComplexResourceType::Life),
+                2,
+            ),
+            (
+                OrbitronError::UnsupportedRecipe {
+                    requested: ComplexResourceType::Life,
+                    supported: vec![],
+                },
+                2,
+            ),
+            (OrbitronError::GeneratorFailure("wrong inputs".into()), 3),
+            (OrbitronError::NoCells, 4),
+            (OrbitronError::Defensive, 5),
+            (OrbitronError::Stopped, 6),
+            (OrbitronError::BackedOff, 7),
+            (OrbitronError::RateLimited, 8),
+            (OrbitronError::NotAllowed, 9),
+            (OrbitronError::Paused, 10),
+            (OrbitronError::Declined, 11),
+            (OrbitronError::Destroyed, 12),
+            (
+                OrbitronError::UnsupportedResource(BasicResourceType::Carbon),
+                2,
+            ),
+            (
+                OrbitronError::ProductionImbalance(BasicResourceType::Hydrogen),
+                13,
+            ),
+        ];
+        for (err, code) in cases {
+            assert_eq!(err.code(), code, "{}", err.kind());
+            let text = err.protocol_text(true);
+            assert_eq!(text, format!("E{code}: {err}"));
+            assert_eq!(error_code(&text), Some(code));
+            assert_eq!(error_code(&err.protocol_text(false)), None);
+        }
+    }
+}
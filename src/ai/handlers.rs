@@ -0,0 +1,5 @@
+pub(crate) mod asteroid;
+#[cfg(feature = "dry-run")]
+pub(crate) mod dry_run;
+pub(crate) mod explorer;
+pub(crate) mod orchestrator;
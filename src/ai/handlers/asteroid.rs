@@ -0,0 +1,322 @@
+//! # Orbitron – Asteroids
+//!
+//! Survival verdicts, rocket building, damage and the defensive mode that
+//! follows an asteroid or a drop in charge.
+use common_game::components::planet::PlanetState;
+use common_game::components::resource::{Combinator, Generator};
+use common_game::components::rocket::Rocket;
+use common_game::logging::*;
+use common_game::utils::ID;
+
+use crate::ai::config::LogCategory;
+use crate::ai::error::NO_CELLS_ERROR;
+use crate::ai::logging::{ACK_MSG_CHNL, RCV_MSG_CHNL, orchestrator_actor, planet_actor};
+use crate::ai::orbitron::{CellView, Lifecycle, Orbitron, charged_cells, has_no_cells};
+use crate::ai::stats::{Direction, increment};
+
+/// Charged cells spent to build a rocket.
+pub(crate) const ROCKET_COST: u32 = 1;
+
+/// Why a planet that can't have rockets is doomed by an asteroid.
+pub(crate) const NO_ROCKETS_ERROR: &str = "Planet type can't have rockets";
+
+/// Why a planet without a charged cell is doomed by an asteroid.
+pub(crate) const NO_CHARGE_ERROR: &str = "Not enough charged cells to build a rocket";
+
+/// Whether the planet would survive an asteroid hitting it right now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Survivability {
+    /// A rocket is already built and would be launched.
+    HasRocket,
+    /// A rocket would be built, spending `cost` charged cells.
+    CanBuildRocket { cost: u32 },
+    /// No rocket can be had, so the planet would be destroyed.
+    Doomed { reason: &'static str },
+}
+
+/// Tells whether the planet would survive an asteroid, without touching it.
+///
+/// [Orbitron] takes this same verdict before acting on an asteroid, so the
+/// answer always matches what the AI would do.
+pub fn asteroid_survivability(state: &PlanetState) -> Survivability {
+    survivability(state, state.has_rocket(), state.can_have_rocket())
+}
+
+pub(crate) fn survivability(
+    cells: &impl CellView,
+    has_rocket: bool,
+    can_have_rocket: bool,
+) -> Survivability {
+    if has_rocket {
+        Survivability::HasRocket
+    } else if !can_attempt_rocket(cells, can_have_rocket) {
+        let reason = if can_have_rocket {
+            NO_CELLS_ERROR
+        } else {
+            NO_ROCKETS_ERROR
+        };
+        Survivability::Doomed { reason }
+    } else if charged_cells(cells) < ROCKET_COST {
+        Survivability::Doomed {
+            reason: NO_CHARGE_ERROR,
+        }
+    } else {
+        Survivability::CanBuildRocket { cost: ROCKET_COST }
+    }
+}
+
+/// Whether a rocket may be built to face an asteroid.
+pub(crate) fn can_attempt_rocket(cells: &impl CellView, can_have_rocket: bool) -> bool {
+    can_have_rocket && !has_no_cells(cells)
+}
+
+impl Orbitron {
+    /// Leaves defensive mode early if a rocket became available meanwhile.
+    pub(crate) fn refresh_defensive(&mut self, state: &PlanetState) {
+        if self.defensive.is_some() && state.has_rocket() {
+            self.exit_defensive_mode(state.id(), "rocket available");
+        }
+    }
+
+    /// Enters defensive mode when fewer cells than
+    /// [OrbitronConfig::defensive_charge_threshold](crate::ai::config::OrbitronConfig::defensive_charge_threshold)
+    /// are charged. Called after a Sunray and after a cell is spent.
+    pub(crate) fn check_low_charge(&mut self, state: &PlanetState) {
+        if self.defensive.is_none()
+            && !state.has_rocket()
+            && charged_cells(state) < self.config.defensive_charge_threshold
+        {
+            self.enter_defensive_mode(state.id(), "low charge");
+        }
+    }
+
+    pub(crate) fn enter_defensive_mode(&mut self, planet_id: ID, trigger: &str) {
+        if self.config.defensive_sunrays == 0 {
+            return;
+        }
+        self.defensive = Some(self.config.defensive_sunrays);
+
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "Entered defensive mode".into());
+        payload.insert("Trigger".into(), trigger.into());
+        payload.insert(
+            "Sunrays Required".into(),
+            self.config.defensive_sunrays.to_string(),
+        );
+        self.emit(
+            LogCategory::Asteroid,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    pub(crate) fn exit_defensive_mode(&mut self, planet_id: ID, reason: &str) {
+        self.defensive = None;
+
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "Left defensive mode".into());
+        payload.insert("Reason".into(), reason.into());
+        self.emit(
+            LogCategory::Asteroid,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Info,
+                payload,
+            ),
+        );
+    }
+
+    /// Discharges up to [OrbitronConfig::asteroid_damage] charged cells after
+    /// a deflected asteroid.
+    pub(crate) fn damage_cells(&mut self, state: &mut PlanetState) {
+        let mut damaged = Vec::new();
+        for index in 0..state.cells_count() {
+            if damaged.len() == self.config.asteroid_damage {
+                break;
+            }
+            if state.cell_mut(index).discharge().is_ok() {
+                self.charged_since.remove(&index);
+                damaged.push(index.to_string());
+            }
+        }
+        if damaged.is_empty() {
+            return;
+        }
+
+        // LOG asteroid damage
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "asteroid_damage".into());
+        payload.insert("cells".into(), damaged.join(", "));
+        self.emit(
+            LogCategory::Asteroid,
+            LogEvent::self_directed(
+                planet_actor(state.id()),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Counts a received Sunray towards leaving defensive mode.
+    pub(crate) fn defensive_sunray(&mut self, planet_id: ID) {
+        if let Some(remaining) = self.defensive {
+            if remaining <= 1 {
+                self.exit_defensive_mode(planet_id, "sunrays received");
+            } else {
+                self.defensive = Some(remaining - 1);
+            }
+        }
+    }
+
+    /// Faces an asteroid, see `PlanetAI::handle_asteroid`.
+    ///
+    /// The stock planet loop answers an asteroid with `Stopped` while the AI
+    /// isn't running, so the stopped branch below is unreachable there. It
+    /// is kept as a defensive fallback for loops that hand the asteroid over
+    /// anyway: the asteroid is faced and counted as a protocol anomaly.
+    pub(crate) fn serve_asteroid(&mut self, state: &mut PlanetState) -> Option<Rocket> {
+        #[cfg(feature = "metrics")]
+        let _timer = self.timings.start("Asteroid");
+        self.begin_exchange();
+        // The planet may not see another Sunray
+        self.flush_batched(state.id());
+        self.flush_supported_requests(state.id());
+        if self.is_destroyed(state.id(), "Asteroid") {
+            return None;
+        }
+        self.check_premature_contact(state.id(), None, "Asteroid");
+        if self.lifecycle == Lifecycle::Stopped {
+            increment(&mut self.protocol_anomalies);
+
+            // LOG asteroid while stopped
+            let mut payload = Payload::new();
+            payload.insert("event".into(), "asteroid_while_stopped".into());
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::self_directed(
+                    planet_actor(state.id()),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+        increment(&mut self.asteroids_faced);
+        self.last_asteroid_tick = Some(self.tick);
+        // LOG incoming asteroid
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "Asteroid".into());
+        self.emit(
+            LogCategory::Asteroid,
+            LogEvent::new(
+                Some(orchestrator_actor()),
+                Some(planet_actor(state.id())),
+                EventType::MessageOrchestratorToPlanet,
+                RCV_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        // LOG asteroid response
+        let mut payload = Payload::new();
+
+        let verdict = asteroid_survivability(state);
+        payload.insert("Verdict".into(), format!("{verdict:?}"));
+        // Why no rocket could be had, logged along with the rocket cost
+        let mut failure = None;
+        match verdict {
+            Survivability::HasRocket => {
+                payload.insert("Result".into(), "Rocket was Ready".into());
+            }
+            Survivability::CanBuildRocket { .. } => {
+                payload.insert("Result".into(), "Rocket was Built".into());
+                let charged = state.cells_iter().position(|cell| cell.is_charged());
+                if let Some(cell) = charged {
+                    failure = state.build_rocket(cell).err();
+                }
+            }
+            Survivability::Doomed { reason } => {
+                payload.insert("Result".into(), "Rocket not attempted".into());
+                if reason == NO_CELLS_ERROR {
+                    payload.insert("Warning".into(), NO_CELLS_ERROR.into());
+                }
+                failure = Some(reason.into());
+            }
+        }
+        let rocket = state.take_rocket();
+
+        // Sequence number matching this asteroid with its AsteroidAck
+        payload.insert("asteroid_seq".into(), self.asteroids_faced.to_string());
+        if let Some(rocket) = &rocket {
+            payload.insert("Result".into(), "Rocket is Available".into());
+            payload.insert("rocket_debug".into(), format!("{rocket:?}"));
+        } else {
+            payload.insert("Result".into(), "No Rocket Available".into());
+            payload.insert("charged_cells".into(), charged_cells(state).to_string());
+            payload.insert(
+                "can_have_rocket".into(),
+                state.can_have_rocket().to_string(),
+            );
+            if state.can_have_rocket() {
+                payload.insert("rocket_cost".into(), ROCKET_COST.to_string());
+            }
+            if let Some(failure) = failure {
+                payload.insert("rocket_failure".into(), failure);
+            }
+        }
+        self.last_asteroid_event = payload.clone();
+        self.emit(
+            LogCategory::Asteroid,
+            LogEvent::new(
+                Some(planet_actor(state.id())),
+                Some(orchestrator_actor()),
+                EventType::MessagePlanetToOrchestrator,
+                ACK_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        if rocket.is_some() {
+            increment(&mut self.asteroids_survived);
+        }
+        if self.asteroids_faced > self.asteroids_survived {
+            self.destroy(state.id(), "asteroid");
+        } else {
+            // Surviving always means a rocket was spent: a planet without one
+            // is destroyed above. Rocketless planets rely on the low-charge
+            // trigger instead
+            self.damage_cells(state);
+            self.enter_defensive_mode(state.id(), "asteroid");
+        }
+        self.record(
+            Direction::OrchestratorToPlanet,
+            "Asteroid",
+            Some("AsteroidAck".into()),
+        );
+        // Asteroids are the only way to Destroyed, so this covers it too
+        let trigger = match self.lifecycle {
+            Lifecycle::Destroyed => "destroyed",
+            _ => "asteroid",
+        };
+        self.dump_history(state.id(), trigger);
+        self.tap(state.id(), None, "AsteroidAck", format!("{rocket:?}"));
+        self.publish(state.id());
+        rocket
+    }
+}
+
+/// Faces an asteroid, see `PlanetAI::handle_asteroid`, which delegates here.
+pub fn process_asteroid(
+    ai: &mut Orbitron,
+    state: &mut PlanetState,
+    _generator: &Generator,
+    _combinator: &Combinator,
+) -> Option<Rocket> {
+    ai.serve_asteroid(state)
+}
@@ -0,0 +1,89 @@
+//! # Orbitron – Dry run
+//!
+//! Only built with the `dry-run` feature, so tournament builds can't turn it
+//! on. With [OrbitronConfig::dry_run](crate::ai::config::OrbitronConfig::dry_run)
+//! set, generation and combination requests are decided by the handler's
+//! own guard ladder and answered empty: no cell is spent and neither the
+//! generator nor the combinator is called. The decision goes to the event history and to
+//! [OrbitronSnapshot::dry_run_decisions](crate::ai::stats::OrbitronSnapshot::dry_run_decisions).
+use common_game::components::planet::PlanetState;
+use common_game::components::resource::{Combinator, Generator};
+use common_game::logging::*;
+use common_game::protocols::planet_explorer::*;
+
+use crate::ai::config::LogCategory;
+use crate::ai::handlers::explorer::{request_inputs, request_type};
+use crate::ai::logging::{ACK_MSG_CHNL, explorer_actor, planet_actor, planet_to_explorer_name};
+use crate::ai::orbitron::Orbitron;
+use crate::ai::stats::{Direction, increment};
+
+impl Orbitron {
+    /// What the AI would answer to a generation or combination request,
+    /// as `would_serve <resource>` or `would_refuse <resource>: <error>`.
+    ///
+    /// Decided by [Orbitron::decide], the guard ladder the handler runs.
+    pub(crate) fn dry_run_decision(
+        &self,
+        state: &PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        msg: &ExplorerToPlanet,
+    ) -> String {
+        let resource = match msg {
+            ExplorerToPlanet::GenerateResourceRequest { resource, .. } => format!("{resource:?}"),
+            ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+                format!("{:?}", request_type(msg))
+            }
+            _ => return "would_serve".into(),
+        };
+        match self.decide(state, generator, combinator, msg) {
+            Ok(()) => format!("would_serve {resource}"),
+            Err(err) => format!("would_refuse {resource}: {}", err.kind()),
+        }
+    }
+
+    /// Answers a generation or combination request without serving it, see
+    /// [Orbitron::dry_run_decision].
+    pub(crate) fn serve_dry_run(
+        &mut self,
+        state: &PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        msg: ExplorerToPlanet,
+        msg_name: String,
+    ) -> PlanetToExplorer {
+        let explorer_id = msg.explorer_id();
+        let decision = self.dry_run_decision(state, generator, combinator, &msg);
+        increment(self.dry_run_decisions.entry(decision.clone()).or_default());
+        let response = match msg {
+            ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+                let (input_1, input_2) = request_inputs(msg);
+                PlanetToExplorer::CombineResourceResponse {
+                    complex_response: Err((format!("dry run: {decision}"), input_1, input_2)),
+                }
+            }
+            _ => PlanetToExplorer::GenerateResourceResponse { resource: None },
+        };
+
+        // LOG dry run decision
+        let mut payload = Payload::new();
+        payload.insert("dry_run".into(), "true".into());
+        payload.insert("Decision".into(), decision.clone());
+        payload.insert("Response".into(), planet_to_explorer_name(&response));
+        self.emit(
+            LogCategory::ExplorerTraffic,
+            LogEvent::new(
+                Some(planet_actor(state.id())),
+                Some(explorer_actor(explorer_id)),
+                EventType::MessagePlanetToExplorer,
+                ACK_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        self.record(Direction::ExplorerToPlanet, msg_name, Some(decision));
+        self.tap_explorer(state.id(), explorer_id, &response);
+        self.publish(state.id());
+        response
+    }
+}
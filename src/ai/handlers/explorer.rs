@@ -0,0 +1,1458 @@
+//! # Orbitron – Explorer messages
+//!
+//! [Orbitron::serve_explorer] and the guards, caps and per-explorer state it
+//! consults.
+use std::collections::HashSet;
+
+use common_game::components::energy_cell::EnergyCell;
+use common_game::components::planet::PlanetState;
+use common_game::components::resource::{
+    BasicResource, BasicResourceType, Combinator, ComplexResource, ComplexResourceRequest,
+    ComplexResourceType, Generator, GenericResource,
+};
+use common_game::logging::*;
+use common_game::protocols::planet_explorer::*;
+use common_game::utils::ID;
+
+use crate::ai::config::{LogCategory, UnknownRecipePolicy};
+use crate::ai::error::{NO_CELLS_ERROR, OrbitronError};
+use crate::ai::logging::{
+    ACK_MSG_CHNL, RCV_MSG_CHNL, explorer_actor, explorer_to_planet_name, planet_actor,
+    planet_to_explorer_name,
+};
+use crate::ai::orbitron::{CellView, Lifecycle, Orbitron, charged_cells, has_no_cells};
+use crate::ai::recipes;
+use crate::ai::stats::{Direction, RefusalReason, increment};
+
+/// Every basic resource, in canonical order.
+pub(crate) const BASIC_RESOURCES: [BasicResourceType; 4] = [
+    BasicResourceType::Hydrogen,
+    BasicResourceType::Oxygen,
+    BasicResourceType::Carbon,
+    BasicResourceType::Silicon,
+];
+
+/// Number of handled messages between two runs of [Orbitron::gc].
+pub(crate) const GC_INTERVAL: u64 = 256;
+
+/// Why [generate] produced nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GenError {
+    /// The resource isn't among the generator's recipes.
+    NotSupportedByThisPlanet(BasicResourceType),
+    /// The generator refused, e.g. because the cell wasn't charged.
+    Generator(String),
+}
+
+impl GenError {
+    /// Code of the matching [OrbitronError], see [OrbitronError::code].
+    pub(crate) fn code(&self) -> u16 {
+        match self {
+            GenError::NotSupportedByThisPlanet(_) => 2,
+            GenError::Generator(_) => 3,
+        }
+    }
+}
+
+/// The generator calls of the generation path, a seam for unit tests to
+/// put a failing generator in its place.
+pub(crate) trait ResourceGenerator {
+    fn recipes(&self) -> HashSet<BasicResourceType>;
+
+    /// Makes `resource` with `cell`, `None` when there's no way to make it.
+    fn make(
+        &self,
+        resource: BasicResourceType,
+        cell: &mut EnergyCell,
+    ) -> Option<Result<BasicResource, String>>;
+}
+
+/// Generates through the [GENERATION](recipes::GENERATION) table.
+impl ResourceGenerator for Generator {
+    fn recipes(&self) -> HashSet<BasicResourceType> {
+        self.all_available_recipes()
+    }
+
+    fn make(
+        &self,
+        resource: BasicResourceType,
+        cell: &mut EnergyCell,
+    ) -> Option<Result<BasicResource, String>> {
+        recipes::maker(resource).map(|make| make(self, cell))
+    }
+}
+
+/// Generates `resource` with `cell`.
+pub(crate) fn generate(
+    generator: &impl ResourceGenerator,
+    cell: &mut EnergyCell,
+    resource: BasicResourceType,
+) -> Result<BasicResource, GenError> {
+    if !generator.recipes().contains(&resource) {
+        return Err(GenError::NotSupportedByThisPlanet(resource));
+    }
+    generator
+        .make(resource, cell)
+        .ok_or(GenError::NotSupportedByThisPlanet(resource))?
+        .map_err(GenError::Generator)
+}
+
+/// Generates `resource` with the first charged cell of `cells` and, with
+/// `retry` set, once more with the next charged one after a generator error.
+///
+/// Returns the outcome of the last attempt and the errors the generator
+/// returned on the way, the last attempt's included.
+pub(crate) fn generate_with_retry<'a>(
+    generator: &impl ResourceGenerator,
+    cells: impl IntoIterator<Item = &'a mut EnergyCell>,
+    resource: BasicResourceType,
+    retry: bool,
+) -> (Result<BasicResource, GenError>, Vec<String>) {
+    let mut errors = Vec::new();
+    let attempts = if retry { 2 } else { 1 };
+    for cell in cells
+        .into_iter()
+        .filter(|cell| cell.is_charged())
+        .take(attempts)
+    {
+        match generate(generator, cell, resource) {
+            Err(GenError::Generator(err)) => errors.push(err),
+            result => return (result, errors),
+        }
+    }
+    let err = errors
+        .last()
+        .cloned()
+        .unwrap_or_else(|| NO_CELLS_ERROR.into());
+    (Err(GenError::Generator(err)), errors)
+}
+
+/// Supported resource closest to `requested` in canonical order, the earlier
+/// one on a tie.
+pub(crate) fn closest_supported(
+    requested: BasicResourceType,
+    supported: &HashSet<BasicResourceType>,
+) -> Option<BasicResourceType> {
+    let position = |resource| BASIC_RESOURCES.iter().position(|r| *r == resource);
+    let requested_at = position(requested)?;
+    BASIC_RESOURCES
+        .into_iter()
+        .filter(|resource| supported.contains(resource))
+        .min_by_key(|resource| position(*resource).map(|at| at.abs_diff(requested_at)))
+}
+
+/// Every complex resource, in canonical order.
+pub(crate) const COMPLEX_RESOURCES: [ComplexResourceType; 6] = [
+    ComplexResourceType::Water,
+    ComplexResourceType::Diamond,
+    ComplexResourceType::Life,
+    ComplexResourceType::Robot,
+    ComplexResourceType::Dolphin,
+    ComplexResourceType::AIPartner,
+];
+
+/// `resources` in the canonical order of [BASIC_RESOURCES].
+///
+/// Recipe sets are `HashSet`s, so this is how they are logged: the same
+/// set always prints the same way.
+pub(crate) fn sorted_basic(resources: &HashSet<BasicResourceType>) -> Vec<BasicResourceType> {
+    BASIC_RESOURCES
+        .into_iter()
+        .filter(|resource| resources.contains(resource))
+        .collect()
+}
+
+/// `recipes` in the canonical order of [COMPLEX_RESOURCES].
+pub(crate) fn sorted_complex(recipes: &HashSet<ComplexResourceType>) -> Vec<ComplexResourceType> {
+    COMPLEX_RESOURCES
+        .into_iter()
+        .filter(|recipe| recipes.contains(recipe))
+        .collect()
+}
+
+/// Charged cells not earmarked for one of the `pending` queued messages.
+pub(crate) fn unreserved_cells(charged: u32, pending: usize) -> u32 {
+    let pending = u32::try_from(pending).unwrap_or(u32::MAX);
+    charged - charged.min(pending)
+}
+
+/// Whether the request only reads the planet state.
+pub(crate) fn is_read_only(msg: &ExplorerToPlanet) -> bool {
+    matches!(
+        msg,
+        ExplorerToPlanet::SupportedResourceRequest { .. }
+            | ExplorerToPlanet::SupportedCombinationRequest { .. }
+            | ExplorerToPlanet::AvailableEnergyCellRequest { .. }
+    )
+}
+
+/// Builds the empty/negative answer to `msg`, handing back any resource
+/// the explorer sent along with it.
+pub(crate) fn refused_response(
+    msg: ExplorerToPlanet,
+    reason: &OrbitronError,
+    with_code: bool,
+) -> PlanetToExplorer {
+    match msg {
+        ExplorerToPlanet::SupportedResourceRequest { .. } => {
+            PlanetToExplorer::SupportedResourceResponse {
+                resource_list: HashSet::new(),
+            }
+        }
+        ExplorerToPlanet::SupportedCombinationRequest { .. } => {
+            PlanetToExplorer::SupportedCombinationResponse {
+                combination_list: HashSet::new(),
+            }
+        }
+        ExplorerToPlanet::GenerateResourceRequest { .. } => {
+            PlanetToExplorer::GenerateResourceResponse { resource: None }
+        }
+        ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+            let (resource_1, resource_2) = request_inputs(msg);
+            PlanetToExplorer::CombineResourceResponse {
+                complex_response: Err((reason.protocol_text(with_code), resource_1, resource_2)),
+            }
+        }
+        ExplorerToPlanet::AvailableEnergyCellRequest { .. } => {
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 0 }
+        }
+    }
+}
+
+/// Refuses a combination with `err`, handing both inputs back and recording
+/// the error in `payload`.
+pub(crate) fn refused_combination(
+    msg: ComplexResourceRequest,
+    err: &OrbitronError,
+    with_code: bool,
+    payload: &mut Payload,
+) -> PlanetToExplorer {
+    let (resource_1, resource_2) = request_inputs(msg);
+    let ret = Err((err.protocol_text(with_code), resource_1, resource_2));
+    payload.insert("Error".into(), err.kind().into());
+    payload.insert("ErrorCode".into(), err.code().to_string());
+    payload.insert(
+        "Combined Resource".into(),
+        format!("Refused Resource Combination Request: {:?}", ret),
+    );
+    PlanetToExplorer::CombineResourceResponse {
+        complex_response: ret,
+    }
+}
+
+/// Returns the complex resource a combination request asks for.
+pub(crate) fn request_type(msg: &ComplexResourceRequest) -> ComplexResourceType {
+    match msg {
+        ComplexResourceRequest::Water(..) => ComplexResourceType::Water,
+        ComplexResourceRequest::Diamond(..) => ComplexResourceType::Diamond,
+        ComplexResourceRequest::Life(..) => ComplexResourceType::Life,
+        ComplexResourceRequest::Robot(..) => ComplexResourceType::Robot,
+        ComplexResourceRequest::Dolphin(..) => ComplexResourceType::Dolphin,
+        ComplexResourceRequest::AIPartner(..) => ComplexResourceType::AIPartner,
+    }
+}
+
+/// Hands back the two input resources of a combination request.
+pub(crate) fn request_inputs(msg: ComplexResourceRequest) -> (GenericResource, GenericResource) {
+    match msg {
+        ComplexResourceRequest::Water(r1, r2) => (r1.to_generic(), r2.to_generic()),
+        ComplexResourceRequest::Diamond(r1, r2) => (r1.to_generic(), r2.to_generic()),
+        ComplexResourceRequest::Life(r1, r2) => (r1.to_generic(), r2.to_generic()),
+        ComplexResourceRequest::Robot(r1, r2) => (r1.to_generic(), r2.to_generic()),
+        ComplexResourceRequest::Dolphin(r1, r2) => (r1.to_generic(), r2.to_generic()),
+        ComplexResourceRequest::AIPartner(r1, r2) => (r1.to_generic(), r2.to_generic()),
+    }
+}
+
+/// Consecutive failed combinations of a single explorer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CombineFailures {
+    pub(crate) count: u32,
+    /// Logical time when the last failure happened.
+    pub(crate) last_at: u64,
+}
+
+impl Orbitron {
+    /// Whether `req` would currently be combined, without spending a cell
+    /// or the inputs, so explorers can check before handing them over.
+    ///
+    /// Inputs need no check: each [ComplexResourceRequest] variant carries
+    /// the exact input types of its recipe. Per-explorer limits (back-off,
+    /// per-tick cap, strategy) are left out, as they depend on who asks.
+    pub fn can_combine(
+        &self,
+        state: &PlanetState,
+        combinator: &Combinator,
+        req: &ComplexResourceRequest,
+    ) -> Result<(), OrbitronError> {
+        let defensive = self.defensive.is_some() && !state.has_rocket();
+        self.combine_check(
+            state,
+            &combinator.all_available_recipes(),
+            request_type(req),
+            defensive,
+        )
+    }
+
+    /// [Orbitron::can_combine] on plain values, in the order the handler
+    /// checks them.
+    pub(crate) fn combine_check(
+        &self,
+        cells: &impl CellView,
+        recipes: &HashSet<ComplexResourceType>,
+        recipe: ComplexResourceType,
+        defensive: bool,
+    ) -> Result<(), OrbitronError> {
+        if self.generation_paused.is_paused() {
+            Err(OrbitronError::Paused)
+        } else if has_no_cells(cells) {
+            Err(OrbitronError::NoCells)
+        } else if defensive {
+            Err(OrbitronError::Defensive)
+        } else if !recipes.contains(&recipe) {
+            Err(self.unsupported_recipe(recipe, recipes))
+        } else if charged_cells(cells) == 0 {
+            Err(OrbitronError::NoChargedCell)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Error for a recipe missing from `recipes`, as chosen by
+    /// [OrbitronConfig::unknown_recipe_policy].
+    pub(crate) fn unsupported_recipe(
+        &self,
+        recipe: ComplexResourceType,
+        recipes: &HashSet<ComplexResourceType>,
+    ) -> OrbitronError {
+        match self.config.unknown_recipe_policy {
+            UnknownRecipePolicy::Structured => OrbitronError::UnsupportedRecipe {
+                requested: recipe,
+                supported: sorted_complex(recipes),
+            },
+            _ => OrbitronError::UnsupportedCombination(recipe),
+        }
+    }
+
+    /// Whether a generation or combination request would be served right
+    /// now, or the error it would be refused with.
+    ///
+    /// This is the guard ladder of [Orbitron::serve_explorer], in its order.
+    /// It has no side effects, so dry runs decide exactly like the handler;
+    /// expired back-offs and defensive mode are settled beforehand by
+    /// [Orbitron::settle_guards]. Read-only requests and resources waiting
+    /// in escrow are `Ok`.
+    pub(crate) fn decide(
+        &self,
+        state: &PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        msg: &ExplorerToPlanet,
+    ) -> Result<(), OrbitronError> {
+        let defensive = self.defensive.is_some() && !state.has_rocket();
+        let charged = charged_cells(state);
+        match *msg {
+            ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id,
+                resource,
+            } => {
+                if self.generation_paused.is_paused() {
+                    Err(OrbitronError::Paused)
+                } else if has_no_cells(state) {
+                    Err(OrbitronError::NoCells)
+                } else if self.escrow_holds(explorer_id, resource) {
+                    Ok(())
+                } else if defensive {
+                    Err(OrbitronError::Defensive)
+                } else if self.is_inflight_capped() && !self.is_fast_tracked(explorer_id, resource)
+                {
+                    Err(OrbitronError::RateLimited)
+                } else if let Some(behind) = self.water_imbalance(resource) {
+                    Err(OrbitronError::ProductionImbalance(behind))
+                } else if !generator.all_available_recipes().contains(&resource) {
+                    Err(OrbitronError::UnsupportedResource(resource))
+                } else if !self.strategy.serve_generate(explorer_id, resource, charged) {
+                    Err(OrbitronError::Declined)
+                } else if charged == 0 {
+                    Err(OrbitronError::NoChargedCell)
+                } else {
+                    Ok(())
+                }
+            }
+            ExplorerToPlanet::CombineResourceRequest {
+                explorer_id,
+                ref msg,
+            } => {
+                let recipe = request_type(msg);
+                let recipes = combinator.all_available_recipes();
+                if self.generation_paused.is_paused() {
+                    Err(OrbitronError::Paused)
+                } else if has_no_cells(state) {
+                    Err(OrbitronError::NoCells)
+                } else if self.is_backing_off(explorer_id) {
+                    Err(OrbitronError::BackedOff)
+                } else if self.is_combine_throttled() {
+                    Err(OrbitronError::RateLimited)
+                } else if defensive {
+                    Err(OrbitronError::Defensive)
+                } else if !recipes.contains(&recipe) {
+                    Err(self.unsupported_recipe(recipe, &recipes))
+                } else if !self.strategy.serve_combine(explorer_id, recipe, charged) {
+                    Err(OrbitronError::Declined)
+                } else if charged == 0 {
+                    Err(OrbitronError::NoChargedCell)
+                } else {
+                    Ok(())
+                }
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Settles the state [Orbitron::decide] only reads: lifts the back-off
+    /// of `msg`'s explorer once expired, and leaves defensive mode once a
+    /// rocket is available.
+    pub(crate) fn settle_guards(&mut self, state: &PlanetState, msg: &ExplorerToPlanet) {
+        if is_read_only(msg) {
+            return;
+        }
+        self.refresh_defensive(state);
+        if let ExplorerToPlanet::CombineResourceRequest { explorer_id, .. } = *msg {
+            self.expire_backoff(state.id(), explorer_id);
+        }
+    }
+
+    /// Alternative offered for `requested`: a weighted pick when
+    /// [OrbitronConfig::generation_weights] is set, the closest supported
+    /// resource otherwise.
+    pub(crate) fn suggest(
+        &mut self,
+        requested: BasicResourceType,
+        supported: &HashSet<BasicResourceType>,
+    ) -> Option<BasicResourceType> {
+        if self.config.generation_weights.is_empty() {
+            closest_supported(requested, supported)
+        } else {
+            self.choose_resource(supported)
+        }
+    }
+
+    /// Picks one of `supported` according to [OrbitronConfig::generation_weights].
+    ///
+    /// Falls back to a uniform pick when no supported resource has a weight.
+    pub(crate) fn choose_resource(
+        &mut self,
+        supported: &HashSet<BasicResourceType>,
+    ) -> Option<BasicResourceType> {
+        let candidates: Vec<BasicResourceType> = BASIC_RESOURCES
+            .into_iter()
+            .filter(|resource| supported.contains(resource))
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        let weights: Vec<u64> = candidates
+            .iter()
+            .map(|resource| {
+                u64::from(
+                    self.config
+                        .generation_weights
+                        .get(resource)
+                        .copied()
+                        .unwrap_or(0),
+                )
+            })
+            .collect();
+        let total: u64 = weights.iter().sum();
+        if total == 0 {
+            let index = self.rng.below(candidates.len() as u64) as usize;
+            return Some(candidates[index]);
+        }
+
+        let mut roll = self.rng.below(total);
+        for (resource, weight) in candidates.iter().zip(weights) {
+            if roll < weight {
+                return Some(*resource);
+            }
+            roll -= weight;
+        }
+        unreachable!("roll is below the total weight")
+    }
+
+    /// Generates a basic resource of the planet's choosing for
+    /// `explorer_id`.
+    ///
+    /// The explorer protocol always names the wanted resource, so this is
+    /// meant for in-process callers. The resource is picked following
+    /// [OrbitronConfig::generation_weights], then served like a
+    /// `GenerateResourceRequest` for it, through every guard of
+    /// [Orbitron::serve_explorer].
+    pub fn generate_any(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        explorer_id: ID,
+    ) -> Option<BasicResource> {
+        let resource = self.choose_resource(&generator.all_available_recipes())?;
+        let msg = ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id,
+            resource,
+        };
+        match self.serve_explorer(state, generator, combinator, msg) {
+            Some(PlanetToExplorer::GenerateResourceResponse { resource }) => resource,
+            _ => None,
+        }
+    }
+
+    /// Asks the shadow strategy about a request the strategy was asked
+    /// about in [Orbitron::decide], tallying whether both agree.
+    pub(crate) fn compare_with_shadow(
+        &mut self,
+        msg: &ExplorerToPlanet,
+        charged_cells: u32,
+        served: bool,
+    ) {
+        let Some(shadow) = &self.shadow else {
+            return;
+        };
+        match msg {
+            ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id,
+                resource,
+            } => {
+                let shadow_serves = shadow.serve_generate(*explorer_id, *resource, charged_cells);
+                self.shadow_report.generate.add(served, shadow_serves);
+            }
+            ExplorerToPlanet::CombineResourceRequest { explorer_id, msg } => {
+                let shadow_serves =
+                    shadow.serve_combine(*explorer_id, request_type(msg), charged_cells);
+                self.shadow_report.combine.add(served, shadow_serves);
+            }
+            _ => {}
+        }
+    }
+
+    /// The other element of Water when `resource` is already ahead of it by
+    /// more than [OrbitronConfig::water_imbalance_threshold], `None` when
+    /// `resource` may be served.
+    pub(crate) fn water_imbalance(&self, resource: BasicResourceType) -> Option<BasicResourceType> {
+        if !self.config.balance_for_water {
+            return None;
+        }
+        let (ahead, behind, other) = match resource {
+            BasicResourceType::Hydrogen => (
+                self.unconsumed_hydrogen,
+                self.unconsumed_oxygen,
+                BasicResourceType::Oxygen,
+            ),
+            BasicResourceType::Oxygen => (
+                self.unconsumed_oxygen,
+                self.unconsumed_hydrogen,
+                BasicResourceType::Hydrogen,
+            ),
+            _ => return None,
+        };
+        (ahead.saturating_sub(behind) > self.config.water_imbalance_threshold).then_some(other)
+    }
+
+    /// Accounts for a generated Hydrogen or Oxygen.
+    pub(crate) fn note_water_input(&mut self, resource: BasicResourceType) {
+        match resource {
+            BasicResourceType::Hydrogen => increment(&mut self.unconsumed_hydrogen),
+            BasicResourceType::Oxygen => increment(&mut self.unconsumed_oxygen),
+            _ => {}
+        }
+    }
+
+    /// Whether the per-tick generation cap has been reached.
+    pub(crate) fn is_inflight_capped(&self) -> bool {
+        self.generations_this_tick >= self.config.max_inflight
+    }
+
+    /// Whether this request is the Oxygen half of a Hydrogen/Oxygen pair
+    /// promised by [OrbitronConfig::auto_pair].
+    pub(crate) fn is_fast_tracked(&self, explorer_id: ID, resource: BasicResourceType) -> bool {
+        resource == BasicResourceType::Oxygen && self.fast_tracked.contains(&explorer_id)
+    }
+
+    /// Hints that `explorer_id` is likely gathering a Hydrogen/Oxygen pair and,
+    /// with [OrbitronConfig::auto_pair], fast-tracks its next Oxygen request.
+    pub(crate) fn note_batch_candidate(&mut self, planet_id: ID, explorer_id: ID) {
+        if self.config.auto_pair {
+            self.fast_tracked.insert(explorer_id);
+        }
+
+        // LOG batch candidate
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "batch_candidate".into());
+        payload.insert("Fast Tracked".into(), self.config.auto_pair.to_string());
+        self.emit(
+            LogCategory::ExplorerTraffic,
+            LogEvent::new(
+                Some(planet_actor(planet_id)),
+                Some(explorer_actor(explorer_id)),
+                EventType::InternalPlanetAction,
+                Channel::Debug,
+                payload,
+            ),
+        );
+    }
+
+    /// Whether the per-tick combination cap has been reached.
+    pub(crate) fn is_combine_throttled(&self) -> bool {
+        self.combines_this_tick >= self.config.max_combines_per_tick
+    }
+
+    pub(crate) fn escrow_holds(&self, explorer_id: ID, resource: BasicResourceType) -> bool {
+        self.escrow
+            .iter()
+            .any(|(owner, kind, _)| *owner == explorer_id && *kind == resource)
+    }
+
+    /// Takes the oldest resource of type `resource` held for `explorer_id`.
+    pub(crate) fn take_from_escrow(
+        &mut self,
+        explorer_id: ID,
+        resource: BasicResourceType,
+    ) -> Option<BasicResource> {
+        let index = self
+            .escrow
+            .iter()
+            .position(|(owner, kind, _)| *owner == explorer_id && *kind == resource)?;
+        Some(self.escrow.remove(index).2)
+    }
+
+    /// Holds `held` for `explorer_id`, evicting the oldest entry when full.
+    pub(crate) fn put_in_escrow(
+        &mut self,
+        planet_id: ID,
+        explorer_id: ID,
+        resource: BasicResourceType,
+        held: BasicResource,
+    ) {
+        if self.escrow.len() >= self.config.escrow_capacity {
+            let (owner, kind, _) = self.escrow.remove(0);
+            increment(&mut self.memory_pressure_events);
+
+            let mut payload = Payload::new();
+            payload.insert(
+                "Message".into(),
+                "Escrow full, evicted oldest resource".into(),
+            );
+            payload.insert(
+                "Evicted".into(),
+                format!("{kind:?} held for explorer {owner}"),
+            );
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+        self.escrow.push((explorer_id, resource, held));
+    }
+
+    /// Records a message from `explorer_id` in the registry, flagging a
+    /// likely reused id, then evicts the least recently seen explorer and
+    /// all its state when more than [OrbitronConfig::max_tracked_explorers]
+    /// are tracked.
+    pub(crate) fn track_explorer(&mut self, planet_id: ID, explorer_id: ID, read_only: bool) {
+        // The message being handled isn't counted yet
+        let seq = self.logical_time.saturating_add(1);
+        let silence = self.config.explorer_silence_threshold;
+        if let Some(silent_for) = self.explorers.record(explorer_id, seq, read_only, silence) {
+            self.log_suspicious_explorer(planet_id, explorer_id, silent_for);
+        }
+        if self.explorers.len() <= self.config.max_tracked_explorers {
+            return;
+        }
+        let Some((evicted, last_seen)) = self.explorers.least_recently_seen() else {
+            return;
+        };
+        self.forget_explorer(evicted);
+        increment(&mut self.memory_pressure_events);
+
+        // LOG evicted explorer
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "explorer_evicted".into());
+        payload.insert("explorer".into(), evicted.to_string());
+        payload.insert("last_seen".into(), last_seen.to_string());
+        payload.insert("cap".into(), self.config.max_tracked_explorers.to_string());
+        self.emit(
+            LogCategory::Failures,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Drops every per-explorer state kept for `explorer_id`.
+    fn forget_explorer(&mut self, explorer_id: ID) {
+        self.explorers.remove(explorer_id);
+        self.fast_tracked.remove(&explorer_id);
+        self.combine_failures.remove(&explorer_id);
+        self.suggestions.remove(&explorer_id);
+        self.premature_contacts.remove(&Some(explorer_id));
+    }
+
+    /// Drops the state of explorers unseen for more than
+    /// [OrbitronConfig::explorer_horizon] messages, and back-off records
+    /// older than that.
+    pub(crate) fn gc(&mut self) {
+        let Some(horizon) = self.config.explorer_horizon else {
+            return;
+        };
+        let stale = self
+            .explorers
+            .seen_before(self.logical_time.saturating_sub(horizon));
+        for explorer_id in &stale {
+            self.forget_explorer(*explorer_id);
+        }
+        let failures = self.combine_failures.len();
+        let now = self.logical_time;
+        self.combine_failures
+            .retain(|_, failures| now.saturating_sub(failures.last_at) <= horizon);
+        if stale.is_empty() && failures == self.combine_failures.len() {
+            return;
+        }
+
+        // LOG collected explorer state
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "explorer_state_gc".into());
+        payload.insert("explorers".into(), format!("{stale:?}"));
+        payload.insert(
+            "backoff_records".into(),
+            (failures - self.combine_failures.len()).to_string(),
+        );
+        payload.insert("horizon".into(), horizon.to_string());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(self.planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Debug,
+                payload,
+            ),
+        );
+    }
+
+    fn log_suspicious_explorer(&self, planet_id: ID, explorer_id: ID, silent_for: u64) {
+        // LOG possibly reused explorer id
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "explorer_id_reuse_suspected".into());
+        payload.insert("explorer".into(), explorer_id.to_string());
+        payload.insert("silent_for".into(), silent_for.to_string());
+        if let Some(record) = self.explorers.get(explorer_id) {
+            payload.insert("first_seen".into(), record.first_seen.to_string());
+            payload.insert("messages".into(), record.messages.to_string());
+        }
+        self.emit(
+            LogCategory::Failures,
+            LogEvent::new(
+                Some(planet_actor(planet_id)),
+                Some(explorer_actor(explorer_id)),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Sends every batched answer, in the order they were produced.
+    ///
+    /// Answers for explorers unregistered in the meantime are dropped.
+    pub(crate) fn flush_batched(&mut self, planet_id: ID) {
+        if self.batched.is_empty() {
+            return;
+        }
+        let batched = std::mem::take(&mut self.batched);
+        let count = batched.len();
+        for (explorer_id, response) in batched {
+            if let Some(sender) = self.explorer_senders.get(explorer_id) {
+                let _ = sender.send(response);
+            }
+        }
+
+        // LOG flushed batch
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "responses_flushed".into());
+        payload.insert("count".into(), count.to_string());
+        self.emit(
+            LogCategory::ExplorerTraffic,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Debug,
+                payload,
+            ),
+        );
+    }
+
+    /// Counts and logs the errors the generator returned while serving a
+    /// generation, `recovered` when a retry made the resource after all.
+    pub(crate) fn note_generator_errors(
+        &mut self,
+        planet_id: ID,
+        explorer_id: ID,
+        errors: &[String],
+        recovered: bool,
+        payload: &mut Payload,
+    ) {
+        if errors.is_empty() {
+            return;
+        }
+        self.generator_errors = self.generator_errors.saturating_add(errors.len() as u64);
+        if recovered {
+            increment(&mut self.generator_retries_succeeded);
+            payload.insert("Retried".into(), "recovered".into());
+        }
+        for (attempt, err) in errors.iter().enumerate() {
+            // LOG generator error
+            let mut warning = Payload::new();
+            warning.insert("event".into(), "generator_error".into());
+            warning.insert("error".into(), err.clone());
+            warning.insert("attempt".into(), (attempt + 1).to_string());
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::new(
+                    Some(planet_actor(planet_id)),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    warning,
+                ),
+            );
+        }
+    }
+
+    /// Holds the log of a `SupportedResourceRequest` answered this tick:
+    /// every such request until the next Sunray gets a single event. An
+    /// asteroid, a stop or dropping the AI logs it early.
+    pub(crate) fn coalesce_supported_request(&mut self, explorer_id: ID, mut payload: Payload) {
+        // Keeps the exchange of the first request, not of the flushing Sunray
+        payload.insert("corr_id".into(), self.corr_id.to_string());
+        self.supported_requests
+            .get_or_insert_with(|| (payload, Vec::new()))
+            .1
+            .push(explorer_id);
+    }
+
+    /// Logs the `SupportedResourceRequest`s held since the last Sunray as
+    /// one event, with their `count` and the `explorers` that sent them.
+    pub(crate) fn flush_supported_requests(&mut self, planet_id: ID) {
+        let Some((mut payload, explorers)) = self.supported_requests.take() else {
+            return;
+        };
+
+        // LOG coalesced supported resource requests
+        let receiver = match explorers.as_slice() {
+            [first, rest @ ..] if rest.iter().all(|id| id == first) => Some(explorer_actor(*first)),
+            _ => None,
+        };
+        payload.insert("Message".into(), "Supported Resource Request".into());
+        payload.insert("count".into(), explorers.len().to_string());
+        payload.insert("explorers".into(), format!("{explorers:?}"));
+        self.emit(
+            LogCategory::ExplorerTraffic,
+            LogEvent::new(
+                Some(planet_actor(planet_id)),
+                receiver,
+                EventType::MessagePlanetToExplorer,
+                ACK_MSG_CHNL,
+                payload,
+            ),
+        );
+    }
+
+    /// Whether `explorer_id` is currently in combination back-off.
+    ///
+    /// Back-off is lifted once enough other messages have been handled
+    /// since the explorer's last failure.
+    pub(crate) fn is_backing_off(&self, explorer_id: ID) -> bool {
+        let threshold = self.config.combine_backoff_threshold;
+        self.combine_failures
+            .get(&explorer_id)
+            .is_some_and(|failures| {
+                !self.backoff_expired(failures) && threshold > 0 && failures.count >= threshold
+            })
+    }
+
+    fn backoff_expired(&self, failures: &CombineFailures) -> bool {
+        self.age_of(failures.last_at) > u64::from(self.config.combine_backoff_reset)
+    }
+
+    /// Forgets the failures of `explorer_id` once its back-off expired.
+    pub(crate) fn expire_backoff(&mut self, planet_id: ID, explorer_id: ID) {
+        if self
+            .combine_failures
+            .get(&explorer_id)
+            .is_some_and(|failures| self.backoff_expired(failures))
+        {
+            self.clear_combine_failures(planet_id, explorer_id);
+        }
+    }
+
+    pub(crate) fn record_combine_failure(&mut self, planet_id: ID, explorer_id: ID) {
+        let failures = self
+            .combine_failures
+            .entry(explorer_id)
+            .or_insert(CombineFailures {
+                count: 0,
+                last_at: 0,
+            });
+        failures.count = failures.count.saturating_add(1);
+        failures.last_at = self.logical_time;
+
+        if failures.count == self.config.combine_backoff_threshold {
+            let mut payload = Payload::new();
+            payload.insert("Message".into(), "Explorer entered back-off".into());
+            payload.insert("Failed Combinations".into(), failures.count.to_string());
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::new(
+                    Some(planet_actor(planet_id)),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+    }
+
+    /// Forgets the failures of `explorer_id` after a successful request.
+    pub(crate) fn clear_combine_failures(&mut self, planet_id: ID, explorer_id: ID) {
+        let Some(failures) = self.combine_failures.remove(&explorer_id) else {
+            return;
+        };
+        let threshold = self.config.combine_backoff_threshold;
+        if threshold > 0 && failures.count >= threshold {
+            let mut payload = Payload::new();
+            payload.insert("Message".into(), "Explorer left back-off".into());
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::new(
+                    Some(planet_actor(planet_id)),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+    }
+}
+
+impl Orbitron {
+    /// Handles messages from explorers.
+    ///
+    /// - Provides supported basic and complex resource types.  
+    ///   Every `SupportedResourceRequest` is answered, but those of a tick are
+    ///   logged as one event on the next Sunray, asteroid or stop.
+    /// - Generates requested basic resources (Hydrogen or Oxygen).  
+    ///   First, we check whether there is any charged cell (the `full_cell` function does this).  
+    ///   If there is, [generate] checks that the generator has a recipe for the requested
+    ///   `BasicResourceType` (Hydrogen and Oxygen on Orbitron) and makes it; otherwise no resource
+    ///   is returned.
+    /// - Generates Water as the only supported complex resource.  
+    ///   As before, we must check whether there is a charged cell.  
+    ///   Since the planet can only generate water, if the requested complex resource type is `Water`,
+    ///   we proceed with generation; otherwise, we return an error message.  
+    ///   Every recipe consumes both inputs whole, so a successful combination
+    ///   leaves nothing over; a failed one hands both inputs back with the error.
+    /// - Returns the number of available charged energy cells.
+    pub(crate) fn serve_explorer(
+        &mut self,
+        state: &mut PlanetState,
+        generator: &Generator,
+        combinator: &Combinator,
+        msg: ExplorerToPlanet,
+    ) -> Option<PlanetToExplorer> {
+        #[cfg(feature = "metrics")]
+        let _timer = self.timings.start(explorer_to_planet_name(&msg));
+        self.begin_exchange();
+        if self.is_destroyed(state.id(), &explorer_to_planet_name(&msg)) {
+            return Some(refused_response(
+                msg,
+                &OrbitronError::Destroyed,
+                self.config.error_codes,
+            ));
+        }
+        let explorer_id: ID = msg.explorer_id();
+        increment(&mut self.explorer_msgs);
+        increment(
+            self.explorer_requests
+                .entry(explorer_to_planet_name(&msg))
+                .or_default(),
+        );
+        self.track_explorer(state.id(), explorer_id, is_read_only(&msg));
+
+        // LOG incoming explorer message, unless coalesced with the response
+        let msg_name = explorer_to_planet_name(&msg);
+        let coalesced = matches!(msg, ExplorerToPlanet::SupportedResourceRequest { .. });
+        if !coalesced && self.logs_enabled(LogCategory::ExplorerTraffic, RCV_MSG_CHNL) {
+            let mut in_payload = Payload::new();
+            in_payload.insert("Message".into(), msg_name.clone());
+            self.emit(
+                LogCategory::ExplorerTraffic,
+                LogEvent::new(
+                    Some(explorer_actor(explorer_id)),
+                    Some(planet_actor(state.id())),
+                    EventType::MessageExplorerToPlanet,
+                    RCV_MSG_CHNL,
+                    in_payload,
+                ),
+            );
+        }
+        self.check_premature_contact(state.id(), Some(explorer_id), &msg_name);
+
+        let allowed = self.config.is_explorer_allowed(explorer_id)
+            || (self.config.serve_read_only_to_disallowed && is_read_only(&msg));
+        if !allowed {
+            let response =
+                refused_response(msg, &OrbitronError::NotAllowed, self.config.error_codes);
+
+            // LOG refused explorer
+            let mut payload = Payload::new();
+            self.refuse(RefusalReason::NotAllowed, &mut payload);
+            payload.insert("Reason".into(), "explorer_not_allowed".into());
+            payload.insert("Response".into(), planet_to_explorer_name(&response));
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::new(
+                    Some(planet_actor(state.id())),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::MessagePlanetToExplorer,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+
+            self.record(
+                Direction::ExplorerToPlanet,
+                msg_name,
+                Some(planet_to_explorer_name(&response)),
+            );
+            self.tap_explorer(state.id(), explorer_id, &response);
+            return Some(response);
+        }
+
+        // Read-only queries are still answered before the first start. The
+        // stock planet loop never hands them over that early, so this only
+        // matters when the AI is driven directly, e.g. by the fuzz targets
+        let lifecycle = self.lifecycle;
+        let refusal = match lifecycle {
+            Lifecycle::Created if !is_read_only(&msg) => Some(RefusalReason::Stopped),
+            Lifecycle::Stopped => Some(RefusalReason::Stopped),
+            _ if !is_read_only(&msg) && self.is_orchestrator_unreachable(state.id()) => {
+                Some(RefusalReason::OrchestratorUnreachable)
+            }
+            _ => None,
+        };
+        if let Some(reason) = refusal {
+            let response = refused_response(msg, &OrbitronError::Stopped, self.config.error_codes);
+
+            // LOG refused while stopped or cut off from the orchestrator
+            let mut payload = Payload::new();
+            self.refuse(reason, &mut payload);
+            payload.insert("Response".into(), planet_to_explorer_name(&response));
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::new(
+                    Some(planet_actor(state.id())),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::MessagePlanetToExplorer,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+
+            self.record(
+                Direction::ExplorerToPlanet,
+                msg_name,
+                Some(planet_to_explorer_name(&response)),
+            );
+            self.tap_explorer(state.id(), explorer_id, &response);
+            return Some(response);
+        }
+
+        self.settle_guards(state, &msg);
+        #[cfg(feature = "dry-run")]
+        if self.config.dry_run && !is_read_only(&msg) {
+            return Some(self.serve_dry_run(state, generator, combinator, msg, msg_name));
+        }
+
+        let decision = self.decide(state, generator, combinator, &msg);
+        let from_escrow = decision.is_ok()
+            && matches!(msg, ExplorerToPlanet::GenerateResourceRequest { resource, .. }
+                if self.escrow_holds(explorer_id, resource));
+        let asked_strategy = match decision {
+            Ok(()) => !from_escrow,
+            Err(OrbitronError::Declined | OrbitronError::NoChargedCell) => true,
+            Err(_) => false,
+        };
+        if asked_strategy {
+            let served = decision != Err(OrbitronError::Declined);
+            self.compare_with_shadow(&msg, charged_cells(state), served);
+        }
+
+        // LOG explorer message result
+        let mut payload = Payload::new();
+
+        let response = match msg {
+            ExplorerToPlanet::SupportedResourceRequest { explorer_id: _id } => {
+                payload.insert(
+                    "Supported Resources".into(),
+                    format!("{:?}", sorted_basic(&generator.all_available_recipes())),
+                );
+
+                Some(PlanetToExplorer::SupportedResourceResponse {
+                    resource_list: generator.all_available_recipes(),
+                })
+            }
+            ExplorerToPlanet::SupportedCombinationRequest { explorer_id: _id } => {
+                payload.insert(
+                    "Supported Combinations".into(),
+                    format!("{:?}", sorted_complex(&combinator.all_available_recipes())),
+                );
+
+                Some(PlanetToExplorer::SupportedCombinationResponse {
+                    combination_list: combinator.all_available_recipes(),
+                })
+            }
+            ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id: _id,
+                resource,
+            } => match decision {
+                Ok(()) if self.escrow_holds(explorer_id, resource) => {
+                    // Already made, so handing it over spends nothing defensive
+                    // mode protects
+                    let held = self.take_from_escrow(explorer_id, resource);
+                    payload.insert(
+                        "Generated Resource".into(),
+                        format!("Delivered from escrow: {:?}", held),
+                    );
+
+                    Some(PlanetToExplorer::GenerateResourceResponse { resource: held })
+                }
+                Err(
+                    err @ (OrbitronError::UnsupportedResource(_) | OrbitronError::NoChargedCell),
+                ) => {
+                    // The response has no room for an error, so its code is only logged
+                    if err == OrbitronError::NoChargedCell {
+                        self.refuse(RefusalReason::NoChargedCell, &mut payload);
+                    }
+                    increment(&mut self.generations_failed);
+                    payload.insert("Error".into(), err.kind().into());
+                    payload.insert("ErrorCode".into(), err.code().to_string());
+                    payload.insert(
+                        "Generated Resource".into(),
+                        "Unsupported Resource Generation Request".into(),
+                    );
+                    let supported = generator.all_available_recipes();
+                    if self.config.suggest_alternatives
+                        && !supported.contains(&resource)
+                        && let Some(suggested) = self.suggest(resource, &supported)
+                    {
+                        payload.insert("Suggested".into(), format!("{suggested:?}"));
+                        self.suggestions.insert(explorer_id, suggested);
+                    }
+
+                    Some(PlanetToExplorer::GenerateResourceResponse { resource: None })
+                }
+                Err(err) => {
+                    if let Some(reason) = RefusalReason::for_error(&err) {
+                        self.refuse(reason, &mut payload);
+                    }
+                    let why = match err {
+                        OrbitronError::Paused => "generation is paused",
+                        OrbitronError::NoCells => NO_CELLS_ERROR,
+                        OrbitronError::Defensive => "planet is in defensive mode",
+                        OrbitronError::RateLimited => "in-flight cap reached",
+                        OrbitronError::ProductionImbalance(_) => "production imbalance",
+                        OrbitronError::Declined => "declined by strategy",
+                        _ => err.kind(),
+                    };
+                    payload.insert(
+                        "Generated Resource".into(),
+                        format!("Refused {resource:?} generation: {why}"),
+                    );
+                    if let OrbitronError::ProductionImbalance(suggested) = err {
+                        payload.insert("Suggested".into(), format!("{suggested:?}"));
+                        self.suggestions.insert(explorer_id, suggested);
+                    }
+
+                    Some(PlanetToExplorer::GenerateResourceResponse { resource: None })
+                }
+                Ok(()) => {
+                    match resource {
+                        BasicResourceType::Hydrogen if charged_cells(state) >= 2 => {
+                            self.note_batch_candidate(state.id(), explorer_id);
+                        }
+                        BasicResourceType::Oxygen => {
+                            self.fast_tracked.remove(&explorer_id);
+                        }
+                        _ => {}
+                    }
+                    let (result, errors) = generate_with_retry(
+                        generator,
+                        state.cells_iter_mut(),
+                        resource,
+                        self.config.retry_on_generator_error,
+                    );
+                    self.note_generator_errors(
+                        state.id(),
+                        explorer_id,
+                        &errors,
+                        result.is_ok(),
+                        &mut payload,
+                    );
+                    let mut generated_resource = result
+                        .inspect_err(|err| {
+                            payload.insert("Error".into(), format!("{err:?}"));
+                            payload.insert("ErrorCode".into(), err.code().to_string());
+                        })
+                        .ok();
+                    if generated_resource.is_some() {
+                        // Only a spent cell counts towards the in-flight cap
+                        self.generations_this_tick += 1;
+                        increment(&mut self.generations_succeeded);
+                        increment(&mut self.round.produced);
+                        self.check_low_charge(state);
+                        self.note_water_input(resource);
+                        self.clear_combine_failures(state.id(), explorer_id);
+                        payload.insert(
+                            "Generated Resource".into(),
+                            format!("{:?}", generated_resource),
+                        );
+                        if self.config.escrow_capacity > 0 {
+                            if let Some(held) = generated_resource.take() {
+                                self.put_in_escrow(state.id(), explorer_id, resource, held);
+                            }
+                            payload.insert("Escrow".into(), "Held for explorer".into());
+                        }
+                    } else {
+                        increment(&mut self.generations_failed);
+                        payload.insert(
+                            "Generated Resource".into(),
+                            "Unsupported Resource Generation Request".into(),
+                        );
+                    }
+
+                    Some(PlanetToExplorer::GenerateResourceResponse {
+                        resource: generated_resource,
+                    })
+                }
+            },
+            ExplorerToPlanet::CombineResourceRequest {
+                explorer_id: _id,
+                msg,
+            } => match decision {
+                Err(
+                    OrbitronError::UnsupportedCombination(_)
+                    | OrbitronError::UnsupportedRecipe { .. },
+                ) if self.config.unknown_recipe_policy == UnknownRecipePolicy::Ignore => {
+                    payload.insert(
+                        "Combined Resource".into(),
+                        format!(
+                            "Ignored request for unknown recipe {:?}",
+                            request_type(&msg)
+                        ),
+                    );
+                    None
+                }
+                Err(
+                    err @ (OrbitronError::UnsupportedCombination(_)
+                    | OrbitronError::UnsupportedRecipe { .. }
+                    | OrbitronError::NoChargedCell),
+                ) => {
+                    // Failed rather than turned down, so it counts towards back-off
+                    if err == OrbitronError::NoChargedCell {
+                        self.refuse(RefusalReason::NoChargedCell, &mut payload);
+                    }
+                    self.record_combine_failure(state.id(), explorer_id);
+                    let (resource_1, resource_2) = request_inputs(msg);
+                    payload.insert("Error".into(), err.kind().into());
+                    payload.insert("ErrorCode".into(), err.code().to_string());
+                    let ret = Err((
+                        err.protocol_text(self.config.error_codes),
+                        resource_1,
+                        resource_2,
+                    ));
+                    payload.insert(
+                        "Combined Resource".into(),
+                        format!("Unsupported Resource Combination Request: {:?}", ret),
+                    );
+
+                    Some(PlanetToExplorer::CombineResourceResponse {
+                        complex_response: ret,
+                    })
+                }
+                Err(err) => {
+                    if let Some(reason) = RefusalReason::for_error(&err) {
+                        self.refuse(reason, &mut payload);
+                    }
+                    Some(refused_combination(
+                        msg,
+                        &err,
+                        self.config.error_codes,
+                        &mut payload,
+                    ))
+                }
+                Ok(()) => {
+                    // Inputs need no validation here: each ComplexResourceRequest
+                    // variant carries the exact input types of its recipe, so a
+                    // request with wrong inputs can't be built. Recipes this planet
+                    // lacks have no combiner and never touch a cell.
+                    let recipe = request_type(&msg);
+                    let cell = state.full_cell();
+
+                    let ret: Result<
+                        ComplexResource,
+                        (OrbitronError, GenericResource, GenericResource),
+                    > = match recipes::combiner(recipe)
+                        .filter(|_| combinator.all_available_recipes().contains(&recipe))
+                    {
+                        Some(make) => match cell {
+                            Some((cell, _)) => make(combinator, msg, cell).map_err(
+                                |(err_str, return_resource_1, return_resource_2)| {
+                                    (
+                                        OrbitronError::GeneratorFailure(err_str),
+                                        return_resource_1,
+                                        return_resource_2,
+                                    )
+                                },
+                            ),
+                            None => {
+                                self.refuse(RefusalReason::NoChargedCell, &mut payload);
+                                let (resource_1, resource_2) = request_inputs(msg);
+                                Err((OrbitronError::NoChargedCell, resource_1, resource_2))
+                            }
+                        },
+                        None => {
+                            let (resource_1, resource_2) = request_inputs(msg);
+                            let err = self
+                                .unsupported_recipe(recipe, &combinator.all_available_recipes());
+                            Err((err, resource_1, resource_2))
+                        }
+                    };
+                    // Errors only become strings at the protocol boundary
+                    let ret = ret.map_err(|(err, resource_1, resource_2)| {
+                        payload.insert("Error".into(), err.kind().into());
+                        payload.insert("ErrorCode".into(), err.code().to_string());
+                        (
+                            err.protocol_text(self.config.error_codes),
+                            resource_1,
+                            resource_2,
+                        )
+                    });
+                    if ret.is_ok() {
+                        if recipe == ComplexResourceType::Water {
+                            self.unconsumed_hydrogen = self.unconsumed_hydrogen.saturating_sub(1);
+                            self.unconsumed_oxygen = self.unconsumed_oxygen.saturating_sub(1);
+                        }
+                        self.combines_this_tick += 1;
+                        increment(self.complex_generated.entry(recipe).or_insert(0));
+                        increment(&mut self.round.produced);
+                        self.check_low_charge(state);
+                        self.clear_combine_failures(state.id(), explorer_id);
+                        payload.insert("Combined Resource".into(), format!("{:?}", ret));
+                    } else {
+                        self.record_combine_failure(state.id(), explorer_id);
+                        payload.insert(
+                            "Combined Resource".into(),
+                            format!("Unsupported Resource Combination Request: {:?}", ret),
+                        );
+                    }
+
+                    Some(PlanetToExplorer::CombineResourceResponse {
+                        complex_response: ret,
+                    })
+                }
+            },
+            ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: _id } => {
+                let mut cnt = charged_cells(state);
+                if self.config.reserve_cells_for_queued {
+                    let pending = self.pending_explorer_count();
+                    payload.insert("Charged Energy Cells".into(), format!("{:?}", cnt));
+                    payload.insert("Queued Explorer Messages".into(), pending.to_string());
+                    cnt = unreserved_cells(cnt, pending);
+                }
+                payload.insert("Available Energy Cells".into(), format!("{:?}", cnt));
+
+                Some(PlanetToExplorer::AvailableEnergyCellResponse {
+                    available_cells: cnt,
+                })
+            }
+        };
+
+        // LOG planet response, as a failure when it is a refusal or an error
+        let category = if payload.contains_key("Error") || payload.contains_key("Refusal Reason") {
+            LogCategory::Failures
+        } else {
+            LogCategory::ExplorerTraffic
+        };
+        if let Some(ref res) = response
+            && coalesced
+        {
+            payload.insert("Response".into(), planet_to_explorer_name(res));
+            self.coalesce_supported_request(explorer_id, payload);
+        } else if let Some(ref res) = response {
+            payload.insert("Response".into(), planet_to_explorer_name(res));
+            self.emit(
+                category,
+                LogEvent::new(
+                    Some(planet_actor(state.id())),
+                    Some(explorer_actor(explorer_id)),
+                    EventType::MessagePlanetToExplorer,
+                    ACK_MSG_CHNL,
+                    payload,
+                ),
+            );
+        }
+
+        self.record(
+            Direction::ExplorerToPlanet,
+            msg_name,
+            response.as_ref().map(planet_to_explorer_name),
+        );
+        if let Some(ref res) = response {
+            self.tap_explorer(state.id(), explorer_id, res);
+        }
+        self.publish(state.id());
+        response
+    }
+}
+
+/// Answers an explorer message through [Orbitron::serve_explorer].
+///
+/// With `batch_responses` set, answers to explorers registered in the
+/// [ExplorerSenders](crate::ai::orbitron::ExplorerSenders) are held until
+/// the next Sunray and `None` is returned.
+///
+/// `PlanetAI::handle_explorer_msg` delegates here. Being a free function
+/// over the planet's parts, it's also what the fuzz targets drive.
+pub fn process_explorer_msg(
+    ai: &mut Orbitron,
+    state: &mut PlanetState,
+    generator: &Generator,
+    combinator: &Combinator,
+    msg: ExplorerToPlanet,
+) -> Option<PlanetToExplorer> {
+    let explorer_id = msg.explorer_id();
+    let response = ai.serve_explorer(state, generator, combinator, msg)?;
+    if !ai.config.batch_responses || !ai.explorer_senders.contains(explorer_id) {
+        return Some(response);
+    }
+    ai.batched.push((explorer_id, response));
+    None
+}
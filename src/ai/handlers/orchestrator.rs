@@ -0,0 +1,703 @@
+//! # Orbitron – Orchestrator messages
+//!
+//! Lifecycle transitions, Sunrays and internal state requests.
+use common_game::components::planet::{DummyPlanetState, PlanetState};
+use common_game::components::resource::{Combinator, Generator};
+use common_game::components::sunray::Sunray;
+use common_game::logging::*;
+use common_game::utils::ID;
+use crossbeam_channel::Receiver;
+
+use crate::ai::config::{ChargeOrder, LogCategory, RoundBoundary};
+use crate::ai::error::NO_CELLS_ERROR;
+use crate::ai::handlers::explorer::BASIC_RESOURCES;
+use crate::ai::logging::{
+    ACK_MSG_CHNL, RCV_MSG_CHNL, explorer_actor, orchestrator_actor, planet_actor,
+};
+use crate::ai::orbitron::{CellView, Lifecycle, Orbitron, charged_cells, is_saturated};
+use crate::ai::stats::{Direction, RoundStats, increment};
+use crate::ai::watchdog::Watchdog;
+
+/// What a Sunray did to the planet's energy cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChargeOutcome {
+    /// The cell at this index took the Sunray.
+    Charged(usize),
+    /// Every cell was already charged.
+    AllFull,
+    /// The planet has no energy cell at all.
+    NoCells,
+}
+
+impl ChargeOutcome {
+    /// Name used in log payloads.
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ChargeOutcome::Charged(_) => "charged",
+            ChargeOutcome::AllFull => "all_full",
+            ChargeOutcome::NoCells => "no_cells",
+        }
+    }
+}
+
+/// Cell the next Sunray goes to: the first empty one, starting after
+/// `last_charged` when charging round-robin.
+pub(crate) fn charge_target(
+    cells: &impl CellView,
+    order: ChargeOrder,
+    last_charged: Option<usize>,
+) -> ChargeOutcome {
+    let total = cells.total_cells();
+    if total == 0 {
+        return ChargeOutcome::NoCells;
+    }
+    let start = match order {
+        ChargeOrder::Default => 0,
+        ChargeOrder::RoundRobin => last_charged.map_or(0, |index| index + 1),
+    };
+    (0..total)
+        .map(|offset| (start + offset) % total)
+        .find(|index| !cells.is_cell_charged(*index))
+        .map_or(ChargeOutcome::AllFull, ChargeOutcome::Charged)
+}
+
+impl Orbitron {
+    /// Moves to [Lifecycle::Running], logging the start.
+    ///
+    /// Returns whether the lifecycle changed; starting a running AI only
+    /// logs that the start was ignored.
+    pub(crate) fn start(&mut self, planet_id: ID) -> bool {
+        if self.lifecycle == Lifecycle::Running {
+            self.log_ignored_transition(planet_id, "start ignored: already running");
+            return false;
+        }
+        let restarting = self.lifecycle == Lifecycle::Stopped;
+        self.lifecycle = Lifecycle::Running;
+
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "Started Planet Orbitron".into());
+
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::new(
+                Some(orchestrator_actor()),
+                Some(planet_actor(planet_id)),
+                EventType::MessageOrchestratorToPlanet,
+                RCV_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        if restarting {
+            self.reset_transient();
+        }
+        if self.metadata_logged == 0 {
+            self.log_metadata(planet_id);
+        }
+        self.spawn_watchdog(planet_id);
+        self.publish(planet_id);
+        true
+    }
+
+    /// Starts the watchdog, unless it is disabled or already running.
+    ///
+    /// The watchdog thread logs through a clone of the AI's sinks, so a
+    /// stall is reported even while the AI itself is never called.
+    pub(crate) fn spawn_watchdog(&mut self, planet_id: ID) {
+        let Some(timeout) = self.config.watchdog_timeout else {
+            return;
+        };
+        if self.watchdog.is_some() {
+            return;
+        }
+        self.heartbeat.beat();
+        let queue = self.explorer_queue.clone();
+        let pending = move || queue.as_ref().map_or(0, Receiver::len);
+        let busy = pending.clone();
+        let logs = self.logs.clone();
+        let enabled = self.logs_enabled(LogCategory::Failures, Channel::Error);
+        self.watchdog = Some(Watchdog::spawn(
+            timeout,
+            self.heartbeat.clone(),
+            move || busy() > 0,
+            move |stalled| {
+                if !enabled {
+                    return;
+                }
+                // LOG wedged run loop
+                let mut payload = Payload::new();
+                payload.insert("event".into(), "watchdog_stall".into());
+                payload.insert("stalled_ms".into(), stalled.as_millis().to_string());
+                payload.insert("timeout_ms".into(), timeout.as_millis().to_string());
+                payload.insert("pending_explorers".into(), pending().to_string());
+                logs.emit(LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Error,
+                    payload,
+                ));
+            },
+        ));
+    }
+
+    /// Moves to [Lifecycle::Stopped], logging the stop and the shutdown summary.
+    ///
+    /// Returns whether the lifecycle changed; stopping a stopped AI only
+    /// logs that the stop was ignored.
+    pub(crate) fn stop(&mut self, planet_id: ID) -> bool {
+        if self.lifecycle != Lifecycle::Running {
+            self.log_ignored_transition(planet_id, "stop ignored: already stopped");
+            return false;
+        }
+        self.lifecycle = Lifecycle::Stopped;
+
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "Stoped Planet Orbitron".into());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::new(
+                Some(orchestrator_actor()),
+                Some(planet_actor(planet_id)),
+                EventType::MessageOrchestratorToPlanet,
+                RCV_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        // LOG shutdown summary
+        let mut payload = self.summary_payload(planet_id);
+        payload.insert("Message".into(), "Shutdown Summary".into());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Info,
+                payload,
+            ),
+        );
+        self.publish(planet_id);
+        true
+    }
+
+    /// Drops the state that only makes sense while the AI keeps running,
+    /// when it is started again after a `StopPlanetAI`:
+    ///
+    /// - answers held for the next Sunray, which are delivered now,
+    /// - combination back-off of every explorer,
+    /// - defensive mode,
+    /// - the generation and combination counts of the current tick,
+    /// - fast-tracked explorers and the Water balance,
+    /// - the last Sunray seen, so the first one isn't taken for a duplicate.
+    ///
+    /// Lifetime statistics, the tick, escrowed resources and the explorer
+    /// registry are kept. The subsystems that held something are logged.
+    pub(crate) fn reset_transient(&mut self) {
+        let planet_id = self.planet_id;
+        let mut cleared = Vec::new();
+        if !self.batched.is_empty() {
+            self.flush_batched(planet_id);
+            cleared.push("batched_responses");
+        }
+        if self.supported_requests.is_some() {
+            self.flush_supported_requests(planet_id);
+            cleared.push("supported_requests");
+        }
+        if !self.combine_failures.is_empty() {
+            self.combine_failures.clear();
+            cleared.push("backoff");
+        }
+        if self.defensive.take().is_some() {
+            cleared.push("defensive_mode");
+        }
+        if self.generations_this_tick > 0 || self.combines_this_tick > 0 {
+            self.generations_this_tick = 0;
+            self.combines_this_tick = 0;
+            cleared.push("rate_limits");
+        }
+        if !self.fast_tracked.is_empty()
+            || self.unconsumed_hydrogen > 0
+            || self.unconsumed_oxygen > 0
+        {
+            self.fast_tracked.clear();
+            self.unconsumed_hydrogen = 0;
+            self.unconsumed_oxygen = 0;
+            cleared.push("fairness");
+        }
+        if self.last_sunray_at.take().is_some() {
+            cleared.push("duplicate_sunray_check");
+        }
+
+        // LOG restart reset
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "transient_state_reset".into());
+        payload.insert("cleared".into(), cleared.join(","));
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Info,
+                payload,
+            ),
+        );
+    }
+
+    /// Flags the incoming Sunray if nothing was handled since the last one.
+    ///
+    /// A [Sunray] carries no identifying data, so back-to-back Sunrays are
+    /// the only hint of an orchestrator resending one after a timeout.
+    pub(crate) fn check_duplicate_sunray(&mut self, planet_id: ID) {
+        let back_to_back = self.last_sunray_at.is_some_and(|at| self.age_of(at) == 0);
+        if !self.config.flag_duplicate_sunrays || !back_to_back {
+            return;
+        }
+        increment(&mut self.suspected_duplicate_sunrays);
+
+        // LOG possible duplicate
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), "possible_duplicate".into());
+        payload.insert(
+            "Suspected Duplicates".into(),
+            self.suspected_duplicate_sunrays.to_string(),
+        );
+        self.emit(
+            LogCategory::OrchestratorTraffic,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Warns once the charged cells drop below
+    /// [OrbitronConfig::low_energy_threshold](crate::ai::config::OrbitronConfig::low_energy_threshold),
+    /// and again only after they went back up to it.
+    ///
+    /// Called as a tick starts, before the Sunray charges a cell. The
+    /// protocol has no message asking the orchestrator for more Sunrays, so
+    /// the warning says so instead of sending one.
+    pub(crate) fn check_low_energy(&mut self, state: &PlanetState) {
+        let threshold = self.config.low_energy_threshold;
+        let charged = charged_cells(state);
+        if charged >= threshold {
+            self.low_energy = false;
+            return;
+        }
+        if self.low_energy {
+            return;
+        }
+        self.low_energy = true;
+
+        // LOG low energy
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "low_energy".into());
+        payload.insert("charged_cells".into(), charged.to_string());
+        payload.insert("threshold".into(), threshold.to_string());
+        payload.insert("tick".into(), self.tick.to_string());
+        payload.insert("sunray_request".into(), "unsupported by protocol".into());
+        self.emit(
+            LogCategory::OrchestratorTraffic,
+            LogEvent::self_directed(
+                planet_actor(state.id()),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Charges a cell with `sunray` in the
+    /// [charge order](crate::ai::config::OrbitronConfig::charge_order). The
+    /// Sunray is dropped unless a cell took it.
+    pub(crate) fn charge(&mut self, state: &mut PlanetState, sunray: Sunray) -> ChargeOutcome {
+        let outcome = charge_target(state, self.config.charge_order, self.last_charged);
+        if let ChargeOutcome::Charged(index) = outcome {
+            state.cell_mut(index).charge(sunray);
+            self.last_charged = Some(index);
+        }
+        outcome
+    }
+
+    /// Whether the planet is destroyed, counting `message` as received after
+    /// the destruction if so.
+    ///
+    /// Only the first such message is logged, as a dead planet warning.
+    pub(crate) fn is_destroyed(&mut self, planet_id: ID, message: &str) -> bool {
+        if self.lifecycle != Lifecycle::Destroyed {
+            return false;
+        }
+        increment(&mut self.messages_after_destruction);
+
+        if !self.dead_planet_logged {
+            self.dead_planet_logged = true;
+            let mut payload = Payload::new();
+            payload.insert("event".into(), "dead_planet".into());
+            payload.insert("Message".into(), message.into());
+            payload.insert("Result".into(), "Ignored, planet destroyed".into());
+            self.emit(
+                LogCategory::Lifecycle,
+                LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+        self.publish(planet_id);
+        true
+    }
+
+    /// Warns, once per sender, about `message` reaching the planet before its
+    /// first start. `explorer_id` is `None` for the orchestrator.
+    ///
+    /// Unreachable under the stock `common_game` planet loop, which answers
+    /// everything but `StartPlanetAI` itself until the AI is started. It
+    /// fires only for callers driving the AI directly, such as
+    /// [process_explorer_msg](crate::ai::handlers::explorer::process_explorer_msg).
+    pub(crate) fn check_premature_contact(
+        &mut self,
+        planet_id: ID,
+        explorer_id: Option<ID>,
+        message: &str,
+    ) {
+        if self.lifecycle != Lifecycle::Created || !self.premature_contacts.insert(explorer_id) {
+            return;
+        }
+        increment(&mut self.protocol_anomalies);
+
+        let sender = match explorer_id {
+            Some(id) => explorer_actor(id),
+            None => orchestrator_actor(),
+        };
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "premature_contact".into());
+        payload.insert("Message".into(), message.into());
+        self.emit(
+            LogCategory::Failures,
+            LogEvent::new(
+                Some(sender),
+                Some(planet_actor(planet_id)),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Whether the orchestrator is gone, logging it the first time.
+    ///
+    /// Nothing the planet produces can reach the game any more, so cells
+    /// are no longer spent on explorers.
+    pub(crate) fn is_orchestrator_unreachable(&mut self, planet_id: ID) -> bool {
+        if !self.orchestrator_link.is_unreachable() {
+            return false;
+        }
+        if !self.unreachable_logged {
+            self.unreachable_logged = true;
+            let mut payload = Payload::new();
+            payload.insert("event".into(), "orchestrator_unreachable".into());
+            self.emit(
+                LogCategory::Failures,
+                LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+        true
+    }
+
+    /// Moves to the terminal [Lifecycle::Destroyed] state.
+    pub(crate) fn destroy(&mut self, planet_id: ID, cause: &str) {
+        self.lifecycle = Lifecycle::Destroyed;
+
+        // LOG planet destruction
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "PlanetDestroyed".into());
+        payload.insert("cause".into(), cause.into());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Warning,
+                payload,
+            ),
+        );
+    }
+
+    /// Starts a new tick, resetting every per-tick limit.
+    pub(crate) fn on_tick(&mut self) {
+        increment(&mut self.tick);
+        self.generations_this_tick = 0;
+        self.combines_this_tick = 0;
+    }
+
+    /// Discharges every energy cell, whatever its charge, to set up a test
+    /// scenario from an empty planet. Only available in tests and with the
+    /// `test-util` feature.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn discharge_all(&mut self, state: &mut PlanetState) {
+        let discharged = state
+            .cells_iter_mut()
+            .filter_map(|cell| cell.discharge().ok())
+            .count();
+        self.charged_since.clear();
+
+        // LOG forced discharge
+        let mut payload = Payload::new();
+        payload.insert("event".into(), "discharge_all".into());
+        payload.insert("discharged".into(), discharged.to_string());
+        payload.insert("cells".into(), state.cells_count().to_string());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(state.id()),
+                EventType::InternalPlanetAction,
+                Channel::Debug,
+                payload,
+            ),
+        );
+        self.publish(state.id());
+    }
+
+    /// Discharges cells left unused for longer than
+    /// [OrbitronConfig::cell_decay_ticks].
+    ///
+    /// Cells are only looked at on Sunrays, so a charge is dated to the first
+    /// tick it was seen; a cell spent in between is dropped from the map
+    /// because it is found empty.
+    pub(crate) fn decay_cells(&mut self, state: &mut PlanetState) {
+        let Some(limit) = self.config.cell_decay_ticks else {
+            return;
+        };
+        for index in 0..state.cells_count() {
+            if !state.cell(index).is_charged() {
+                self.charged_since.remove(&index);
+                continue;
+            }
+            let since = *self.charged_since.entry(index).or_insert(self.tick);
+            if self.tick - since <= u64::from(limit) {
+                continue;
+            }
+            let _ = state.cell_mut(index).discharge();
+            self.charged_since.remove(&index);
+
+            // LOG decayed cell
+            let mut payload = Payload::new();
+            payload.insert("event".into(), "cell_decayed".into());
+            payload.insert("cell".into(), index.to_string());
+            payload.insert("charged_at_tick".into(), since.to_string());
+            self.emit(
+                LogCategory::OrchestratorTraffic,
+                LogEvent::self_directed(
+                    planet_actor(state.id()),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+    }
+
+    /// Charges a cell with `sunray`, see `PlanetAI::handle_sunray`.
+    pub(crate) fn serve_sunray(&mut self, state: &mut PlanetState, sunray: Sunray) {
+        #[cfg(feature = "metrics")]
+        let _timer = self.timings.start("Sunray");
+        self.begin_exchange();
+        self.flush_batched(state.id());
+        self.flush_supported_requests(state.id());
+        if self.is_destroyed(state.id(), "Sunray") {
+            return;
+        }
+        self.check_premature_contact(state.id(), None, "Sunray");
+        self.on_tick();
+        if let RoundBoundary::OnEveryNSunrays(n) = self.config.round_boundary
+            && n > 0
+            && self.sunrays_this_round >= n
+        {
+            self.end_round();
+        }
+        self.sunrays_this_round = self.sunrays_this_round.saturating_add(1);
+        self.check_low_energy(state);
+        self.check_duplicate_sunray(state.id());
+        self.record(
+            Direction::OrchestratorToPlanet,
+            "Sunray",
+            Some("SunrayAck".into()),
+        );
+        self.tap(state.id(), None, "SunrayAck", String::new());
+        self.last_sunray_at = Some(self.logical_time);
+        let mut payload = Payload::new();
+
+        // A cell is either charged or not: one Sunray fills it, and a Sunray
+        // finding every cell charged goes unused.
+        let outcome = self.charge(state, sunray);
+        payload.insert("charge_outcome".into(), outcome.as_str().into());
+        match outcome {
+            ChargeOutcome::NoCells => {
+                payload.insert("Energy Cell State".into(), NO_CELLS_ERROR.into());
+                self.emit(
+                    LogCategory::Failures,
+                    LogEvent::self_directed(
+                        planet_actor(state.id()),
+                        EventType::InternalPlanetAction,
+                        Channel::Warning,
+                        payload,
+                    ),
+                );
+                self.defensive_sunray(state.id());
+                self.publish(state.id());
+                return;
+            }
+            ChargeOutcome::AllFull => {
+                payload.insert("Energy Cell State".into(), "Energy Cell full".into());
+                payload.insert("charge_events".into(), "sunray returned".into());
+                increment(&mut self.sunrays_wasted);
+
+                // LOG wasted sunray
+                let mut warning = Payload::new();
+                warning.insert("event".into(), "SunrayWasted".into());
+                warning.insert("tick".into(), self.tick.to_string());
+                warning.insert("sunrays_wasted".into(), self.sunrays_wasted.to_string());
+                self.emit(
+                    LogCategory::OrchestratorTraffic,
+                    LogEvent::self_directed(
+                        planet_actor(state.id()),
+                        EventType::InternalPlanetAction,
+                        Channel::Warning,
+                        warning,
+                    ),
+                );
+            }
+            ChargeOutcome::Charged(index) => {
+                payload.insert("Energy Cell State".into(), "Energy Cell charged".into());
+                increment(self.charge_events.entry(index).or_default());
+                increment(&mut self.round.sunrays_absorbed);
+                payload.insert("charge_events".into(), format!("cell {index}"));
+            }
+        }
+        self.decay_cells(state);
+        self.defensive_sunray(state.id());
+        self.check_low_charge(state);
+        // For the orchestrator's capacity planning: the ack itself only
+        // carries the planet id
+        payload.insert("charged_cells".into(), charged_cells(state).to_string());
+        payload.insert("total_cells".into(), state.cells_count().to_string());
+        payload.insert("saturated".into(), is_saturated(state).to_string());
+        payload.insert("sunrays_wasted".into(), self.sunrays_wasted.to_string());
+
+        // LOG incoming sunray handle
+        self.emit(
+            LogCategory::OrchestratorTraffic,
+            LogEvent::broadcast(
+                planet_actor(state.id()),
+                EventType::InternalPlanetAction,
+                RCV_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        self.publish(state.id());
+    }
+
+    /// Reports the planet state, see `PlanetAI::handle_internal_state_req`.
+    pub(crate) fn serve_internal_state(&mut self, state: &mut PlanetState) -> DummyPlanetState {
+        self.begin_exchange();
+        self.check_premature_contact(state.id(), None, "InternalStateRequest");
+        let mut payload = Payload::new();
+
+        payload.insert("Planet State".into(), format!("{:?}", state.to_dummy()));
+        self.insert_asteroid_counts(&mut payload);
+        let held = self.inventory_summary();
+        let inventory: Vec<String> = BASIC_RESOURCES
+            .iter()
+            .filter_map(|resource| {
+                held.get(resource)
+                    .map(|count| format!("{resource:?}: {count}"))
+            })
+            .collect();
+        payload.insert("Inventory".into(), inventory.join(", "));
+        payload.insert(
+            "changed_fields".into(),
+            self.state_changes(&*state).join(", "),
+        );
+        payload.insert("Config".into(), self.config.to_string());
+        let mut requests: Vec<String> = self
+            .explorer_requests
+            .iter()
+            .map(|(name, count)| format!("{name}: {count}"))
+            .collect();
+        requests.sort();
+        payload.insert("Explorer Requests".into(), requests.join(", "));
+        // The request ends the round it arrives in, which is then the last one
+        let ends_round = self.config.round_boundary == RoundBoundary::OnInternalStateRequest;
+        let last_round = if ends_round {
+            Some(&self.round)
+        } else {
+            self.rounds.back()
+        };
+        payload.insert(
+            "Last Round".into(),
+            last_round.map_or("none".into(), RoundStats::to_string),
+        );
+
+        // LOG internal state response
+        self.emit(
+            LogCategory::OrchestratorTraffic,
+            LogEvent::new(
+                Some(planet_actor(state.id())),
+                Some(orchestrator_actor()),
+                EventType::MessagePlanetToOrchestrator,
+                ACK_MSG_CHNL,
+                payload,
+            ),
+        );
+
+        self.record(
+            Direction::OrchestratorToPlanet,
+            "InternalStateRequest",
+            Some("InternalStateResponse".into()),
+        );
+        self.tap(
+            state.id(),
+            None,
+            "InternalStateResponse",
+            format!("{:?}", state.to_dummy()),
+        );
+        if ends_round {
+            self.end_round();
+        }
+        self.publish(state.id());
+        state.to_dummy()
+    }
+}
+
+/// Charges a cell with `sunray`, see `PlanetAI::handle_sunray`, which
+/// delegates here.
+pub fn process_sunray(
+    ai: &mut Orbitron,
+    state: &mut PlanetState,
+    _generator: &Generator,
+    _combinator: &Combinator,
+    sunray: Sunray,
+) {
+    ai.serve_sunray(state, sunray);
+}
+
+/// Reports the planet state, see `PlanetAI::handle_internal_state_req`,
+/// which delegates here.
+pub fn process_internal_state_req(
+    ai: &mut Orbitron,
+    state: &mut PlanetState,
+    _generator: &Generator,
+    _combinator: &Combinator,
+) -> DummyPlanetState {
+    ai.serve_internal_state(state)
+}
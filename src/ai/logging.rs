@@ -0,0 +1,548 @@
+//! # Orbitron – Logging
+//!
+//! Log participants, message names and the glue sending the AI's log events
+//! to the global logger, to routed sinks or to a test buffer.
+//!
+//! With the `tracing` feature every log event is also mirrored as a
+//! `tracing` event, and each handler runs inside a span named after it (see
+//! [handler_span]). Without the feature log events take the same path as
+//! before and nothing else is emitted.
+#[cfg(any(test, feature = "test-util"))]
+use std::sync::{Arc, Mutex};
+
+use common_game::logging::*;
+use common_game::protocols::planet_explorer::*;
+use common_game::utils::ID;
+use crossbeam_channel::Sender;
+
+use super::config::{LogCategory, config_payload};
+use super::orbitron::Orbitron;
+use super::stats::increment;
+
+/// Set channels for incoming/outgoing messages
+pub(crate) const RCV_MSG_CHNL: Channel = Channel::Debug;
+pub(crate) const ACK_MSG_CHNL: Channel = Channel::Debug;
+
+pub(crate) const ORCHESTRATOR_ID: ID = 0;
+
+/// Log participant standing for the planet `planet_id`.
+///
+/// Every log event names its actors through these helpers, so the same
+/// actor always comes with the same type and id.
+pub(crate) fn planet_actor(planet_id: ID) -> Participant {
+    Participant::new(ActorType::Planet, planet_id)
+}
+
+/// Log participant standing for the orchestrator.
+pub(crate) fn orchestrator_actor() -> Participant {
+    Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)
+}
+
+/// Log participant standing for the explorer `explorer_id`.
+pub(crate) fn explorer_actor(explorer_id: ID) -> Participant {
+    Participant::new(ActorType::Explorer, explorer_id)
+}
+
+/// Helper functions to convert messages and responses into string names
+pub(crate) fn explorer_to_planet_name(msg: &ExplorerToPlanet) -> String {
+    match msg {
+        ExplorerToPlanet::SupportedResourceRequest { .. } => "Supported Resource Request".into(),
+        ExplorerToPlanet::SupportedCombinationRequest { .. } => {
+            "Supported Combination Request".into()
+        }
+        ExplorerToPlanet::GenerateResourceRequest { .. } => "Generate Resource Request".into(),
+        ExplorerToPlanet::CombineResourceRequest { .. } => "Combine Resource Request".into(),
+        ExplorerToPlanet::AvailableEnergyCellRequest { .. } => {
+            "Available Energy Cell Request".into()
+        }
+    }
+}
+
+/// Helper functions to convert messages and responses into string names
+pub(crate) fn planet_to_explorer_name(msg: &PlanetToExplorer) -> String {
+    match msg {
+        PlanetToExplorer::SupportedResourceResponse { .. } => "Supported Resource Response".into(),
+        PlanetToExplorer::SupportedCombinationResponse { .. } => {
+            "Supported Combination Response".into()
+        }
+        PlanetToExplorer::GenerateResourceResponse { .. } => "Generate Resource Response".into(),
+        PlanetToExplorer::CombineResourceResponse { .. } => "Combine Resource Response".into(),
+        PlanetToExplorer::AvailableEnergyCellResponse { .. } => {
+            "Available Energy Cell Response".into()
+        }
+        _ => "Unexpected Message".into(),
+    }
+}
+
+/// Enters a span named after the handler `$name` for the rest of the
+/// enclosing block; expands to nothing without the `tracing` feature.
+macro_rules! handler_span {
+    ($name:literal, $planet_id:expr) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::info_span!($name, planet_id = $planet_id).entered();
+    };
+}
+pub(crate) use handler_span;
+
+/// Mirrors `event` as a `tracing` event at the level of its channel.
+///
+/// `seq` is the number of messages the AI had handled when `event` was
+/// logged, `None` for events logged outside the AI.
+#[cfg(feature = "tracing")]
+fn trace_event(event: &LogEvent, seq: Option<u64>) {
+    let actor = |participant: &Option<Participant>| {
+        participant.as_ref().map_or_else(
+            || "-".to_string(),
+            |participant| format!("{:?}:{}", participant.actor_type, participant.id),
+        )
+    };
+    let field = |keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| event.payload.get(*key))
+            .map_or("-", String::as_str)
+    };
+    let sender = actor(&event.sender);
+    let receiver = actor(&event.receiver);
+    let event_type = format!("{:?}", event.event_type);
+    let message = field(&["Message", "event"]);
+    let outcome = field(&["charge_outcome", "Result", "Response", "Error"]);
+    let seq = seq.map_or_else(|| "-".to_string(), |seq| seq.to_string());
+    macro_rules! mirror {
+        ($level:expr) => {
+            tracing::event!(
+                $level,
+                sender = sender.as_str(),
+                receiver = receiver.as_str(),
+                event_type = event_type.as_str(),
+                message_name = message,
+                outcome,
+                seq = seq.as_str(),
+            )
+        };
+    }
+    match event.channel {
+        Channel::Error => mirror!(tracing::Level::ERROR),
+        Channel::Warning => mirror!(tracing::Level::WARN),
+        Channel::Info => mirror!(tracing::Level::INFO),
+        Channel::Debug => mirror!(tracing::Level::DEBUG),
+        Channel::Trace => mirror!(tracing::Level::TRACE),
+    }
+}
+
+/// Sends `event` to the global logger, for events logged outside the AI.
+pub(crate) fn emit_global(event: LogEvent) {
+    #[cfg(feature = "tracing")]
+    trace_event(&event, None);
+    event.emit();
+}
+
+/// Copy of an answer produced by the AI, sent to a tap.
+///
+/// Resources and rockets can't be cloned, so the answer travels as its name
+/// and `Debug` text instead of the message itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TappedMessage {
+    pub planet_id: ID,
+    /// Explorer receiving the answer, `None` for the orchestrator.
+    pub explorer_id: Option<ID>,
+    pub name: String,
+    pub details: String,
+}
+
+/// Log event captured by a test logger, see [Orbitron::set_test_logger].
+#[cfg(any(test, feature = "test-util"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedEvent {
+    pub event_type: EventType,
+    pub channel: Channel,
+    pub payload: Payload,
+}
+
+/// Destinations of the AI's log events; a clone keeps logging to the same
+/// places from another thread.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LogSink {
+    /// Sinks taking log events away from the global logger, by event type.
+    routes: Vec<(EventType, Sender<LogEvent>)>,
+    /// See [Orbitron::set_test_logger].
+    #[cfg(any(test, feature = "test-util"))]
+    test_logger: Option<Arc<Mutex<Vec<CapturedEvent>>>>,
+}
+
+impl LogSink {
+    pub(crate) fn emit(&self, event: LogEvent) {
+        #[cfg(feature = "tracing")]
+        trace_event(&event, None);
+        self.deliver(event);
+    }
+
+    /// Sends `event` to its route, the test buffer or the global logger.
+    fn deliver(&self, event: LogEvent) {
+        if let Some((_, sink)) = self
+            .routes
+            .iter()
+            .find(|(routed, _)| *routed == event.event_type)
+        {
+            let _ = sink.try_send(event);
+            return;
+        }
+        #[cfg(any(test, feature = "test-util"))]
+        if let Some(buf) = &self.test_logger {
+            buf.lock().unwrap().push(CapturedEvent {
+                event_type: event.event_type,
+                channel: event.channel,
+                payload: event.payload,
+            });
+            return;
+        }
+        event.emit();
+    }
+}
+
+impl Orbitron {
+    /// Logs the startup banner: [planet_metadata](crate::planet_metadata),
+    /// the enabled crate features and every entry of [config_payload].
+    pub(crate) fn log_metadata(&mut self, planet_id: ID) {
+        let metadata = crate::planet_metadata();
+        let mut payload = config_payload(&self.config, planet_id);
+        payload.insert("ai_name".into(), metadata.ai_name.into());
+        payload.insert("version".into(), metadata.version.into());
+        payload.insert("planet_type".into(), format!("{:?}", metadata.planet_type));
+        payload.insert("gen_rules".into(), format!("{:?}", metadata.gen_rules));
+        payload.insert("comb_rules".into(), format!("{:?}", metadata.comb_rules));
+        let features: Vec<&str> = [
+            ("checkpoint", cfg!(feature = "checkpoint")),
+            ("dry-run", cfg!(feature = "dry-run")),
+            ("metrics", cfg!(feature = "metrics")),
+            ("test-util", cfg!(feature = "test-util")),
+            ("tokio", cfg!(feature = "tokio")),
+            ("tracing", cfg!(feature = "tracing")),
+        ]
+        .into_iter()
+        .filter_map(|(feature, enabled)| enabled.then_some(feature))
+        .collect();
+        payload.insert("features".into(), features.join(","));
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::broadcast(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Info,
+                payload,
+            ),
+        );
+        increment(&mut self.metadata_logged);
+    }
+
+    pub(crate) fn log_ignored_transition(&self, planet_id: ID, message: &str) {
+        let mut payload = Payload::new();
+        payload.insert("Message".into(), message.into());
+        self.emit(
+            LogCategory::Lifecycle,
+            LogEvent::self_directed(
+                planet_actor(planet_id),
+                EventType::InternalPlanetAction,
+                Channel::Debug,
+                payload,
+            ),
+        );
+    }
+
+    /// Logs every entry of [Orbitron::recent_history] as its own numbered
+    /// warning, oldest first.
+    pub(crate) fn dump_history(&self, planet_id: ID, trigger: &str) {
+        let total = self.history.len();
+        for (line, entry) in self.history.iter().enumerate() {
+            // LOG post-mortem history line
+            let mut payload = Payload::new();
+            payload.insert("event".into(), "history_dump".into());
+            payload.insert("trigger".into(), trigger.into());
+            payload.insert("line".into(), format!("{}/{total}", line + 1));
+            payload.insert("seq".into(), entry.seq.to_string());
+            payload.insert("entry_corr_id".into(), entry.corr_id.to_string());
+            payload.insert("direction".into(), format!("{:?}", entry.direction));
+            payload.insert("message".into(), entry.message.clone());
+            payload.insert("outcome".into(), entry.outcome.clone());
+            self.emit(
+                LogCategory::Asteroid,
+                LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+    }
+
+    /// Mirrors every answer to `sender`, for live monitoring.
+    ///
+    /// Sending never blocks: copies that don't fit in the channel, or that
+    /// nobody receives any more, are dropped.
+    pub fn set_tap(&mut self, sender: Sender<TappedMessage>) {
+        self.tap = Some(sender);
+    }
+
+    pub(crate) fn tap(&self, planet_id: ID, explorer_id: Option<ID>, name: &str, details: String) {
+        if let Some(tap) = &self.tap {
+            let _ = tap.try_send(TappedMessage {
+                planet_id,
+                explorer_id,
+                name: name.into(),
+                details,
+            });
+        }
+    }
+
+    pub(crate) fn tap_explorer(&self, planet_id: ID, explorer_id: ID, response: &PlanetToExplorer) {
+        self.tap(
+            planet_id,
+            Some(explorer_id),
+            &planet_to_explorer_name(response),
+            format!("{response:?}"),
+        );
+    }
+
+    /// Sends every log event of type `event_type` to `sink` instead of the
+    /// global logger. Later routes for the same type replace earlier ones;
+    /// types without a route keep going to the global logger.
+    ///
+    /// Events are dropped when `sink` is full or disconnected.
+    pub fn route_logs(&mut self, event_type: EventType, sink: Sender<LogEvent>) {
+        self.logs.routes.retain(|(routed, _)| *routed != event_type);
+        self.logs.routes.push((event_type, sink));
+    }
+
+    /// Starts a new exchange, from a message reaching a handler to its
+    /// answer, and returns its correlation id.
+    ///
+    /// Every event logged until the next exchange is tagged with it under
+    /// `corr_id`, and so is the exchange's [HistoryEntry].
+    pub(crate) fn begin_exchange(&mut self) -> u64 {
+        increment(&mut self.corr_id);
+        self.corr_id
+    }
+
+    /// Logs `event` unless [OrbitronConfig::log_verbosity] silences it for
+    /// `category` or its type isn't in [OrbitronConfig::enabled_event_types].
+    /// The event is tagged with the current round and, unless it already
+    /// has one, the `corr_id` of the current exchange first.
+    pub(crate) fn emit(&self, category: LogCategory, mut event: LogEvent) {
+        if !self.logs_enabled(category, event.channel)
+            || !self.config.enabled_event_types.contains(&event.event_type)
+        {
+            return;
+        }
+        event
+            .payload
+            .insert("round".into(), self.current_round.to_string());
+        event
+            .payload
+            .entry("corr_id".into())
+            .or_insert_with(|| self.corr_id.to_string());
+        self.events_emitted
+            .set(self.events_emitted.get().saturating_add(1));
+        #[cfg(feature = "tracing")]
+        trace_event(&event, Some(self.logical_time));
+        self.logs.deliver(event);
+    }
+
+    /// Whether an event of `category` on `channel` would be logged, so busy
+    /// paths can skip building its payload.
+    pub(crate) fn logs_enabled(&self, category: LogCategory, channel: Channel) -> bool {
+        self.config.log_verbosity.allows(category, channel)
+    }
+
+    /// Collects every log event without a route into `buf` instead of
+    /// sending it to the global logger. Only available in tests and with the
+    /// `test-util` feature.
+    #[cfg(any(test, feature = "test-util"))]
+    pub fn set_test_logger(&mut self, buf: Arc<Mutex<Vec<CapturedEvent>>>) {
+        self.logs.test_logger = Some(buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use common_game::components::resource::BasicResourceType;
+    #[cfg(feature = "tracing")]
+    use common_game::protocols::orchestrator_planet::OrchestratorToPlanet;
+
+    use super::*;
+
+    #[test]
+    fn test_explorer_to_planet_names() {
+        let cases = [
+            (
+                ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 },
+                "Supported Resource Request",
+            ),
+            (
+                ExplorerToPlanet::SupportedCombinationRequest { explorer_id: 1 },
+                "Supported Combination Request",
+            ),
+            (
+                ExplorerToPlanet::GenerateResourceRequest {
+                    explorer_id: 1,
+                    resource: BasicResourceType::Oxygen,
+                },
+                "Generate Resource Request",
+            ),
+            (
+                ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 },
+                "Available Energy Cell Request",
+            ),
+        ];
+        for (msg, name) in cases {
+            assert_eq!(explorer_to_planet_name(&msg), name);
+        }
+    }
+
+    #[test]
+    fn test_planet_to_explorer_names() {
+        let cases = [
+            (
+                PlanetToExplorer::SupportedResourceResponse {
+                    resource_list: HashSet::new(),
+                },
+                "Supported Resource Response",
+            ),
+            (
+                PlanetToExplorer::SupportedCombinationResponse {
+                    combination_list: HashSet::new(),
+                },
+                "Supported Combination Response",
+            ),
+            (
+                PlanetToExplorer::GenerateResourceResponse { resource: None },
+                "Generate Resource Response",
+            ),
+            (
+                PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 2 },
+                "Available Energy Cell Response",
+            ),
+            (PlanetToExplorer::Stopped, "Unexpected Message"),
+        ];
+        for (msg, name) in cases {
+            assert_eq!(planet_to_explorer_name(&msg), name);
+        }
+    }
+
+    /// Enclosing span of a recorded event, with its fields.
+    #[cfg(feature = "tracing")]
+    type RecordedEvent = (Option<String>, std::collections::HashMap<String, String>);
+
+    /// Spans, with their planet id, and events recorded by a `tracing`
+    /// subscriber.
+    #[cfg(feature = "tracing")]
+    #[derive(Clone, Default)]
+    struct Recorder {
+        spans: Arc<Mutex<Vec<(String, String)>>>,
+        events: Arc<Mutex<Vec<RecordedEvent>>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    struct Fields(std::collections::HashMap<String, String>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for Fields {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().into(), format!("{value:?}"));
+        }
+
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().into(), value.into());
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl<S> tracing_subscriber::Layer<S> for Recorder
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            _id: &tracing::span::Id,
+            _ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = Fields(Default::default());
+            attrs.record(&mut fields);
+            let planet_id = fields.0.remove("planet_id").unwrap_or_default();
+            self.spans
+                .lock()
+                .unwrap()
+                .push((attrs.metadata().name().into(), planet_id));
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut fields = Fields(Default::default());
+            event.record(&mut fields);
+            let span = ctx.event_span(event).map(|span| span.name().to_string());
+            self.events.lock().unwrap().push((span, fields.0));
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_sunray_is_traced() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        use crate::testkit::{TIMEOUT, sunray};
+
+        let (to_planet, rx_orch) = crossbeam_channel::unbounded();
+        let (tx_orch, from_planet) = crossbeam_channel::unbounded();
+        let (_explorer_tx, rx_expl) = crossbeam_channel::unbounded();
+        let mut planet = crate::create_planet(rx_orch, tx_orch, rx_expl, 7);
+
+        // The subscriber only sees the planet thread
+        let recorder = Recorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let thread = std::thread::spawn(move || {
+            tracing::subscriber::with_default(subscriber, || planet.run())
+        });
+        to_planet.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        from_planet.recv_timeout(TIMEOUT).unwrap();
+        to_planet
+            .send(OrchestratorToPlanet::Sunray(sunray()))
+            .unwrap();
+        from_planet.recv_timeout(TIMEOUT).unwrap();
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        thread.join().unwrap().unwrap();
+
+        let spans = recorder.spans.lock().unwrap();
+        assert_eq!(*spans, [("handle_sunray".to_string(), "7".to_string())]);
+        let events = recorder.events.lock().unwrap();
+        let sunray_events: Vec<_> = events
+            .iter()
+            .filter(|(span, _)| span.as_deref() == Some("handle_sunray"))
+            .map(|(_, fields)| fields)
+            .collect();
+        assert!(!sunray_events.is_empty());
+        let charged = sunray_events
+            .iter()
+            .find(|fields| fields["outcome"] == "charged")
+            .expect("the Sunray log event is mirrored");
+        assert_eq!(charged["sender"], "Planet:7");
+        assert_eq!(charged["receiver"], "-");
+        assert_eq!(charged["seq"], "1");
+        assert!(events.iter().all(|(_, fields)| {
+            [
+                "sender",
+                "receiver",
+                "event_type",
+                "message_name",
+                "outcome",
+                "seq",
+            ]
+            .iter()
+            .all(|field| fields.contains_key(*field))
+        }));
+    }
+}
@@ -19,69 +19,516 @@
 //! - Explorer interactions
 //!   * Supported recipes from the [Generator] and [Combinator]  
 //!   * Resource generation requests  
-//!   * Resource combination (including error reporting)  
+//!   * Resource combination of every advertised recipe, through the
+//!     [recipes](crate::ai::recipes) table (including error reporting)  
 //!   * Energy cell availability
 //!
 //! - Asteroid survival logic  
 //!   Attempts to build a [Rocket] and return it.  
-//!   If no rocket is returned, the planet is destroyed.
+//!   If no rocket is returned, the planet is destroyed. The outcome can be
+//!   queried beforehand with [asteroid_survivability].
 //!
 //! - Lifecycle control  
 //!   Handles `StartPlanetAI` and `StopPlanetAI` messages, enabling
-//!   or disabling the decision-making logic.
-use common_game::components::planet::{DummyPlanetState, PlanetAI, PlanetState};
+//!   or disabling the decision-making logic. A planet destroyed by an
+//!   asteroid reaches [Lifecycle::Destroyed] and ignores everything after.
+//!
+//! - Defensive mode  
+//!   After an asteroid, or once the charge drops below
+//!   [OrbitronConfig::defensive_charge_threshold], the AI stops spending
+//!   cells on explorers until a rocket is available again or enough
+//!   Sunrays have been received (see [OrbitronConfig::defensive_sunrays]).
+//!
+//! - Explorer access control  
+//!   Explorers outside [OrbitronConfig::allowed_explorers] or inside
+//!   [OrbitronConfig::denied_explorers] get negative answers without the
+//!   planet state being touched.
+//!
+//! - Combination back-off  
+//!   Explorers repeatedly failing combinations are refused for a while
+//!   (see [OrbitronConfig::combine_backoff_threshold]).
+//!
+//! - Resource escrow  
+//!   Optionally holds generated resources until the explorer asks for them
+//!   again (see [OrbitronConfig::escrow_capacity]).
+//!
+//! - Weighted generation  
+//!   [Orbitron::generate_any] lets the planet pick the resource itself,
+//!   following [OrbitronConfig::generation_weights]; so can the resource
+//!   suggested in place of an unsupported one, with
+//!   [OrbitronConfig::suggest_alternatives].
+//!
+//! - Degenerate states  
+//!   A planet without energy cells refuses generation and combination with
+//!   a dedicated error, wastes Sunrays with a warning and never attempts a
+//!   rocket.
+//!
+//! ## Ticks
+//!
+//! The protocol has no clock, so the AI counts time in ticks: a new tick
+//! starts with every Sunray. Per-tick limits such as
+//! [OrbitronConfig::max_inflight] are reset in `on_tick`, and charges age
+//! in ticks (see [OrbitronConfig::cell_decay_ticks]).
+//!
+//! ## Logical time
+//!
+//! Everything else ages in logical time: the number of messages handled,
+//! from any channel, which only moves when a handler runs. Entries are
+//! stamped with the logical time they were made at and compared through
+//! `age_of`, never against a wall clock, so the same message sequence
+//! always gives the same windows, in tests as in the game. This covers the
+//! combination back-off ([OrbitronConfig::combine_backoff_reset]), silent
+//! explorer ids ([OrbitronConfig::explorer_silence_threshold]) and the
+//! duplicate Sunray check.
+//!
+//! Every 256 messages, per-explorer state of explorers unseen for more than
+//! [OrbitronConfig::explorer_horizon] is dropped, as if they were evicted.
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use common_game::components::planet::{DummyPlanetState, PlanetAI, PlanetState, PlanetType};
 use common_game::components::resource::{
-    BasicResourceType, Combinator, ComplexResource, ComplexResourceRequest, Generator,
-    GenericResource,
+    BasicResource, BasicResourceType, Combinator, ComplexResourceType, Generator,
 };
 use common_game::components::rocket::Rocket;
 use common_game::components::sunray::Sunray;
 use common_game::logging::*;
+use common_game::protocols::orchestrator_planet::PlanetToOrchestrator;
 use common_game::protocols::planet_explorer::*;
 use common_game::utils::ID;
+use crossbeam_channel::{Receiver, Sender};
 
-/// Set channels for incoming/outgoing messages
-const RCV_MSG_CHNL: Channel = Channel::Debug;
-const ACK_MSG_CHNL: Channel = Channel::Debug;
+use super::config::{OrbitronConfig, UnknownRecipePolicy};
+use super::handlers::asteroid::process_asteroid;
+use super::handlers::explorer::{CombineFailures, process_explorer_msg};
+use super::handlers::orchestrator::{process_internal_state_req, process_sunray};
+use super::logging::{LogSink, emit_global, handler_span, planet_actor};
+use super::relay::Relay;
+use super::rng::Rng;
+use super::stats::StateSummary;
+use super::strategy::{DefaultStrategy, OrbitronStrategy, ShadowReport};
+#[cfg(feature = "metrics")]
+use super::timing::Timings;
+use super::watchdog::{Heartbeat, Watchdog};
 
-const ORCHESTRATOR_ID: ID = 0;
+pub use super::handlers::asteroid::{Survivability, asteroid_survivability};
+#[cfg(any(test, feature = "test-util"))]
+pub use super::logging::CapturedEvent;
+pub use super::logging::TappedMessage;
+pub use super::registry::{ExplorerRecord, ExplorerRegistry};
+pub use super::stats::{
+    Direction, EventRecord, HistoryEntry, OrbitronSnapshot, RefusalReason, RoundStats,
+    SnapshotHandle,
+};
 
-/// Helper functions to convert messages and responses into string names
-fn explorer_to_planet_name(msg: &ExplorerToPlanet) -> String {
-    match msg {
-        ExplorerToPlanet::SupportedResourceRequest { .. } => "Supported Resource Request".into(),
-        ExplorerToPlanet::SupportedCombinationRequest { .. } => {
-            "Supported Combination Request".into()
-        }
-        ExplorerToPlanet::GenerateResourceRequest { .. } => "Generate Resource Request".into(),
-        ExplorerToPlanet::CombineResourceRequest { .. } => "Combine Resource Request".into(),
-        ExplorerToPlanet::AvailableEnergyCellRequest { .. } => {
-            "Available Energy Cell Request".into()
-        }
+/// Everything an explorer needs to know about a planet, in one place.
+///
+/// Each field matches the answer to the corresponding individual explorer
+/// request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanetDescription {
+    pub planet_type: PlanetType,
+    /// Same as `SupportedResourceResponse`.
+    pub basic_recipes: HashSet<BasicResourceType>,
+    /// Same as `SupportedCombinationResponse`.
+    pub complex_recipes: HashSet<ComplexResourceType>,
+    pub total_cells: usize,
+    /// Same as `AvailableEnergyCellResponse`.
+    pub charged_cells: u32,
+    pub can_build_rockets: bool,
+}
+
+/// Describes the capabilities of a planet.
+pub fn describe(
+    planet_type: PlanetType,
+    state: &PlanetState,
+    generator: &Generator,
+    combinator: &Combinator,
+) -> PlanetDescription {
+    PlanetDescription {
+        planet_type,
+        basic_recipes: generator.all_available_recipes(),
+        complex_recipes: combinator.all_available_recipes(),
+        total_cells: state.cells_count(),
+        charged_cells: charged_cells(state),
+        can_build_rockets: state.can_have_rocket(),
     }
 }
-/// Helper functions to convert messages and responses into string names
-fn planet_to_explorer_name(msg: &PlanetToExplorer) -> String {
-    match msg {
-        PlanetToExplorer::SupportedResourceResponse { .. } => "Supported Resource Response".into(),
-        PlanetToExplorer::SupportedCombinationResponse { .. } => {
-            "Supported Combination Response".into()
-        }
-        PlanetToExplorer::GenerateResourceResponse { .. } => "Generate Resource Response".into(),
-        PlanetToExplorer::CombineResourceResponse { .. } => "Combine Resource Response".into(),
-        PlanetToExplorer::AvailableEnergyCellResponse { .. } => {
-            "Available Energy Cell Response".into()
+
+/// Read-only view of a planet's energy cells.
+///
+/// Cell-dependent decisions take this instead of [PlanetState] so they can
+/// be exercised against synthetic states the framework would never build,
+/// such as a planet without cells.
+pub(crate) trait CellView {
+    fn total_cells(&self) -> usize;
+    fn charged_cells(&self) -> u32;
+    fn is_cell_charged(&self, index: usize) -> bool;
+}
+
+impl CellView for PlanetState {
+    fn total_cells(&self) -> usize {
+        self.cells_count()
+    }
+
+    fn charged_cells(&self) -> u32 {
+        let mut cnt: u32 = 0;
+        for cell in self.cells_iter() {
+            if cell.is_charged() {
+                cnt += 1;
+            }
         }
-        _ => "Unexpected Message".into(),
+        cnt
+    }
+
+    fn is_cell_charged(&self, index: usize) -> bool {
+        self.cell(index).is_charged()
     }
 }
 
+/// Counts the charged energy cells.
+pub(crate) fn charged_cells(cells: &impl CellView) -> u32 {
+    cells.charged_cells()
+}
+
+/// Whether the planet has no energy cell at all.
+pub(crate) fn has_no_cells(cells: &impl CellView) -> bool {
+    cells.total_cells() == 0
+}
+
+/// Whether every cell is charged, so a Sunray would be handed back unused.
+pub(crate) fn saturated(cells: &impl CellView) -> bool {
+    charged_cells(cells) as usize == cells.total_cells()
+}
+
+/// Whether every energy cell of the planet is charged: a Sunray sent now
+/// would be wasted. Also true for a planet without cells.
+pub fn is_saturated(state: &PlanetState) -> bool {
+    saturated(state)
+}
+
 /// Represents the AI controller for the Orbitron planet.
 ///
-/// The `is_stopped` flag indicates whether the planet's AI is currently
-/// inactive and should ignore incoming logic or requests.
+/// The `lifecycle` tells whether the planet's AI is currently running,
+/// stopped, or gone for good after an asteroid it couldn't survive.
 pub struct Orbitron {
-    is_stopped: bool,
+    /// Id the AI was created for, see [Orbitron::metrics_prometheus].
+    pub(super) planet_id: ID,
+    pub(super) lifecycle: Lifecycle,
+    pub(super) config: OrbitronConfig,
+    /// Sunrays still to be received before defensive mode ends,
+    /// `None` when the AI is not in defensive mode.
+    pub(super) defensive: Option<u32>,
+    /// Complex resources successfully produced, by type.
+    pub(super) complex_generated: HashMap<ComplexResourceType, u64>,
+    /// Explorer messages handled so far.
+    pub(super) explorer_msgs: u64,
+    /// Explorer messages handled so far, by request name.
+    pub(super) explorer_requests: HashMap<String, u64>,
+    /// Current tick, advanced by every Sunray.
+    pub(super) tick: u64,
+    /// Resource generations started during the current tick.
+    pub(super) generations_this_tick: usize,
+    /// Complex resources produced during the current tick.
+    pub(super) combines_this_tick: usize,
+    /// Hydrogen and Oxygen handed out and not yet used in a Water combination
+    /// on this planet, see [OrbitronConfig::balance_for_water].
+    pub(super) unconsumed_hydrogen: u64,
+    pub(super) unconsumed_oxygen: u64,
+    /// Explorers whose next Oxygen request skips the `max_inflight` cap.
+    pub(super) fast_tracked: HashSet<ID>,
+    /// Consecutive failed combinations, by explorer.
+    pub(super) combine_failures: HashMap<ID, CombineFailures>,
+    /// Generated resources not yet collected, oldest first.
+    pub(super) escrow: Vec<(ID, BasicResourceType, BasicResource)>,
+    /// Refused explorer requests, by reason.
+    pub(super) refusal_reasons: HashMap<RefusalReason, u64>,
+    /// Asteroids that hit the planet.
+    pub(super) asteroids_faced: u64,
+    /// Asteroids deflected with a rocket.
+    pub(super) asteroids_survived: u64,
+    /// Tick of the last asteroid, `None` before the first one.
+    pub(super) last_asteroid_tick: Option<u64>,
+    /// Payload of the last asteroid response logged.
+    pub(super) last_asteroid_event: Payload,
+    /// Source of every randomized decision, seeded from the config.
+    pub(super) rng: Rng,
+    /// Most recently handled messages, oldest first, at most
+    /// [OrbitronConfig::event_history] of them.
+    pub(super) recent_events: Vec<EventRecord>,
+    /// Compact entries for the post-mortem dump, at most
+    /// [OrbitronConfig::post_mortem_history] of them.
+    pub(super) history: VecDeque<HistoryEntry>,
+    /// Messages handled so far, by every handler: the AI's clock, see
+    /// [Logical time](self#logical-time).
+    pub(super) logical_time: u64,
+    /// Correlation id of the exchange being handled, see
+    /// [Orbitron::begin_exchange].
+    pub(super) corr_id: u64,
+    /// Value of `handled` right after the last Sunray.
+    pub(super) last_sunray_at: Option<u64>,
+    /// Round in progress, from `1`, see [OrbitronConfig::round_boundary].
+    pub(super) current_round: u32,
+    /// Figures of the round in progress.
+    pub(super) round: RoundStats,
+    /// Ended rounds, oldest first, at most [OrbitronConfig::round_history]
+    /// of them.
+    pub(super) rounds: VecDeque<RoundStats>,
+    /// Sunrays received during the round in progress.
+    pub(super) sunrays_this_round: u32,
+    /// Whether the planet is below [OrbitronConfig::low_energy_threshold]
+    /// and was already reported.
+    pub(super) low_energy: bool,
+    /// Sunrays flagged as possible duplicates.
+    pub(super) suspected_duplicate_sunrays: u64,
+    /// Senders warned about contacting the planet before its first start;
+    /// `None` stands for the orchestrator.
+    pub(super) premature_contacts: HashSet<Option<ID>>,
+    /// Messages breaking the expected protocol flow.
+    pub(super) protocol_anomalies: u64,
+    /// Figures of the last `InternalStateRequest`, to report what changed.
+    pub(super) last_state_summary: Option<StateSummary>,
+    /// Latest suggestion made to each explorer, see
+    /// [OrbitronConfig::suggest_alternatives].
+    pub(super) suggestions: HashMap<ID, BasicResourceType>,
+    /// First and last sighting of each tracked explorer, see
+    /// [Orbitron::explorer_registry].
+    pub(super) explorers: ExplorerRegistry,
+    /// Entries evicted because a collection was full.
+    pub(super) memory_pressure_events: u64,
+    /// Times the planet metadata was logged; only the first start logs it.
+    pub(super) metadata_logged: u64,
+    /// Receives a copy of every answer, see [Orbitron::set_tap].
+    pub(super) tap: Option<Sender<TappedMessage>>,
+    /// Where log events go, see [Orbitron::route_logs].
+    pub(super) logs: LogSink,
+    /// See [Orbitron::pause_handle].
+    pub(super) generation_paused: PauseHandle,
+    /// See [Orbitron::orchestrator_link].
+    pub(super) orchestrator_link: OrchestratorLink,
+    /// Tick at which each charged cell was first seen charged, by cell index.
+    pub(super) charged_since: HashMap<usize, u64>,
+    /// Sunrays absorbed, by cell index.
+    pub(super) charge_events: HashMap<usize, u64>,
+    /// Cell charged by the last Sunray, see [ChargeOrder::RoundRobin].
+    pub(super) last_charged: Option<usize>,
+    /// Sunrays handed back because every cell was charged.
+    pub(super) sunrays_wasted: u64,
+    /// Basic resources generated, and generations attempted that failed.
+    pub(super) generations_succeeded: u64,
+    pub(super) generations_failed: u64,
+    /// Errors returned by the generator, and retries that then succeeded.
+    pub(super) generator_errors: u64,
+    pub(super) generator_retries_succeeded: u64,
+    /// See [OrbitronSnapshot::dry_run_decisions].
+    #[cfg(feature = "dry-run")]
+    pub(super) dry_run_decisions: HashMap<String, u64>,
+    /// See [Orbitron::timing_report].
+    #[cfg(feature = "metrics")]
+    pub(super) timings: Timings,
+    /// Takes the last say on requests that passed every guard.
+    pub(super) strategy: Box<dyn OrbitronStrategy>,
+    /// Consulted next to `strategy` without acting, see [Orbitron::with_shadow].
+    pub(super) shadow: Option<Box<dyn OrbitronStrategy>>,
+    pub(super) shadow_report: ShadowReport,
+    /// Whether the unreachable orchestrator was already logged.
+    pub(super) unreachable_logged: bool,
+    /// Messages that reached the AI once the planet was destroyed.
+    pub(super) messages_after_destruction: u64,
+    /// Whether the first message after the destruction was already logged.
+    pub(super) dead_planet_logged: bool,
+    /// See [Orbitron::explorer_senders].
+    pub(super) explorer_senders: ExplorerSenders,
+    /// Answers held until the next Sunray, oldest first, see
+    /// [OrbitronConfig::batch_responses].
+    pub(super) batched: Vec<(ID, PlanetToExplorer)>,
+    /// Logged answer to this tick's first `SupportedResourceRequest` and
+    /// every explorer that sent one, see [Orbitron::flush_supported_requests].
+    pub(super) supported_requests: Option<(Payload, Vec<ID>)>,
+    /// Clone of the planet's explorer receiver, only used to measure its length.
+    pub(super) explorer_queue: Option<Receiver<ExplorerToPlanet>>,
+    /// Log events that passed [OrbitronConfig::log_verbosity]; a `Cell` as
+    /// logging only needs `&self`.
+    pub(super) events_emitted: Cell<u64>,
+    /// Beaten for every handled message, see [OrbitronConfig::watchdog_timeout].
+    pub(super) heartbeat: Heartbeat,
+    /// Spawned at the first start when a timeout is configured.
+    pub(super) watchdog: Option<Watchdog>,
+    /// See [Orbitron::relay_to_orchestrator].
+    pub(super) relay: Option<Relay>,
+    pub(super) published: SnapshotHandle,
+}
+
+/// Lifecycle of the planet as seen by its AI.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum Lifecycle {
+    /// Waiting for the first `StartPlanetAI`.
+    #[default]
+    Created,
+    /// Halted by `StopPlanetAI`.
+    Stopped,
+    Running,
+    /// Hit by an asteroid without a rocket. Terminal: every later message
+    /// is ignored.
+    Destroyed,
+}
+
+impl fmt::Display for Lifecycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Lifecycle::Created => "Created",
+            Lifecycle::Stopped => "Stopped",
+            Lifecycle::Running => "Running",
+            Lifecycle::Destroyed => "Destroyed",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Shared switch pausing resource generation and combination of an [Orbitron].
+///
+/// While paused, generate and combine requests are refused; Sunrays,
+/// asteroids and every query are handled as usual.
+#[derive(Debug, Clone, Default)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    pub fn pause(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn resume(&self) {
+        self.0.store(false, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Shared flag raised once the orchestrator can no longer be reached.
+///
+/// The planet loop owns the orchestrator channel, so the flag is raised from
+/// outside the AI: by the relay of [Orbitron::relay_to_orchestrator], which
+/// every planet built by [create_planet](crate::create_planet) sends through,
+/// as soon as a message can't be delivered, and by
+/// [run_planet_with_link](crate::run_planet_with_link) when the loop fails.
+#[derive(Debug, Clone, Default)]
+pub struct OrchestratorLink(Arc<AtomicBool>);
+
+impl OrchestratorLink {
+    pub fn mark_unreachable(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_unreachable(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Shared senders the AI may use to answer explorers itself, see
+/// [OrbitronConfig::batch_responses].
+///
+/// The planet loop keeps the sender of every `IncomingExplorerRequest` to
+/// itself, so whoever docks an explorer registers a clone here as well.
+#[derive(Debug, Clone, Default)]
+pub struct ExplorerSenders(Arc<Mutex<HashMap<ID, Sender<PlanetToExplorer>>>>);
+
+impl ExplorerSenders {
+    pub fn register(&self, explorer_id: ID, sender: Sender<PlanetToExplorer>) {
+        self.0.lock().unwrap().insert(explorer_id, sender);
+    }
+
+    pub fn unregister(&self, explorer_id: ID) {
+        self.0.lock().unwrap().remove(&explorer_id);
+    }
+
+    pub fn contains(&self, explorer_id: ID) -> bool {
+        self.0.lock().unwrap().contains_key(&explorer_id)
+    }
+
+    pub(crate) fn get(&self, explorer_id: ID) -> Option<Sender<PlanetToExplorer>> {
+        self.0.lock().unwrap().get(&explorer_id).cloned()
+    }
+}
+
+/// Builder for an [Orbitron] with non-default options.
+///
+/// ```
+/// use orbitron::OrbitronBuilder;
+///
+/// let ai = OrbitronBuilder::new(1).seed(7).build();
+/// assert_eq!(ai.config().rng_seed, Some(7));
+/// ```
+#[derive(Debug, Clone)]
+pub struct OrbitronBuilder {
+    id: ID,
+    config: OrbitronConfig,
+    auto_start: bool,
+}
+
+impl OrbitronBuilder {
+    pub fn new(id: ID) -> Self {
+        Self {
+            id,
+            config: OrbitronConfig::default(),
+            auto_start: false,
+        }
+    }
+
+    /// Replaces the whole configuration.
+    pub fn config(mut self, config: OrbitronConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Seeds the generator behind randomized decisions.
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.config.rng_seed = Some(seed);
+        self
+    }
+
+    /// Chooses how requests for a recipe the planet doesn't have are answered.
+    pub fn unknown_recipe_policy(mut self, policy: UnknownRecipePolicy) -> Self {
+        self.config.unknown_recipe_policy = policy;
+        self
+    }
+
+    /// Sets how many handled messages [Orbitron::recent_events] keeps.
+    pub fn event_history(mut self, capacity: usize) -> Self {
+        self.config.event_history = capacity;
+        self
+    }
+
+    /// Sets how many handled messages [Orbitron::recent_history] keeps.
+    pub fn post_mortem_history(mut self, capacity: usize) -> Self {
+        self.config.post_mortem_history = capacity;
+        self
+    }
+
+    /// Builds the AI already running instead of waiting for `StartPlanetAI`.
+    ///
+    /// The planet loop of `common_game` keeps its own running flag and only
+    /// forwards messages to the AI after a `StartPlanetAI`, so inside a
+    /// [Planet](common_game::components::planet::Planet) the orchestrator must
+    /// still send it. Doing so is harmless: the AI logs
+    /// "start ignored: already running" and changes nothing.
+    pub fn auto_start(mut self, auto_start: bool) -> Self {
+        self.auto_start = auto_start;
+        self
+    }
+
+    pub fn build(self) -> Orbitron {
+        let mut ai = Orbitron::with_config(self.id, self.config);
+        if self.auto_start {
+            ai.start(self.id);
+        }
+        ai
+    }
 }
 
 /// Creates a new `Orbitron` AI instance.
@@ -90,21 +537,207 @@ pub struct Orbitron {
 /// begin processing once explicitly started.
 impl Orbitron {
     pub fn new(id: ID) -> Self {
+        Self::with_config(id, OrbitronConfig::default())
+    }
+
+    pub fn with_config(id: ID, config: OrbitronConfig) -> Self {
         // LOG internal ai creation
         let mut payload = Payload::new();
         payload.insert("Message".into(), "New AI orbitron created".into());
-        LogEvent::self_directed(
-            Participant::new(ActorType::Planet, id),
+        emit_global(LogEvent::self_directed(
+            planet_actor(id),
             EventType::InternalPlanetAction,
             Channel::Info,
             payload,
-        )
-        .emit();
+        ));
+
+        let ai = Self {
+            planet_id: id,
+            lifecycle: Lifecycle::Created,
+            rng: Rng::new(config.effective_seed(id)),
+            config,
+            defensive: None,
+            complex_generated: HashMap::new(),
+            explorer_requests: HashMap::new(),
+            unconsumed_hydrogen: 0,
+            unconsumed_oxygen: 0,
+            explorer_msgs: 0,
+            tick: 0,
+            generations_this_tick: 0,
+            combines_this_tick: 0,
+            fast_tracked: HashSet::new(),
+            combine_failures: HashMap::new(),
+            escrow: Vec::new(),
+            refusal_reasons: HashMap::new(),
+            asteroids_faced: 0,
+            asteroids_survived: 0,
+            last_asteroid_tick: None,
+            low_energy: false,
+            current_round: 1,
+            round: RoundStats {
+                round: 1,
+                ..Default::default()
+            },
+            rounds: VecDeque::new(),
+            sunrays_this_round: 0,
+            last_asteroid_event: Payload::new(),
+            recent_events: Vec::new(),
+            history: VecDeque::new(),
+            logical_time: 0,
+            corr_id: 0,
+            last_sunray_at: None,
+            suspected_duplicate_sunrays: 0,
+            premature_contacts: HashSet::new(),
+            protocol_anomalies: 0,
+            suggestions: HashMap::new(),
+            explorers: ExplorerRegistry::default(),
+            memory_pressure_events: 0,
+            last_state_summary: None,
+            metadata_logged: 0,
+            tap: None,
+            logs: LogSink::default(),
+            generation_paused: PauseHandle::default(),
+            orchestrator_link: OrchestratorLink::default(),
+            charged_since: HashMap::new(),
+            charge_events: HashMap::new(),
+            last_charged: None,
+            sunrays_wasted: 0,
+            generations_succeeded: 0,
+            generations_failed: 0,
+            generator_errors: 0,
+            generator_retries_succeeded: 0,
+            #[cfg(feature = "dry-run")]
+            dry_run_decisions: HashMap::new(),
+            #[cfg(feature = "metrics")]
+            timings: Timings::default(),
+            unreachable_logged: false,
+            messages_after_destruction: 0,
+            dead_planet_logged: false,
+            explorer_senders: ExplorerSenders::default(),
+            batched: Vec::new(),
+            supported_requests: None,
+            explorer_queue: None,
+            events_emitted: Cell::new(0),
+            heartbeat: Heartbeat::default(),
+            watchdog: None,
+            relay: None,
+            strategy: Box::new(DefaultStrategy),
+            shadow: None,
+            shadow_report: ShadowReport::default(),
+            published: SnapshotHandle::default(),
+        };
+        ai.publish(id);
+        ai
+    }
+
+    /// Builds an AI acting on `primary`, while `shadow` is asked the same
+    /// questions and only its agreement with `primary` is recorded (see
+    /// [Orbitron::shadow_report]).
+    pub fn with_shadow(
+        id: ID,
+        primary: Box<dyn OrbitronStrategy>,
+        shadow: Box<dyn OrbitronStrategy>,
+    ) -> Self {
+        let mut ai = Self::new(id);
+        ai.strategy = primary;
+        ai.shadow = Some(shadow);
+        ai
+    }
+
+    /// Agreement of the shadow strategy with the primary one, `None` without
+    /// a shadow.
+    pub fn shadow_report(&self) -> Option<ShadowReport> {
+        self.shadow.as_ref().map(|_| self.shadow_report)
+    }
+
+    /// Returns a copy of the configuration this AI was built with.
+    ///
+    /// It is also published in [OrbitronSnapshot::config] and logged on every
+    /// `InternalStateRequest`, to read it back once the AI runs in a planet.
+    pub fn config(&self) -> OrbitronConfig {
+        self.config.clone()
+    }
+
+    /// Returns a handle that pauses generation, even after this AI is moved
+    /// into a planet.
+    pub fn pause_handle(&self) -> PauseHandle {
+        self.generation_paused.clone()
+    }
+
+    /// Returns the flag telling this AI its orchestrator is gone.
+    pub fn orchestrator_link(&self) -> OrchestratorLink {
+        self.orchestrator_link.clone()
+    }
+
+    /// Returns the senders this AI answers batched explorers through.
+    pub fn explorer_senders(&self) -> ExplorerSenders {
+        self.explorer_senders.clone()
+    }
+
+    /// Returns a handle that keeps observing this AI after it is moved into a planet.
+    pub fn snapshot_handle(&self) -> SnapshotHandle {
+        self.published.clone()
+    }
+
+    /// Lets the AI watch the explorer channel of its planet.
+    ///
+    /// `queue` must be a clone of the receiver handed to the planet; the AI
+    /// never receives from it, it only reads its length.
+    pub fn watch_explorer_queue(&mut self, queue: Receiver<ExplorerToPlanet>) {
+        self.explorer_queue = Some(queue);
+    }
+
+    /// Relays what the planet sends to `to_orchestrator`, raising the
+    /// [orchestrator link](Orbitron::orchestrator_link) as soon as a message
+    /// can't be delivered; hand the returned sender to the planet instead.
+    ///
+    /// Without it the AI only learns that the orchestrator is gone once the
+    /// loop has failed, when it will never be called again.
+    pub fn relay_to_orchestrator(
+        &mut self,
+        to_orchestrator: Sender<PlanetToOrchestrator>,
+    ) -> Sender<PlanetToOrchestrator> {
+        let (relay, sender) = Relay::spawn(to_orchestrator, self.orchestrator_link.clone());
+        self.relay = Some(relay);
+        sender
+    }
+
+    /// Number of explorer messages waiting to be handled, `0` when the
+    /// queue isn't watched.
+    pub fn pending_explorer_count(&self) -> usize {
+        self.explorer_queue.as_ref().map_or(0, Receiver::len)
+    }
 
-        Self { is_stopped: true }
+    /// Whether the AI is running, stopped, or destroyed.
+    pub fn lifecycle(&self) -> Lifecycle {
+        self.lifecycle
     }
 }
 
+/// `KillPlanet` ends the planet loop without calling the AI, so batched
+/// answers are sent, and whatever is still coalesced is logged, when the AI
+/// goes away with it.
+impl Drop for Orbitron {
+    fn drop(&mut self) {
+        self.flush_batched(self.planet_id);
+        self.flush_supported_requests(self.planet_id);
+    }
+}
+
+/// Introspection for unit tests driving the AI without a planet.
+#[cfg(test)]
+impl Orbitron {
+    /// Snapshot of the bookkeeping; there is no planet, so its id is `0`.
+    pub(crate) fn stats(&self) -> OrbitronSnapshot {
+        self.snapshot(0)
+    }
+
+    /// Swaps the configuration mid-test. The random generator keeps the
+    /// seed it was built with.
+    pub(crate) fn set_config_for_test(&mut self, config: OrbitronConfig) {
+        self.config = config;
+    }
+}
 impl PlanetAI for Orbitron {
     /// This function is used to handle Sunray msg
     /// # Returns
@@ -113,26 +746,12 @@ impl PlanetAI for Orbitron {
     fn handle_sunray(
         &mut self,
         state: &mut PlanetState,
-        _generator: &Generator,
-        _combinator: &Combinator,
+        generator: &Generator,
+        combinator: &Combinator,
         sunray: Sunray,
     ) {
-        let mut payload = Payload::new();
-
-        if state.charge_cell(sunray).is_some() {
-            payload.insert("Energy Cell State".into(), "Energy Cell full".into());
-        } else {
-            payload.insert("Energy Cell State".into(), "Energy Cell charged".into());
-        }
-
-        // LOG incoming sunray handle
-        LogEvent::broadcast(
-            Participant::new(ActorType::Planet, state.id()),
-            EventType::InternalPlanetAction,
-            RCV_MSG_CHNL,
-            payload,
-        )
-        .emit();
+        handler_span!("handle_sunray", state.id());
+        process_sunray(self, state, generator, combinator, sunray);
     }
 
     /// This function is used to handle InternalStateRequest msg
@@ -141,38 +760,14 @@ impl PlanetAI for Orbitron {
     fn handle_internal_state_req(
         &mut self,
         state: &mut PlanetState,
-        _generator: &Generator,
-        _combinator: &Combinator,
+        generator: &Generator,
+        combinator: &Combinator,
     ) -> DummyPlanetState {
-        let mut payload = Payload::new();
-
-        payload.insert("Planet State".into(), format!("{:?}", state.to_dummy()));
-
-        // LOG internal state response
-        LogEvent::new(
-            Some(Participant::new(ActorType::Planet, state.id())),
-            Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-            EventType::MessagePlanetToOrchestrator,
-            ACK_MSG_CHNL,
-            payload,
-        )
-        .emit();
-
-        state.to_dummy()
+        handler_span!("handle_internal_state_req", state.id());
+        process_internal_state_req(self, state, generator, combinator)
     }
 
-    /// Handles messages from explorers.
-    ///
-    /// - Provides supported basic and complex resource types
-    /// - Generates requested basic resources (Hydrogen or Oxygen).  
-    ///   First, we check whether there is any charged cell (the `full_cell` function does this).  
-    ///   If there is, we then check whether the requested `BasicResourceType` is Hydrogen or Oxygen.  
-    ///   If it is, we generate it; otherwise, the function returns `None`.
-    /// - Generates Water as the only supported complex resource.  
-    ///   As before, we must check whether there is a charged cell.  
-    ///   Since the planet can only generate water, if the requested complex resource type is `Water`,
-    ///   we proceed with generation; otherwise, we return an error message.
-    /// - Returns the number of available charged energy cells.
+    /// Answers explorers, see [process_explorer_msg].
     fn handle_explorer_msg(
         &mut self,
         state: &mut PlanetState,
@@ -180,177 +775,10 @@ impl PlanetAI for Orbitron {
         combinator: &Combinator,
         msg: ExplorerToPlanet,
     ) -> Option<PlanetToExplorer> {
-        let explorer_id: ID = msg.explorer_id();
-
-        // LOG incoming explorer message
-        let mut in_payload = Payload::new();
-        in_payload.insert("Message".into(), explorer_to_planet_name(&msg));
-
-        LogEvent::new(
-            Some(Participant::new(ActorType::Orchestrator, explorer_id)),
-            Some(Participant::new(ActorType::Planet, state.id())),
-            EventType::MessageExplorerToPlanet,
-            RCV_MSG_CHNL,
-            in_payload,
-        )
-        .emit();
-
-
-        // LOG explorer message result
-        let mut payload = Payload::new();
-
-        let response = match msg {
-            ExplorerToPlanet::SupportedResourceRequest { explorer_id: _id } => {
-                payload.insert(
-                    "Supported Resources".into(),
-                    format!("{:?}", generator.all_available_recipes()),
-                );
-
-                Some(PlanetToExplorer::SupportedResourceResponse {
-                    resource_list: generator.all_available_recipes(),
-                })
-            }
-            ExplorerToPlanet::SupportedCombinationRequest { explorer_id: _id } => {
-                payload.insert(
-                    "Supported Combinations".into(),
-                    format!("{:?}", combinator.all_available_recipes()),
-                );
-
-                Some(PlanetToExplorer::SupportedCombinationResponse {
-                    combination_list: combinator.all_available_recipes(),
-                })
-            }
-            ExplorerToPlanet::GenerateResourceRequest {
-                explorer_id: _id,
-                resource,
-            } => {
-                let generated_resource = state.full_cell().and_then(|(cell, _)| match resource {
-                    BasicResourceType::Hydrogen => generator
-                        .make_hydrogen(cell)
-                        .ok()
-                        .map(|hydrogen| hydrogen.to_basic()),
-                    BasicResourceType::Oxygen => generator
-                        .make_oxygen(cell)
-                        .ok()
-                        .map(|oxygen| oxygen.to_basic()),
-                    _ => None,
-                });
-                if generated_resource.is_some() {
-                    payload.insert(
-                        "Generated Resource".into(),
-                        format!("{:?}", generated_resource),
-                    );
-                } else {
-                    payload.insert(
-                        "Generated Resource".into(),
-                        "Unsupported Resource Generation Request".into(),
-                    );
-                }
-
-                Some(PlanetToExplorer::GenerateResourceResponse {
-                    resource: generated_resource,
-                })
-            }
-            ExplorerToPlanet::CombineResourceRequest {
-                explorer_id: _id,
-                msg,
-            } => {
-                let cell = state.full_cell();
-
-                let ret: Result<ComplexResource, (String, GenericResource, GenericResource)> =
-                    match msg {
-                        ComplexResourceRequest::Water(resource_1, resource_2) => match cell {
-                            Some((cell, _)) => combinator
-                                .make_water(resource_1, resource_2, cell)
-                                .map(|water| water.to_complex())
-                                .map_err(|(err_str, return_resource_1, return_resource_2)| {
-                                    (
-                                        err_str,
-                                        return_resource_1.to_generic(),
-                                        return_resource_2.to_generic(),
-                                    )
-                                }),
-                            None => Err((
-                                "No charged energy cell found".to_string(),
-                                resource_1.to_generic(),
-                                resource_2.to_generic(),
-                            )),
-                        },
-
-                        other => {
-                            let variant_name = format!("{other:?}");
-
-                            let (resource_1, resource_2) = match other {
-                                ComplexResourceRequest::Diamond(r1, r2) => {
-                                    (r1.to_generic(), r2.to_generic())
-                                }
-                                ComplexResourceRequest::Life(r1, r2) => {
-                                    (r1.to_generic(), r2.to_generic())
-                                }
-                                ComplexResourceRequest::Robot(r1, r2) => {
-                                    (r1.to_generic(), r2.to_generic())
-                                }
-                                ComplexResourceRequest::Dolphin(r1, r2) => {
-                                    (r1.to_generic(), r2.to_generic())
-                                }
-                                ComplexResourceRequest::AIPartner(r1, r2) => {
-                                    (r1.to_generic(), r2.to_generic())
-                                }
-                                ComplexResourceRequest::Water(_, _) => {
-                                    unreachable!("Water is handled above")
-                                }
-                            };
-
-                            Err((
-                                format!("There isn't a recipe for {variant_name:?}"),
-                                resource_1,
-                                resource_2,
-                            ))
-                        }
-                    };
-                if ret.is_ok() {
-                    payload.insert("Combined Resource".into(), format!("{:?}", ret));
-                } else {
-                    payload.insert(
-                        "Combined Resource".into(),
-                        format!("Unsupported Resource Combination Request: {:?}", ret),
-                    );
-                }
-
-                Some(PlanetToExplorer::CombineResourceResponse {
-                    complex_response: ret,
-                })
-            }
-            ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: _id } => {
-                let mut cnt: u32 = 0;
-                for cell in state.cells_iter() {
-                    if cell.is_charged() {
-                        cnt += 1;
-                    }
-                }
-                payload.insert("Available Energy Cells".into(), format!("{:?}", cnt));
-
-                Some(PlanetToExplorer::AvailableEnergyCellResponse {
-                    available_cells: cnt,
-                })
-            }
-        };
-
-        // LOG planet response
-        if let Some(ref res) = response {
-            payload.insert("Response".into(), planet_to_explorer_name(res));
-            LogEvent::new(
-                Some(Participant::new(ActorType::Planet, state.id())),
-                Some(Participant::new(ActorType::Orchestrator, explorer_id)),
-                EventType::MessagePlanetToExplorer,
-                ACK_MSG_CHNL,
-                payload,
-            )
-            .emit();
-        }
-
-        response
+        handler_span!("handle_explorer_msg", state.id());
+        process_explorer_msg(self, state, generator, combinator, msg)
     }
+
     /// This handler will be invoked when a [OrchestratorToPlanet::Asteroid]
     /// message is received.
     ///
@@ -358,51 +786,25 @@ impl PlanetAI for Orbitron {
     /// In order to survice, planet try to build rocket.
     /// After this attempt an owned [Rocket] must be returned from this method;
     /// if `None` is returned instead, the planet will  be destroyed by the orchestrator
+    ///
+    /// Before `StartPlanetAI` and after `StopPlanetAI` the stock planet loop
+    /// answers the asteroid with `Stopped` itself and never calls this
+    /// handler. Should a different loop call it anyway, the asteroid is
+    /// faced like any other and logged as a protocol anomaly; this is a
+    /// defensive fallback only, not a path the stock game takes.
+    ///
+    /// The `AsteroidAck` carrying the rocket is sent by the planet loop with
+    /// a blocking send, like every orchestrator answer: a full channel
+    /// delays it, it never drops it. A delay shows up as a watchdog stall,
+    /// see [OrbitronConfig::watchdog_timeout].
     fn handle_asteroid(
         &mut self,
         state: &mut PlanetState,
-        _generator: &Generator,
-        _combinator: &Combinator,
+        generator: &Generator,
+        combinator: &Combinator,
     ) -> Option<Rocket> {
-        // LOG incoming asteroid
-        let mut payload = Payload::new();
-        payload.insert("Message".into(), "Asteroid".into());
-        LogEvent::new(
-            Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-            Some(Participant::new(ActorType::Planet, state.id())),
-            EventType::MessageOrchestratorToPlanet,
-            RCV_MSG_CHNL,
-            payload,
-        )
-        .emit();
-
-        // LOG asteroid response
-        let mut payload = Payload::new();
-
-        let has_rocket = state.has_rocket();
-        if has_rocket {
-            payload.insert("Result".into(), "Rocket was Ready".into());
-        } else {
-            payload.insert("Result".into(), "Rocket was Built".into());
-            let _ = state.build_rocket(0);
-        }
-        let rocket = state.take_rocket();
-
-        if rocket.is_some() {
-            payload.insert("Result".into(), "Rocket is Available".into());
-        } else {
-            payload.insert("Result".into(), "No Rocket Available".into());
-        }
-        LogEvent::new(
-            Some(Participant::new(ActorType::Planet, state.id())),
-            Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-            EventType::MessagePlanetToOrchestrator,
-            ACK_MSG_CHNL,
-            payload,
-        )
-        .emit();
-
-        rocket
+        handler_span!("handle_asteroid", state.id());
+        process_asteroid(self, state, generator, combinator)
     }
 
     fn on_explorer_arrival(
@@ -426,21 +828,15 @@ impl PlanetAI for Orbitron {
     /// This method will be invoked when a [OrchestratorToPlanet::StartPlanetAI]
     /// is received, but only if the planet is currently in a stopped state.
     ///
-    /// Start messages received when planet is already running are ignored.
+    /// Start messages received when planet is already running are ignored,
+    /// including the one sent to an AI built with
+    /// [OrbitronBuilder::auto_start].
     fn on_start(&mut self, state: &PlanetState, _generator: &Generator, _combinator: &Combinator) {
-        self.is_stopped = false;
-
-        let mut payload = Payload::new();
-        payload.insert("Message".into(), "Started Planet Orbitron".into());
-
-        LogEvent::new(
-            Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-            Some(Participant::new(ActorType::Planet, state.id())),
-            EventType::MessageOrchestratorToPlanet,
-            RCV_MSG_CHNL,
-            payload,
-        )
-        .emit();
+        self.begin_exchange();
+        if self.is_destroyed(state.id(), "StartPlanetAI") {
+            return;
+        }
+        self.start(state.id());
     }
 
     /// This method will be invoked when a [OrchestratorToPlanet::StopPlanetAI]
@@ -448,17 +844,3256 @@ impl PlanetAI for Orbitron {
     ///
     /// Stop messages received when planet is already stopped are ignored.
     fn on_stop(&mut self, state: &PlanetState, _generator: &Generator, _combinator: &Combinator) {
-        self.is_stopped = true;
+        self.begin_exchange();
+        self.flush_batched(state.id());
+        self.flush_supported_requests(state.id());
+        if self.is_destroyed(state.id(), "StopPlanetAI") {
+            return;
+        }
+        self.stop(state.id());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::config::{ChargeOrder, LogVerbosity, Verbosity, config_payload};
+    use crate::ai::error::OrbitronError;
+    use crate::ai::handlers::asteroid::{
+        NO_CHARGE_ERROR, NO_ROCKETS_ERROR, ROCKET_COST, can_attempt_rocket, survivability,
+    };
+    use crate::ai::handlers::explorer::{
+        BASIC_RESOURCES, GC_INTERVAL, GenError, ResourceGenerator, closest_supported, generate,
+        generate_with_retry, is_read_only, unreserved_cells,
+    };
+    use crate::ai::handlers::orchestrator::{ChargeOutcome, charge_target};
+    use crate::ai::logging::{ORCHESTRATOR_ID, explorer_actor, orchestrator_actor};
+    use crate::ai::strategy::ShadowTally;
+    use crate::testkit::{self, SteppedPlanet, TestExplorer, TestPlanet, charged_cell, fixtures};
+    use common_game::components::energy_cell::EnergyCell;
+    use common_game::components::resource::{
+        ComplexResource, ComplexResourceRequest, GenericResource,
+    };
+    use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestrator};
+
+    #[test]
+    fn test_generate_dispatches_every_resource() {
+        let (full, _) = testkit::lab();
+        let mut hydrogen_only = Generator::new();
+        hydrogen_only.add(BasicResourceType::Hydrogen).unwrap();
+
+        for resource in BASIC_RESOURCES {
+            let generated = generate(&full, &mut charged_cell(), resource).unwrap();
+            assert_eq!(generated.get_type(), resource);
+
+            match generate(&hydrogen_only, &mut charged_cell(), resource) {
+                Ok(generated) => {
+                    assert_eq!(resource, BasicResourceType::Hydrogen);
+                    assert_eq!(generated.get_type(), resource);
+                }
+                Err(err) => assert_eq!(err, GenError::NotSupportedByThisPlanet(resource)),
+            }
+        }
+        assert!(matches!(
+            generate(&full, &mut EnergyCell::new(), BasicResourceType::Oxygen),
+            Err(GenError::Generator(_))
+        ));
+    }
+
+    /// Fails its first `failures` calls, then generates like `inner`.
+    struct FlakyGenerator {
+        inner: Generator,
+        failures: Cell<u32>,
+    }
+
+    impl ResourceGenerator for FlakyGenerator {
+        fn recipes(&self) -> HashSet<BasicResourceType> {
+            self.inner.recipes()
+        }
+
+        fn make(
+            &self,
+            resource: BasicResourceType,
+            cell: &mut EnergyCell,
+        ) -> Option<Result<BasicResource, String>> {
+            if self.failures.get() > 0 {
+                self.failures.set(self.failures.get() - 1);
+                return Some(Err("cell state race".into()));
+            }
+            self.inner.make(resource, cell)
+        }
+    }
+
+    #[test]
+    fn test_generator_error_retried_with_next_cell() {
+        let flaky = |failures| FlakyGenerator {
+            inner: testkit::lab().0,
+            failures: Cell::new(failures),
+        };
+        let hydrogen = BasicResourceType::Hydrogen;
+
+        // No retry by default: the first error is final
+        let mut cells = [EnergyCell::new(), charged_cell(), charged_cell()];
+        let (result, errors) = generate_with_retry(&flaky(1), &mut cells, hydrogen, false);
+        assert_eq!(result, Err(GenError::Generator("cell state race".into())));
+        assert_eq!(errors, ["cell state race"]);
+
+        // The retry takes the next charged cell
+        let (result, errors) = generate_with_retry(&flaky(1), &mut cells, hydrogen, true);
+        assert_eq!(result.map(|made| made.get_type()), Ok(hydrogen));
+        assert_eq!(errors.len(), 1);
+        let charged: Vec<bool> = cells.iter().map(EnergyCell::is_charged).collect();
+        assert_eq!(charged, [false, true, false]);
+
+        // A single retry, never more
+        let mut cells = [charged_cell(), charged_cell(), charged_cell()];
+        let (result, errors) = generate_with_retry(&flaky(2), &mut cells, hydrogen, true);
+        assert!(result.is_err());
+        assert_eq!(errors.len(), 2);
 
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::new(1);
+        ai.set_test_logger(Arc::clone(&logs));
         let mut payload = Payload::new();
-        payload.insert("Message".into(), "Stoped Planet Orbitron".into());
-        LogEvent::new(
-            Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-            Some(Participant::new(ActorType::Planet, state.id())),
-            EventType::MessageOrchestratorToPlanet,
-            RCV_MSG_CHNL,
-            payload,
+        ai.note_generator_errors(1, 7, &errors, false, &mut payload);
+        ai.note_generator_errors(1, 7, &errors[..1], true, &mut payload);
+        let snapshot = ai.stats();
+        assert_eq!(snapshot.generator_errors, 3);
+        assert_eq!(snapshot.generator_retries_succeeded, 1);
+        assert_eq!(payload["Retried"], "recovered");
+        assert_eq!(warnings(&logs, "generator_error"), 3);
+    }
+
+    /// A type A planet, able to build rockets and survive asteroids.
+    fn rocket_planet(config: OrbitronConfig) -> (TestPlanet, SnapshotHandle) {
+        TestPlanet::custom(
+            PlanetType::A,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![],
+            config,
         )
-        .emit();
+    }
+
+    #[test]
+    fn test_asteroid_enters_defensive_mode_until_sunrays() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 2,
+            ..Default::default()
+        };
+        let (planet, snapshot) = rocket_planet(config);
+        let explorer = planet.explorer(7);
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+
+        // One cell goes into the rocket
+        assert!(planet.asteroid().is_some());
+        assert!(snapshot.get().defensive_mode);
+
+        // The charged cell is kept for survival
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(explorer.available_cells(), 1);
+
+        planet.sunray();
+        assert!(snapshot.get().defensive_mode);
+        planet.sunray();
+        assert!(!snapshot.get().defensive_mode);
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+    }
+
+    #[test]
+    fn test_defensive_mode_only_after_rocket_launch() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 2,
+            ..Default::default()
+        };
+        let (rocketless, snapshot) = TestPlanet::orbitron(config.clone());
+        rocketless.start();
+        rocketless.sunray();
+        assert!(rocketless.asteroid().is_none());
+        assert_eq!(snapshot.get().lifecycle, Lifecycle::Destroyed);
+        assert!(!snapshot.get().defensive_mode);
+
+        let (planet, snapshot) = rocket_planet(config);
+        planet.start();
+        planet.sunray();
+        assert!(planet.asteroid().is_some());
+        assert!(snapshot.get().defensive_mode);
+    }
+
+    #[test]
+    fn test_low_charge_enters_defensive_mode() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 2,
+            defensive_charge_threshold: 1,
+            ..Default::default()
+        };
+        let (planet, snapshot) = TestPlanet::orbitron(config);
+        let explorer = planet.explorer(7);
+        planet.start();
+        planet.sunray();
+        assert!(!snapshot.get().defensive_mode);
+
+        // Spending the only cell of the Type B planet drops it below 1
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert!(snapshot.get().defensive_mode);
+
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(
+            snapshot.get().refusal_reasons[&RefusalReason::ReservePolicy],
+            1
+        );
+        assert_eq!(explorer.available_cells(), 1);
+
+        planet.sunray();
+        assert!(!snapshot.get().defensive_mode);
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+    }
+
+    #[test]
+    fn test_defensive_mode_disabled() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 0,
+            ..Default::default()
+        };
+        let (planet, snapshot) = rocket_planet(config);
+        let explorer = planet.explorer(7);
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+
+        assert!(planet.asteroid().is_some());
+        assert!(!snapshot.get().defensive_mode);
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+    }
+
+    fn access_config(serve_read_only: bool) -> OrbitronConfig {
+        OrbitronConfig {
+            allowed_explorers: Some(HashSet::from([1, 2])),
+            denied_explorers: HashSet::from([2]),
+            serve_read_only_to_disallowed: serve_read_only,
+            ..Default::default()
+        }
+    }
+
+    fn supported_resources(explorer: &TestExplorer) -> HashSet<BasicResourceType> {
+        match explorer.request(ExplorerToPlanet::SupportedResourceRequest {
+            explorer_id: explorer.id,
+        }) {
+            PlanetToExplorer::SupportedResourceResponse { resource_list } => resource_list,
+            other => panic!("expected SupportedResourceResponse, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_explorer_access_consuming_request() {
+        let (planet, _) = TestPlanet::orbitron(access_config(true));
+        let allowed = planet.explorer(1);
+        let denied = planet.explorer(2);
+        let unlisted = planet.explorer(3);
+        planet.start();
+        planet.sunray();
+
+        // Refused explorers never touch the charged cell
+        assert!(denied.generate(BasicResourceType::Hydrogen).is_none());
+        assert!(unlisted.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(allowed.available_cells(), 1);
+        assert!(allowed.generate(BasicResourceType::Hydrogen).is_some());
+    }
+
+    #[test]
+    fn test_explorer_access_read_only_request() {
+        let (planet, _) = TestPlanet::orbitron(access_config(false));
+        let allowed = planet.explorer(1);
+        let denied = planet.explorer(2);
+        let unlisted = planet.explorer(3);
+        planet.start();
+
+        assert_eq!(supported_resources(&allowed).len(), 2);
+        assert!(supported_resources(&denied).is_empty());
+        assert!(supported_resources(&unlisted).is_empty());
+    }
+
+    #[test]
+    fn test_explorer_access_read_only_served_when_enabled() {
+        let (planet, _) = TestPlanet::orbitron(access_config(true));
+        let denied = planet.explorer(2);
+        let unlisted = planet.explorer(3);
+        planet.start();
+
+        assert_eq!(supported_resources(&denied).len(), 2);
+        assert_eq!(supported_resources(&unlisted).len(), 2);
+    }
+
+    #[test]
+    fn test_robot_combination_counts_product() {
+        let (planet, snapshot) = TestPlanet::custom(
+            PlanetType::B,
+            vec![BasicResourceType::Hydrogen],
+            vec![ComplexResourceType::Robot],
+            OrbitronConfig::default(),
+        );
+        let explorer = planet.explorer(5);
+        planet.start();
+        planet.sunray();
+
+        let (generator, combinator) = testkit::lab();
+        let silicon = generator.make_silicon(&mut charged_cell()).unwrap();
+        let carbon = generator.make_carbon(&mut charged_cell()).unwrap();
+        let hydrogen = generator.make_hydrogen(&mut charged_cell()).unwrap();
+        let oxygen = generator.make_oxygen(&mut charged_cell()).unwrap();
+        let water = combinator
+            .make_water(hydrogen, oxygen, &mut charged_cell())
+            .unwrap();
+        let life = combinator
+            .make_life(water, carbon, &mut charged_cell())
+            .unwrap();
+
+        let robot = explorer.combine(ComplexResourceRequest::Robot(silicon, life));
+        assert!(matches!(robot, Ok(ComplexResource::Robot(_))));
+        assert_eq!(
+            snapshot.get().complex_generated,
+            HashMap::from([(ComplexResourceType::Robot, 1)])
+        );
+    }
+
+    #[test]
+    fn test_unknown_recipe_keeps_cell_charged() {
+        let (planet, snapshot) = TestPlanet::orbitron(OrbitronConfig::default());
+        let mut planet = SteppedPlanet::new(planet);
+        planet.step_orchestrator(OrchestratorToPlanet::StartPlanetAI);
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+
+        let response = planet.step_explorer(ExplorerToPlanet::CombineResourceRequest {
+            explorer_id: 5,
+            msg: diamond_request(),
+        });
+        assert!(matches!(
+            response,
+            Some(PlanetToExplorer::CombineResourceResponse {
+                complex_response: Err(_)
+            })
+        ));
+        let response =
+            planet.step_explorer(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 5 });
+        assert!(matches!(
+            response,
+            Some(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 })
+        ));
+        assert!(snapshot.get().complex_generated.is_empty());
+    }
+
+    fn diamond_request() -> ComplexResourceRequest {
+        let (generator, _) = testkit::lab();
+        ComplexResourceRequest::Diamond(
+            generator.make_carbon(&mut charged_cell()).unwrap(),
+            generator.make_carbon(&mut charged_cell()).unwrap(),
+        )
+    }
+
+    /// Error and inputs handed back for a Diamond, which planet 42 can't make.
+    fn unknown_recipe_error(
+        policy: UnknownRecipePolicy,
+    ) -> (String, GenericResource, GenericResource) {
+        let ai = OrbitronBuilder::new(42)
+            .unknown_recipe_policy(policy)
+            .build();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        let error = explorer.combine(diamond_request()).unwrap_err();
+        assert_eq!(explorer.available_cells(), 1);
+        error
+    }
+
+    fn is_carbon(resource: &GenericResource) -> bool {
+        matches!(
+            resource,
+            GenericResource::BasicResources(BasicResource::Carbon(_))
+        )
+    }
+
+    #[test]
+    fn test_unknown_recipe_rejected_by_default() {
+        let (error, resource_1, resource_2) = unknown_recipe_error(UnknownRecipePolicy::Reject);
+        assert_eq!(error, "There isn't a recipe for Diamond");
+        assert!(is_carbon(&resource_1) && is_carbon(&resource_2));
+    }
+
+    #[test]
+    fn test_unknown_recipe_structured() {
+        let (error, resource_1, resource_2) = unknown_recipe_error(UnknownRecipePolicy::Structured);
+        assert_eq!(
+            error,
+            "UnsupportedRecipe requested=Diamond supported=[Water]"
+        );
+        assert!(is_carbon(&resource_1) && is_carbon(&resource_2));
+    }
+
+    #[test]
+    fn test_error_codes_on_failure_paths() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = OrbitronBuilder::new(42)
+            .config(OrbitronConfig {
+                allowed_explorers: Some(HashSet::from([1])),
+                error_codes: true,
+                ..Default::default()
+            })
+            .build();
+        ai.set_test_logger(Arc::clone(&logs));
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        let stranger = planet.explorer(2);
+        planet.start();
+
+        let code = |error: (String, GenericResource, GenericResource)| {
+            crate::error_code(&error.0).expect("error text starts with a code")
+        };
+        assert_eq!(explorer.generate(BasicResourceType::Hydrogen), None);
+        assert_eq!(code(explorer.combine(water_request()).unwrap_err()), 1);
+        assert_eq!(code(stranger.combine(water_request()).unwrap_err()), 9);
+        planet.sunray();
+        assert_eq!(code(explorer.combine(diamond_request()).unwrap_err()), 2);
+
+        // Generate responses carry no error, the code is only logged
+        let generate_codes: Vec<String> = logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|event| event.payload.contains_key("Generated Resource"))
+            .filter_map(|event| event.payload.get("ErrorCode").cloned())
+            .collect();
+        assert_eq!(generate_codes, ["1"]);
+    }
+
+    #[test]
+    fn test_unknown_recipe_ignored() {
+        let ai = OrbitronBuilder::new(42)
+            .unknown_recipe_policy(UnknownRecipePolicy::Ignore)
+            .build();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        explorer.send(ExplorerToPlanet::CombineResourceRequest {
+            explorer_id: 1,
+            msg: diamond_request(),
+        });
+        // The first answer is the one to the next request
+        assert_eq!(explorer.available_cells(), 1);
+        assert!(explorer.received().is_empty());
+
+        // Supported recipes are still combined
+        assert!(explorer.combine(water_request()).is_ok());
+    }
+
+    #[test]
+    fn test_unsupported_recipe_without_charge_is_not_a_refusal() {
+        let (planet, snapshot) = TestPlanet::orbitron(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        // The recipe is checked first: no charged cell is not the answer
+        let Err((error, _, _)) = explorer.combine(diamond_request()) else {
+            panic!("expected a failed combination");
+        };
+        assert_ne!(error, OrbitronError::NoChargedCell.to_string());
+        assert!(snapshot.get().refusal_reasons.is_empty());
+
+        assert_eq!(
+            combine_error(&explorer),
+            OrbitronError::NoChargedCell.to_string()
+        );
+        assert_eq!(
+            snapshot.get().refusal_reasons,
+            HashMap::from([(RefusalReason::NoChargedCell, 1)])
+        );
+    }
+
+    fn water_request() -> ComplexResourceRequest {
+        let (generator, _) = testkit::lab();
+        ComplexResourceRequest::Water(
+            generator.make_hydrogen(&mut charged_cell()).unwrap(),
+            generator.make_oxygen(&mut charged_cell()).unwrap(),
+        )
+    }
+
+    fn combine_error(explorer: &TestExplorer) -> String {
+        match explorer.combine(water_request()) {
+            Err((error, _, _)) => error,
+            Ok(product) => panic!("expected a failed combination, got {product:?}"),
+        }
+    }
+
+    #[test]
+    fn test_combines_throttled_per_tick() {
+        let config = OrbitronConfig {
+            max_combines_per_tick: 1,
+            ..Default::default()
+        };
+        let (planet, _) = TestPlanet::orbitron(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.combine(water_request()).is_ok());
+        assert_eq!(
+            combine_error(&explorer),
+            OrbitronError::RateLimited.to_string()
+        );
+
+        // A new tick lifts the limit
+        planet.sunray();
+        assert!(explorer.combine(water_request()).is_ok());
+    }
+
+    #[test]
+    fn test_throttled_combine_keeps_cell() {
+        let config = OrbitronConfig {
+            max_combines_per_tick: 0,
+            ..Default::default()
+        };
+        let (planet, _) = TestPlanet::orbitron(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert_eq!(
+            combine_error(&explorer),
+            OrbitronError::RateLimited.to_string()
+        );
+        assert_eq!(explorer.available_cells(), 1);
+    }
+
+    #[test]
+    fn test_failed_combinations_trigger_backoff() {
+        let (planet, _) = TestPlanet::orbitron(OrbitronConfig::default());
+        let explorer_7 = planet.explorer(7);
+        let explorer_8 = planet.explorer(8);
+        planet.start();
+
+        // No sunray yet, so every Water combination fails
+        for _ in 0..3 {
+            assert_eq!(
+                combine_error(&explorer_7),
+                OrbitronError::NoChargedCell.to_string()
+            );
+        }
+        assert_eq!(
+            combine_error(&explorer_7),
+            OrbitronError::BackedOff.to_string()
+        );
+        assert_eq!(
+            combine_error(&explorer_8),
+            OrbitronError::NoChargedCell.to_string()
+        );
+    }
+
+    #[test]
+    fn test_backoff_cleared_by_success() {
+        let (planet, _) = TestPlanet::orbitron(OrbitronConfig::default());
+        let explorer = planet.explorer(7);
+        planet.start();
+
+        for _ in 0..3 {
+            combine_error(&explorer);
+        }
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        assert_eq!(
+            combine_error(&explorer),
+            OrbitronError::NoChargedCell.to_string()
+        );
+    }
+
+    fn paired_planet(auto_pair: bool) -> (TestPlanet, SnapshotHandle) {
+        let config = OrbitronConfig {
+            max_inflight: 1,
+            auto_pair,
+            ..Default::default()
+        };
+        TestPlanet::custom(
+            PlanetType::D,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![],
+            config,
+        )
+    }
+
+    #[test]
+    fn test_auto_pair_fast_tracks_oxygen() {
+        let (planet, _) = paired_planet(true);
+        let explorer = planet.explorer(1);
+        let other = planet.explorer(2);
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        // Only the explorer that asked for Hydrogen gets past the cap, once
+        assert!(other.generate(BasicResourceType::Oxygen).is_none());
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+    }
+
+    #[test]
+    fn test_pairs_rate_limited_without_auto_pair() {
+        let (planet, _) = paired_planet(false);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+    }
+
+    #[test]
+    fn test_inflight_cap_limits_generations_per_tick() {
+        let config = OrbitronConfig {
+            max_inflight: 1,
+            ..Default::default()
+        };
+        let (planet, _) = TestPlanet::custom(
+            PlanetType::D,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![],
+            config,
+        );
+        let mut planet = SteppedPlanet::new(planet);
+        let generate = |planet: &mut SteppedPlanet, resource| {
+            let msg = ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id: 3,
+                resource,
+            };
+            match planet.step_explorer(msg) {
+                Some(PlanetToExplorer::GenerateResourceResponse { resource }) => resource.is_some(),
+                other => panic!("expected GenerateResourceResponse, got {other:?}"),
+            }
+        };
+        planet.step_orchestrator(OrchestratorToPlanet::StartPlanetAI);
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+
+        assert!(generate(&mut planet, BasicResourceType::Hydrogen));
+        assert!(!generate(&mut planet, BasicResourceType::Oxygen));
+        let response =
+            planet.step_explorer(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 3 });
+        assert!(matches!(
+            response,
+            Some(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 })
+        ));
+
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+        assert!(generate(&mut planet, BasicResourceType::Oxygen));
+    }
+
+    #[test]
+    fn test_inflight_cap_ignores_failed_generations() {
+        let config = OrbitronConfig {
+            max_inflight: 1,
+            ..Default::default()
+        };
+        let (planet, _) = TestPlanet::custom(
+            PlanetType::D,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![],
+            config,
+        );
+        let explorer = planet.explorer(3);
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+
+        // Unsupported: fails without a cell, so the cap is still free
+        assert!(explorer.generate(BasicResourceType::Carbon).is_none());
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+        assert_eq!(explorer.available_cells(), 1);
+    }
+
+    fn escrow_config(capacity: usize) -> OrbitronConfig {
+        OrbitronConfig {
+            escrow_capacity: capacity,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_escrow_delivers_without_spending_cell() {
+        let (planet, snapshot) = TestPlanet::orbitron(escrow_config(4));
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(snapshot.get().escrowed, 1);
+        assert_eq!(explorer.available_cells(), 0);
+
+        assert!(matches!(
+            explorer.generate(BasicResourceType::Hydrogen),
+            Some(BasicResource::Hydrogen(_))
+        ));
+        assert_eq!(snapshot.get().escrowed, 0);
+    }
+
+    #[test]
+    fn test_escrow_delivers_in_defensive_mode() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 2,
+            ..escrow_config(4)
+        };
+        let (planet, snapshot) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        assert!(planet.asteroid().is_some());
+        assert!(snapshot.get().defensive_mode);
+
+        assert!(matches!(
+            explorer.generate(BasicResourceType::Hydrogen),
+            Some(BasicResource::Hydrogen(_))
+        ));
+        // Nothing else is served until the planet leaves defensive mode
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+    }
+
+    #[test]
+    fn test_escrow_evicts_oldest() {
+        let (planet, snapshot) = TestPlanet::orbitron(escrow_config(1));
+        let first = planet.explorer(1);
+        let second = planet.explorer(2);
+        planet.start();
+
+        planet.sunray();
+        assert!(first.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        assert!(second.generate(BasicResourceType::Oxygen).is_none());
+        assert_eq!(snapshot.get().escrowed, 1);
+
+        // The first explorer's Hydrogen was evicted and no cell is left
+        assert!(first.generate(BasicResourceType::Hydrogen).is_none());
+        assert!(second.generate(BasicResourceType::Oxygen).is_some());
+    }
+
+    #[test]
+    fn test_inventory_lists_escrowed_resources() {
+        let (planet, snapshot) = TestPlanet::orbitron(escrow_config(4));
+        let first = planet.explorer(1);
+        let second = planet.explorer(2);
+        planet.start();
+
+        planet.sunray();
+        assert!(first.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        assert!(second.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(
+            snapshot.get().inventory,
+            HashMap::from([(BasicResourceType::Hydrogen, 2)])
+        );
+
+        assert!(first.generate(BasicResourceType::Hydrogen).is_some());
+        assert_eq!(
+            snapshot.get().inventory,
+            HashMap::from([(BasicResourceType::Hydrogen, 1)])
+        );
+    }
+
+    #[test]
+    fn test_escrow_disabled_by_default() {
+        let (planet, snapshot) = TestPlanet::orbitron(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert_eq!(snapshot.get().escrowed, 0);
+        assert!(snapshot.get().inventory.is_empty());
+    }
+
+    #[test]
+    fn test_refusal_reasons_are_counted() {
+        let config = OrbitronConfig {
+            denied_explorers: HashSet::from([9]),
+            max_inflight: 1,
+            ..Default::default()
+        };
+        let (planet, snapshot) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        let denied = planet.explorer(9);
+        planet.start();
+
+        assert!(denied.generate(BasicResourceType::Hydrogen).is_none());
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        planet.asteroid();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+
+        // `Stopped` can't be produced here: the planet loop answers explorers
+        // itself while the AI is stopped
+        assert_eq!(
+            snapshot.get().refusal_reasons,
+            HashMap::from([
+                (RefusalReason::NotAllowed, 1),
+                (RefusalReason::NoChargedCell, 1),
+                (RefusalReason::RateLimited, 1),
+                (RefusalReason::ReservePolicy, 1),
+            ])
+        );
+    }
+
+    /// Payload entries measuring wall-clock time, which no seed controls.
+    const TIMING_KEYS: [&str; 3] = ["Timings", "stalled_ms", "timeout_ms"];
+
+    /// Runs a fixed scenario that draws from the seeded generator on the
+    /// handler path, and returns everything it logged minus timings.
+    fn seeded_run(seed: u64) -> Vec<CapturedEvent> {
+        let (planet, _, logs) = captured_planet(OrbitronConfig {
+            rng_seed: Some(seed),
+            suggest_alternatives: true,
+            generation_weights: HashMap::from([
+                (BasicResourceType::Hydrogen, 1),
+                (BasicResourceType::Oxygen, 1),
+            ]),
+            ..Default::default()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        for resource in [BasicResourceType::Hydrogen, BasicResourceType::Oxygen] {
+            planet.sunray();
+            // Each suggestion for Carbon is a weighted draw
+            for _ in 0..8 {
+                assert!(explorer.generate(BasicResourceType::Carbon).is_none());
+            }
+            assert!(explorer.generate(resource).is_some());
+        }
+        explorer.available_cells();
+        planet.send(OrchestratorToPlanet::StopPlanetAI);
+        planet.recv();
+
+        let mut events = logs.lock().unwrap().clone();
+        for event in &mut events {
+            event
+                .payload
+                .retain(|key, _| !TIMING_KEYS.contains(&key.as_str()));
+        }
+        events
+    }
+
+    #[test]
+    fn test_same_seed_same_logs() {
+        assert_eq!(seeded_run(1234), seeded_run(1234));
+        // The draws do reach the logs
+        assert_ne!(seeded_run(1234), seeded_run(4321));
+    }
+
+    /// Resources an AI picks on its own over many uniform draws.
+    fn picks(mut ai: Orbitron) -> Vec<Option<BasicResourceType>> {
+        let supported = HashSet::from([BasicResourceType::Hydrogen, BasicResourceType::Oxygen]);
+        (0..32).map(|_| ai.choose_resource(&supported)).collect()
+    }
+
+    #[test]
+    fn test_seed_drives_random_decisions() {
+        let seeded = |seed| OrbitronBuilder::new(1).seed(seed).build();
+        assert_eq!(picks(seeded(7)), picks(seeded(7)));
+        assert_ne!(picks(seeded(7)), picks(seeded(8)));
+    }
+
+    #[test]
+    fn test_default_seed_derived_from_planet_id() {
+        assert_eq!(picks(Orbitron::new(1)), picks(Orbitron::new(1)));
+        assert_ne!(picks(Orbitron::new(1)), picks(Orbitron::new(2)));
+    }
+
+    /// Synthetic cells, for states the framework can't build.
+    struct FakeCells {
+        total: usize,
+        charged: u32,
+    }
+
+    impl CellView for FakeCells {
+        fn total_cells(&self) -> usize {
+            self.total
+        }
+
+        fn charged_cells(&self) -> u32 {
+            self.charged
+        }
+
+        /// The charged cells are the first ones.
+        fn is_cell_charged(&self, index: usize) -> bool {
+            index < self.charged as usize
+        }
+    }
+
+    #[test]
+    fn test_zero_cell_state_is_degenerate() {
+        let cells = FakeCells {
+            total: 0,
+            charged: 0,
+        };
+        assert!(has_no_cells(&cells));
+        assert_eq!(charged_cells(&cells), 0);
+        assert!(!can_attempt_rocket(&cells, true));
+        assert!(!can_attempt_rocket(&cells, false));
+    }
+
+    #[test]
+    fn test_cells_present_allow_rocket_only_when_supported() {
+        let cells = FakeCells {
+            total: 5,
+            charged: 2,
+        };
+        assert!(!has_no_cells(&cells));
+        assert_eq!(charged_cells(&cells), 2);
+        assert!(can_attempt_rocket(&cells, true));
+        assert!(!can_attempt_rocket(&cells, false));
+    }
+
+    #[test]
+    fn test_charge_target_outcomes() {
+        let cells = |total, charged| FakeCells { total, charged };
+        for order in [ChargeOrder::Default, ChargeOrder::RoundRobin] {
+            assert_eq!(
+                charge_target(&cells(0, 0), order, None),
+                ChargeOutcome::NoCells
+            );
+            assert_eq!(
+                charge_target(&cells(3, 3), order, Some(1)),
+                ChargeOutcome::AllFull
+            );
+            assert_eq!(
+                charge_target(&cells(3, 1), order, None),
+                ChargeOutcome::Charged(1)
+            );
+        }
+        // Round-robin starts after the last charged cell and wraps around
+        assert_eq!(
+            charge_target(&cells(3, 1), ChargeOrder::Default, Some(1)),
+            ChargeOutcome::Charged(1)
+        );
+        assert_eq!(
+            charge_target(&cells(3, 1), ChargeOrder::RoundRobin, Some(1)),
+            ChargeOutcome::Charged(2)
+        );
+        assert_eq!(
+            charge_target(&cells(3, 2), ChargeOrder::RoundRobin, Some(2)),
+            ChargeOutcome::Charged(2)
+        );
+    }
+
+    #[test]
+    fn test_combine_check_preflights_request() {
+        let ai = Orbitron::new(1);
+        let recipes = HashSet::from([ComplexResourceType::Water]);
+        let uncharged = FakeCells {
+            total: 1,
+            charged: 0,
+        };
+        let charged = FakeCells {
+            total: 1,
+            charged: 1,
+        };
+
+        assert_eq!(
+            ai.combine_check(&uncharged, &recipes, ComplexResourceType::Water, false),
+            Err(OrbitronError::NoChargedCell)
+        );
+        assert_eq!(
+            ai.combine_check(&charged, &recipes, ComplexResourceType::Diamond, false),
+            Err(OrbitronError::UnsupportedCombination(
+                ComplexResourceType::Diamond
+            ))
+        );
+        assert_eq!(
+            ai.combine_check(&charged, &recipes, ComplexResourceType::Water, true),
+            Err(OrbitronError::Defensive)
+        );
+        assert_eq!(
+            ai.combine_check(&charged, &recipes, ComplexResourceType::Water, false),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn test_survivability_verdicts() {
+        // Type A: five cells, rockets allowed.
+        let type_a = FakeCells {
+            total: 5,
+            charged: 1,
+        };
+        assert_eq!(survivability(&type_a, true, true), Survivability::HasRocket);
+        assert_eq!(
+            survivability(&type_a, false, true),
+            Survivability::CanBuildRocket { cost: ROCKET_COST }
+        );
+        let drained = FakeCells {
+            total: 5,
+            charged: 0,
+        };
+        assert_eq!(
+            survivability(&drained, false, true),
+            Survivability::Doomed {
+                reason: NO_CHARGE_ERROR
+            }
+        );
+
+        // Type B: one cell, no rockets.
+        let type_b = FakeCells {
+            total: 1,
+            charged: 1,
+        };
+        assert_eq!(
+            survivability(&type_b, false, false),
+            Survivability::Doomed {
+                reason: NO_ROCKETS_ERROR
+            }
+        );
+    }
+
+    #[test]
+    fn test_type_a_survives_asteroid_when_charged() {
+        let (planet, handle) = rocket_planet(OrbitronConfig::default());
+        planet.start();
+
+        planet.sunray();
+        assert!(planet.asteroid().is_some());
+        let snapshot = handle.get();
+        assert_eq!(snapshot.lifecycle, Lifecycle::Running);
+        assert_eq!(snapshot.asteroids_faced, 1);
+        assert_eq!(snapshot.asteroids_survived, 1);
+        let event = snapshot.last_asteroid_event;
+        assert_eq!(event["asteroid_seq"], "1");
+        assert!(event.contains_key("rocket_debug"));
+
+        // The only charged cell went into the first rocket
+        assert!(planet.asteroid().is_none());
+        let snapshot = handle.get();
+        assert_eq!(snapshot.lifecycle, Lifecycle::Destroyed);
+        let event = snapshot.last_asteroid_event;
+        assert_eq!(event["charged_cells"], "0");
+        assert_eq!(event["rocket_cost"], ROCKET_COST.to_string());
+        assert_eq!(event["rocket_failure"], NO_CHARGE_ERROR);
+    }
+
+    #[test]
+    fn test_type_b_is_doomed_by_asteroid() {
+        let (planet, handle) = TestPlanet::orbitron(OrbitronConfig::default());
+        planet.start();
+        planet.sunray();
+
+        assert!(planet.asteroid().is_none());
+        let snapshot = handle.get();
+        assert_eq!(snapshot.lifecycle, Lifecycle::Destroyed);
+        assert_eq!(snapshot.asteroids_faced, 1);
+        assert_eq!(snapshot.asteroids_survived, 0);
+        let event = snapshot.last_asteroid_event;
+        assert_eq!(event["asteroid_seq"], "1");
+        assert_eq!(event["charged_cells"], "1");
+        assert_eq!(event["can_have_rocket"], "false");
+        assert_eq!(event["rocket_failure"], NO_ROCKETS_ERROR);
+        assert!(!event.contains_key("rocket_cost"));
+        assert!(!event.contains_key("rocket_debug"));
+    }
+
+    #[test]
+    fn test_destroyed_planet_ignores_messages() {
+        let (planet, handle) = TestPlanet::orbitron(OrbitronConfig::default());
+        planet.start();
+        planet.sunray();
+        assert!(planet.asteroid().is_none());
+        let destroyed = handle.get();
+        assert_eq!(destroyed.lifecycle, Lifecycle::Destroyed);
+        assert!(!destroyed.defensive_mode);
+
+        // Neither time nor lifecycle moves on any more. The planet loop is
+        // still running, so it answers the start without reaching the AI.
+        planet.sunray();
+        planet.start();
+        assert_eq!(
+            handle.get(),
+            OrbitronSnapshot {
+                messages_after_destruction: 1,
+                // The dead planet warning
+                events_emitted: destroyed.events_emitted + 1,
+                ..destroyed
+            }
+        );
+    }
+
+    #[test]
+    fn test_asteroid_history() {
+        let mut payload = Payload::new();
+        Orbitron::new(1).insert_asteroid_counts(&mut payload);
+        assert_eq!(payload["Last Asteroid Tick"], "never");
+
+        // A single cell, spent on the first rocket and then by the explorer
+        let (planet, handle) = TestPlanet::custom(
+            PlanetType::C,
+            vec![BasicResourceType::Hydrogen],
+            vec![],
+            OrbitronConfig {
+                defensive_sunrays: 0,
+                ..Default::default()
+            },
+        );
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        assert!(planet.asteroid().is_some());
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        assert!(planet.asteroid().is_none());
+
+        let snapshot = handle.get();
+        assert_eq!(snapshot.asteroids_faced, 2);
+        assert_eq!(snapshot.asteroids_survived, 1);
+        assert_eq!(snapshot.last_asteroid_tick, Some(2));
+        assert_eq!(snapshot.lifecycle, Lifecycle::Destroyed);
+    }
+
+    #[test]
+    fn test_destroyed_planet_counts_late_messages() {
+        let (planet, handle, logs) = captured_planet(OrbitronConfig::default());
+        let mut planet = SteppedPlanet::new(planet);
+        planet.step_orchestrator(OrchestratorToPlanet::StartPlanetAI);
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+        planet.step_orchestrator(OrchestratorToPlanet::Asteroid(testkit::asteroid()));
+        assert_eq!(handle.get().lifecycle, Lifecycle::Destroyed);
+
+        let response = planet.step_explorer(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: 1,
+            resource: BasicResourceType::Oxygen,
+        });
+        assert!(matches!(
+            response,
+            Some(PlanetToExplorer::GenerateResourceResponse { resource: None })
+        ));
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+
+        // The charged cell survived both
+        match planet.step_orchestrator(OrchestratorToPlanet::InternalStateRequest) {
+            Some(PlanetToOrchestrator::InternalStateResponse { planet_state, .. }) => {
+                assert_eq!(planet_state.charged_cells_count, 1);
+            }
+            other => panic!("expected InternalStateResponse, got {other:?}"),
+        }
+        assert_eq!(handle.get().messages_after_destruction, 2);
+        assert_eq!(warnings(&logs, "dead_planet"), 1);
+    }
+
+    #[test]
+    fn test_pending_explorer_count_reads_queue() {
+        let mut ai = Orbitron::new(1);
+        assert_eq!(ai.pending_explorer_count(), 0);
+
+        let (tx, rx) = crossbeam_channel::unbounded();
+        ai.watch_explorer_queue(rx.clone());
+        for explorer_id in 0..3 {
+            tx.send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id })
+                .unwrap();
+        }
+        assert_eq!(ai.pending_explorer_count(), 3);
+
+        rx.recv().unwrap();
+        assert_eq!(ai.pending_explorer_count(), 2);
+    }
+
+    #[test]
+    fn test_watchdog_reports_wedged_loop() {
+        use std::time::{Duration, Instant};
+
+        let (to_planet, rx_orch) = crossbeam_channel::unbounded();
+        // Room for a single answer: the orchestrator never reads, so the
+        // second one blocks the run loop
+        let (tx_orch, from_planet) = crossbeam_channel::bounded(1);
+        let (explorer_tx, rx_expl) = crossbeam_channel::unbounded();
+
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::with_config(
+            1,
+            OrbitronConfig {
+                watchdog_timeout: Some(Duration::from_millis(50)),
+                ..Default::default()
+            },
+        );
+        ai.set_test_logger(Arc::clone(&logs));
+        ai.watch_explorer_queue(rx_expl.clone());
+        let mut planet = crate::create_planet_with_ai(rx_orch, tx_orch, rx_expl, 1, Box::new(ai));
+        let thread = std::thread::spawn(move || planet.run());
+
+        to_planet.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        to_planet
+            .send(OrchestratorToPlanet::Sunray(testkit::sunray()))
+            .unwrap();
+        // Queued once the loop took the Sunray: its ack can't fit in the
+        // channel, still holding the start answer
+        let deadline = Instant::now() + testkit::TIMEOUT;
+        while !(to_planet.is_empty() && from_planet.is_full()) {
+            assert!(Instant::now() < deadline, "the loop never got stuck");
+            std::thread::yield_now();
+        }
+        explorer_tx
+            .send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 })
+            .unwrap();
+
+        let stalls = || -> Vec<CapturedEvent> {
+            logs.lock()
+                .unwrap()
+                .iter()
+                .filter(|captured| {
+                    captured.payload.get("event").map(String::as_str) == Some("watchdog_stall")
+                })
+                .cloned()
+                .collect()
+        };
+        while stalls().is_empty() {
+            assert!(Instant::now() < deadline, "no stall reported");
+            std::thread::yield_now();
+        }
+        // The loop can't beat again while stuck, so no second report follows
+        let stalls = stalls();
+        assert_eq!(stalls.len(), 1);
+        assert_eq!(stalls[0].channel, Channel::Error);
+        assert_eq!(stalls[0].payload["pending_explorers"], "1");
+
+        // Unwedge the loop and shut the planet down
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        drop(explorer_tx);
+        while from_planet.recv_timeout(testkit::TIMEOUT).is_ok() {}
+        thread.join().unwrap().ok();
+    }
+
+    #[test]
+    fn test_queued_read_only_messages_reserve_cells() {
+        use std::time::Instant;
+
+        let (to_planet, rx_orch) = crossbeam_channel::unbounded();
+        // Room for a single answer, so the loop can be held while explorer
+        // messages queue up
+        let (tx_orch, from_planet) = crossbeam_channel::bounded(1);
+        let (explorer_tx, rx_expl) = crossbeam_channel::unbounded();
+        let mut ai = Orbitron::with_config(
+            1,
+            OrbitronConfig {
+                reserve_cells_for_queued: true,
+                ..Default::default()
+            },
+        );
+        ai.watch_explorer_queue(rx_expl.clone());
+        let mut planet = crate::create_planet_with_ai(rx_orch, tx_orch, rx_expl, 1, Box::new(ai));
+        let thread = std::thread::spawn(move || planet.run());
+
+        let request = |msg| {
+            to_planet.send(msg).unwrap();
+            from_planet.recv_timeout(testkit::TIMEOUT).unwrap()
+        };
+        let (new_sender, from_orbitron) = crossbeam_channel::unbounded();
+        request(OrchestratorToPlanet::StartPlanetAI);
+        request(OrchestratorToPlanet::IncomingExplorerRequest {
+            explorer_id: 1,
+            new_sender,
+        });
+        request(OrchestratorToPlanet::Sunray(testkit::sunray()));
+
+        // Hold the loop on its second unread Sunray ack
+        for _ in 0..2 {
+            to_planet
+                .send(OrchestratorToPlanet::Sunray(testkit::sunray()))
+                .unwrap();
+        }
+        let deadline = Instant::now() + testkit::TIMEOUT;
+        while !(to_planet.is_empty() && from_planet.is_full()) {
+            assert!(Instant::now() < deadline, "the loop never got stuck");
+            std::thread::yield_now();
+        }
+        explorer_tx
+            .send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 })
+            .unwrap();
+        explorer_tx
+            .send(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 })
+            .unwrap();
+        for _ in 0..2 {
+            from_planet.recv_timeout(testkit::TIMEOUT).unwrap();
+        }
+
+        // The charged cell is held back for the queued request, even though
+        // that one only reads
+        assert!(matches!(
+            from_orbitron.recv_timeout(testkit::TIMEOUT),
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 0 })
+        ));
+
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        drop(explorer_tx);
+        while from_planet.recv_timeout(testkit::TIMEOUT).is_ok() {}
+        thread.join().unwrap().ok();
+    }
+
+    #[test]
+    fn test_watchdog_drop_returns_at_once() {
+        use std::time::{Duration, Instant};
+
+        let watchdog = Watchdog::spawn(
+            Duration::from_secs(3600),
+            Heartbeat::default(),
+            || false,
+            |_| {},
+        );
+        let dropped_at = Instant::now();
+        drop(watchdog);
+        assert!(dropped_at.elapsed() < testkit::TIMEOUT);
+    }
+
+    #[test]
+    fn test_asteroid_ack_waits_for_full_channel() {
+        use std::time::Duration;
+
+        let (to_planet, rx_orch) = crossbeam_channel::unbounded();
+        let (tx_orch, from_planet) = crossbeam_channel::bounded(1);
+        let (_explorer_tx, rx_expl) = crossbeam_channel::unbounded();
+        let mut planet = crate::create_planet(rx_orch, tx_orch, rx_expl, 1);
+        let thread = std::thread::spawn(move || planet.run());
+
+        // StartPlanetAIResult fills the channel, the ack has to wait
+        to_planet.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        to_planet
+            .send(OrchestratorToPlanet::Asteroid(testkit::asteroid()))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(100));
+
+        assert!(matches!(
+            from_planet.recv_timeout(testkit::TIMEOUT),
+            Ok(PlanetToOrchestrator::StartPlanetAIResult { .. })
+        ));
+        assert!(matches!(
+            from_planet.recv_timeout(testkit::TIMEOUT),
+            Ok(PlanetToOrchestrator::AsteroidAck { planet_id: 1, .. })
+        ));
+
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        while from_planet.recv_timeout(testkit::TIMEOUT).is_ok() {}
+        thread.join().unwrap().ok();
+    }
+
+    #[test]
+    fn test_auto_start_builds_running_ai() {
+        let ai = OrbitronBuilder::new(1).build();
+        assert_eq!(ai.snapshot(1).lifecycle, Lifecycle::Created);
+
+        let ai = OrbitronBuilder::new(1).auto_start(true).build();
+        assert_eq!(ai.snapshot_handle().get().lifecycle, Lifecycle::Running);
+    }
+
+    #[test]
+    fn test_config_reflects_builder_options() {
+        let config = OrbitronConfig {
+            defensive_sunrays: 0,
+            denied_explorers: HashSet::from([3]),
+            max_inflight: 2,
+            auto_pair: true,
+            asteroid_damage: 1,
+            ..Default::default()
+        };
+        let ai = OrbitronBuilder::new(1)
+            .config(config.clone())
+            .seed(9)
+            .event_history(4)
+            .build();
+
+        let expected = OrbitronConfig {
+            rng_seed: Some(9),
+            event_history: 4,
+            ..config
+        };
+        assert_eq!(ai.config(), expected);
+        assert_eq!(ai.snapshot_handle().get().config, expected);
+    }
+
+    #[test]
+    fn test_auto_started_ai_accepts_start() {
+        let ai = OrbitronBuilder::new(42).auto_start(true).build();
+        let handle = ai.snapshot_handle();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+
+        // The planet loop still waits for its own start
+        planet.start();
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        assert_eq!(handle.get().lifecycle, Lifecycle::Running);
+    }
+
+    #[test]
+    fn test_recent_events_keep_last_entries() {
+        let ai = OrbitronBuilder::new(42).event_history(64).build();
+        let handle = ai.snapshot_handle();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        planet.start();
+        for _ in 0..70 {
+            planet.sunray();
+        }
+
+        let events = handle.get().recent_events;
+        assert_eq!(events.len(), 64);
+        let ticks: Vec<u64> = events.iter().map(|event| event.tick).collect();
+        assert_eq!(ticks, (7..=70).collect::<Vec<u64>>());
+        assert_eq!(
+            events[0],
+            EventRecord {
+                message: "Sunray".into(),
+                response: Some("SunrayAck".into()),
+                tick: 7,
+            }
+        );
+    }
+
+    #[test]
+    fn test_recent_history_keeps_last_entries() {
+        let mut ai = OrbitronBuilder::new(1).post_mortem_history(2).build();
+        ai.record(
+            Direction::OrchestratorToPlanet,
+            "Sunray",
+            Some("SunrayAck".into()),
+        );
+        ai.record(
+            Direction::ExplorerToPlanet,
+            "Generate Resource Request",
+            None,
+        );
+        ai.record(
+            Direction::OrchestratorToPlanet,
+            "Sunray",
+            Some("SunrayAck".into()),
+        );
+
+        let seqs: Vec<u64> = ai.recent_history().iter().map(|entry| entry.seq).collect();
+        assert_eq!(seqs, [2, 3]);
+        assert_eq!(
+            ai.recent_history()[0],
+            HistoryEntry {
+                seq: 2,
+                corr_id: 0,
+                direction: Direction::ExplorerToPlanet,
+                message: "Generate Resource Request".into(),
+                outcome: "-".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_gc_drops_stale_explorer_state() {
+        let mut ai = Orbitron::with_config(
+            1,
+            OrbitronConfig {
+                explorer_horizon: Some(100),
+                ..Default::default()
+            },
+        );
+        let tick = |ai: &mut Orbitron| ai.record(Direction::OrchestratorToPlanet, "Sunray", None);
+        ai.track_explorer(1, 7, false);
+        ai.record_combine_failure(1, 7);
+        ai.suggestions.insert(7, BasicResourceType::Oxygen);
+        tick(&mut ai);
+        while ai.logical_time < GC_INTERVAL - 1 {
+            ai.track_explorer(1, 8, true);
+            tick(&mut ai);
+        }
+        assert_eq!(ai.age_of(1), GC_INTERVAL - 2);
+        assert!(ai.explorers.get(7).is_some());
+
+        // The 256th message runs the collection
+        tick(&mut ai);
+        assert!(ai.explorers.get(7).is_none());
+        assert!(!ai.combine_failures.contains_key(&7));
+        assert!(!ai.suggestions.contains_key(&7));
+        assert!(ai.explorers.get(8).is_some());
+    }
+
+    #[test]
+    fn test_backoff_window_in_logical_time() {
+        let run = || {
+            let mut ai = Orbitron::with_config(
+                1,
+                OrbitronConfig {
+                    combine_backoff_threshold: 2,
+                    combine_backoff_reset: 5,
+                    ..Default::default()
+                },
+            );
+            for _ in 0..2 {
+                ai.record_combine_failure(1, 7);
+                ai.record(
+                    Direction::ExplorerToPlanet,
+                    "Combine Resource Request",
+                    None,
+                );
+            }
+            // Sunrays count as much as explorer messages
+            (0..8)
+                .map(|_| {
+                    let backing_off = ai.is_backing_off(7);
+                    ai.record(Direction::OrchestratorToPlanet, "Sunray", None);
+                    backing_off
+                })
+                .collect::<Vec<bool>>()
+        };
+        let window = run();
+        assert_eq!(window, [true, true, true, true, true, false, false, false]);
+        assert_eq!(run(), window);
+    }
+
+    #[test]
+    fn test_asteroid_dumps_recent_history() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+        for i in 0..60 {
+            if i % 3 == 2 {
+                explorer.available_cells();
+            } else {
+                planet.sunray();
+            }
+        }
+        planet.asteroid();
+
+        let dump: Vec<Payload> = logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| {
+                captured.payload.get("event").map(String::as_str) == Some("history_dump")
+            })
+            .map(|captured| captured.payload.clone())
+            .collect();
+        // 60 messages and the asteroid, the first 11 fell out of the window
+        assert_eq!(dump.len(), 50);
+        for (i, line) in dump.iter().enumerate() {
+            let seq = i as u64 + 12;
+            assert_eq!(line["line"], format!("{}/50", i + 1));
+            assert_eq!(line["seq"], seq.to_string());
+            assert_eq!(line["trigger"], "destroyed");
+            let expected = match seq {
+                61 => "Asteroid",
+                seq if seq % 3 == 0 => "Available Energy Cell Request",
+                _ => "Sunray",
+            };
+            assert_eq!(line["message"], expected, "seq {seq}");
+        }
+    }
+
+    #[test]
+    fn test_exchange_events_share_corr_id() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+        explorer.available_cells();
+        planet.asteroid();
+
+        let logs = logs.lock().unwrap();
+        let corr_id = |event_type| {
+            logs.iter()
+                .find(|captured| captured.event_type == event_type)
+                .map(|captured| captured.payload["corr_id"].clone())
+                .unwrap()
+        };
+        let (inbound, outbound) = (
+            corr_id(EventType::MessageExplorerToPlanet),
+            corr_id(EventType::MessagePlanetToExplorer),
+        );
+        assert_eq!(inbound, outbound);
+        // The history entry dumped on the asteroid carries it too
+        let recorded = logs
+            .iter()
+            .find(|captured| {
+                captured.payload.get("message").map(String::as_str)
+                    == Some("Available Energy Cell Request")
+            })
+            .map(|captured| captured.payload["entry_corr_id"].clone())
+            .unwrap();
+        assert_eq!(recorded, inbound);
+        let asteroid = logs
+            .iter()
+            .find(|captured| captured.payload.contains_key("entry_corr_id"))
+            .map(|captured| captured.payload["corr_id"].clone())
+            .unwrap();
+        assert_ne!(asteroid, inbound);
+    }
+
+    #[test]
+    fn test_snapshot_display_empty() {
+        let snapshot = OrbitronSnapshot {
+            planet_id: 42,
+            ..Default::default()
+        };
+        assert_eq!(
+            snapshot.to_string(),
+            "Orbitron #42 [Created] tick 0, produced nothing, asteroids survived 0/0, refused none"
+        );
+    }
+
+    #[test]
+    fn test_snapshot_display_populated() {
+        let snapshot = OrbitronSnapshot {
+            planet_id: 42,
+            lifecycle: Lifecycle::Running,
+            defensive_mode: true,
+            tick: 7,
+            escrowed: 2,
+            complex_generated: HashMap::from([
+                (ComplexResourceType::Robot, 1),
+                (ComplexResourceType::Water, 3),
+            ]),
+            refusal_reasons: HashMap::from([
+                (RefusalReason::NotAllowed, 1),
+                (RefusalReason::NoChargedCell, 4),
+            ]),
+            asteroids_faced: 2,
+            asteroids_survived: 2,
+            ..Default::default()
+        };
+        assert_eq!(
+            snapshot.to_string(),
+            "Orbitron #42 [Running, defensive] tick 7, produced Water:3 Robot:1, \
+             asteroids survived 2/2, 2 in escrow, refused no_charged_cell:4 not_allowed:1"
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_transitions_are_idempotent() {
+        let mut ai = Orbitron::new(1);
+        assert!(!ai.stop(1));
+        assert!(ai.start(1));
+        assert!(!ai.start(1));
+        assert_eq!(ai.snapshot(1).lifecycle, Lifecycle::Running);
+        assert!(ai.stop(1));
+        assert!(!ai.stop(1));
+        assert_eq!(ai.snapshot(1).lifecycle, Lifecycle::Stopped);
+    }
+
+    #[test]
+    fn test_state_changes_between_requests() {
+        let mut ai = Orbitron::new(1);
+        ai.start(1);
+        let drained = FakeCells {
+            total: 1,
+            charged: 0,
+        };
+        assert_eq!(
+            ai.state_changes(&drained),
+            [
+                "charged_cells: →0",
+                "lifecycle: →Running",
+                "defensive_mode: →false",
+                "sunrays_absorbed: →0",
+                "explorer_messages: →0",
+                "asteroids_faced: →0",
+                "asteroids_survived: →0",
+            ]
+        );
+
+        ai.on_tick();
+        let charged = FakeCells {
+            total: 1,
+            charged: 1,
+        };
+        assert_eq!(
+            ai.state_changes(&charged),
+            ["charged_cells: 0→1", "sunrays_absorbed: 0→1"]
+        );
+        assert!(ai.state_changes(&charged).is_empty());
+    }
+
+    #[test]
+    fn test_water_balance_refuses_surplus_element() {
+        let config = OrbitronConfig {
+            balance_for_water: true,
+            water_imbalance_threshold: 2,
+            ..Default::default()
+        };
+        let (planet, handle) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        for _ in 0..5 {
+            planet.sunray();
+        }
+
+        for _ in 0..3 {
+            assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        }
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        let snapshot = handle.get();
+        assert_eq!(
+            snapshot
+                .refusal_reasons
+                .get(&RefusalReason::ProductionImbalance),
+            Some(&1)
+        );
+        assert_eq!(
+            snapshot.suggestions.get(&1),
+            Some(&BasicResourceType::Oxygen)
+        );
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+    }
+
+    #[test]
+    fn test_unsupported_request_suggests_alternative() {
+        let config = OrbitronConfig {
+            suggest_alternatives: true,
+            ..Default::default()
+        };
+        let (planet, handle) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Carbon).is_none());
+        assert_eq!(
+            handle.get().suggestions.get(&1),
+            Some(&BasicResourceType::Oxygen)
+        );
+        // The cell is still there for a supported request
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+    }
+
+    #[test]
+    fn test_weighted_suggestion() {
+        let config = OrbitronConfig {
+            suggest_alternatives: true,
+            generation_weights: HashMap::from([(BasicResourceType::Hydrogen, 1)]),
+            ..Default::default()
+        };
+        let (planet, handle) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        // Oxygen is closer to Carbon, but only Hydrogen has a weight
+        assert!(explorer.generate(BasicResourceType::Carbon).is_none());
+        assert_eq!(
+            handle.get().suggestions.get(&1),
+            Some(&BasicResourceType::Hydrogen)
+        );
+    }
+
+    #[test]
+    fn test_closest_supported() {
+        let supported = HashSet::from([BasicResourceType::Hydrogen, BasicResourceType::Oxygen]);
+        let closest = |resource| closest_supported(resource, &supported);
+        assert_eq!(
+            closest(BasicResourceType::Carbon),
+            Some(BasicResourceType::Oxygen)
+        );
+        assert_eq!(
+            closest(BasicResourceType::Silicon),
+            Some(BasicResourceType::Oxygen)
+        );
+        assert_eq!(
+            closest(BasicResourceType::Hydrogen),
+            Some(BasicResourceType::Hydrogen)
+        );
+        assert_eq!(
+            closest_supported(BasicResourceType::Carbon, &HashSet::new()),
+            None
+        );
+    }
+
+    // The planet loop answers explorers itself until `StartPlanetAI`, so the
+    // check is driven directly here.
+    #[test]
+    fn test_premature_contact_counted_once_per_sender() {
+        let mut ai = Orbitron::new(1);
+        ai.check_premature_contact(1, Some(7), "SupportedResourceRequest");
+        assert_eq!(ai.snapshot(1).protocol_anomalies, 1);
+        ai.check_premature_contact(1, Some(7), "SupportedResourceRequest");
+        assert_eq!(ai.snapshot(1).protocol_anomalies, 1);
+
+        ai.check_premature_contact(1, Some(8), "SupportedResourceRequest");
+        ai.check_premature_contact(1, None, "Sunray");
+        assert_eq!(ai.snapshot(1).protocol_anomalies, 3);
+
+        ai.start(1);
+        ai.check_premature_contact(1, Some(9), "SupportedResourceRequest");
+        assert_eq!(ai.snapshot(1).protocol_anomalies, 3);
+    }
+
+    #[test]
+    fn test_metadata_logged_once() {
+        let mut ai = Orbitron::new(1);
+        ai.start(1);
+        ai.start(1);
+        ai.stop(1);
+        ai.start(1);
+        assert_eq!(ai.metadata_logged, 1);
+    }
+
+    #[test]
+    fn test_startup_banner_carries_config() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = OrbitronBuilder::new(1).seed(5).build();
+        ai.set_test_logger(Arc::clone(&logs));
+        ai.start(1);
+
+        let logs = logs.lock().unwrap();
+        let banner = logs
+            .iter()
+            .find(|captured| captured.payload.contains_key("ai_name"))
+            .unwrap();
+        let expected = config_payload(&ai.config, 1);
+        for (key, value) in &expected {
+            assert_eq!(banner.payload.get(key), Some(value), "{key}");
+        }
+        assert_eq!(banner.payload["rng_seed"], "5");
+        assert_eq!(banner.payload["planet_type"], "B");
+    }
+
+    #[test]
+    fn test_back_to_back_sunrays_flagged() {
+        let config = OrbitronConfig {
+            flag_duplicate_sunrays: true,
+            ..Default::default()
+        };
+        let (planet, snapshot) = TestPlanet::orbitron(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        planet.sunray();
+        planet.sunray();
+        assert_eq!(snapshot.get().suspected_duplicate_sunrays, 1);
+
+        // Any message in between clears the suspicion
+        explorer.available_cells();
+        planet.sunray();
+        assert_eq!(snapshot.get().suspected_duplicate_sunrays, 1);
+    }
+
+    #[test]
+    fn test_duplicate_sunrays_not_flagged_by_default() {
+        let (planet, snapshot) = TestPlanet::orbitron(OrbitronConfig::default());
+        planet.start();
+
+        planet.sunray();
+        planet.sunray();
+        assert_eq!(snapshot.get().suspected_duplicate_sunrays, 0);
+    }
+
+    #[test]
+    fn test_tap_receives_answers() {
+        let (tap, tapped) = crossbeam_channel::unbounded();
+        let mut ai = Orbitron::new(42);
+        ai.set_tap(tap);
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        planet.sunray();
+        let ack = tapped.recv_timeout(testkit::TIMEOUT).unwrap();
+        assert_eq!((ack.explorer_id, ack.name.as_str()), (None, "SunrayAck"));
+
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        let response = tapped.recv_timeout(testkit::TIMEOUT).unwrap();
+        assert_eq!(response.explorer_id, Some(1));
+        assert_eq!(response.name, "Generate Resource Response");
+        assert!(response.details.contains("Oxygen"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_every_handled_message_is_timed() {
+        let ai = Orbitron::new(42);
+        let timings = ai.timings.clone();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        for _ in 0..50 {
+            planet.sunray();
+            explorer.available_cells();
+        }
+
+        let counts = timings.counts();
+        let total: u64 = counts.values().flatten().sum();
+        assert_eq!(total, 100);
+        assert_eq!(counts["Sunray"].iter().sum::<u64>(), 50);
+    }
+
+    #[test]
+    fn test_counters_saturate() {
+        let mut ai = Orbitron::new(1);
+        ai.tick = u64::MAX - 1;
+        ai.on_tick();
+        ai.on_tick();
+        assert_eq!(ai.tick, u64::MAX);
+
+        ai.refusal_reasons.insert(RefusalReason::Paused, u64::MAX);
+        ai.refuse(RefusalReason::Paused, &mut Payload::new());
+        assert_eq!(ai.refusal_reasons[&RefusalReason::Paused], u64::MAX);
+    }
+
+    #[test]
+    fn test_tracked_explorers_capped() {
+        let mut ai = Orbitron::with_config(
+            1,
+            OrbitronConfig {
+                max_tracked_explorers: 64,
+                ..Default::default()
+            },
+        );
+        for explorer_id in 0..10_000 {
+            // What the explorer handler does for a new explorer.
+            ai.explorer_msgs += 1;
+            ai.logical_time += 1;
+            ai.track_explorer(1, explorer_id, true);
+            ai.fast_tracked.insert(explorer_id);
+            ai.suggestions
+                .insert(explorer_id, BasicResourceType::Oxygen);
+            ai.combine_failures.insert(
+                explorer_id,
+                CombineFailures {
+                    count: 1,
+                    last_at: ai.explorer_msgs,
+                },
+            );
+            assert!(ai.explorers.len() <= 64);
+            assert!(ai.fast_tracked.len() <= 64);
+            assert!(ai.suggestions.len() <= 64);
+            assert!(ai.combine_failures.len() <= 64);
+        }
+        assert_eq!(ai.snapshot(1).memory_pressure_events, 10_000 - 64);
+
+        let live = 10_000 - 64..10_000;
+        for explorer_id in live.clone() {
+            assert!(ai.fast_tracked.contains(&explorer_id));
+            assert!(ai.combine_failures.contains_key(&explorer_id));
+        }
+
+        // The oldest live explorer, seen again, outlives the next one.
+        ai.explorer_msgs += 1;
+        ai.logical_time += 1;
+        ai.track_explorer(1, live.start, true);
+        ai.explorer_msgs += 1;
+        ai.logical_time += 1;
+        ai.track_explorer(1, 10_000, true);
+        assert!(ai.suggestions.contains_key(&live.start));
+        assert!(!ai.suggestions.contains_key(&(live.start + 1)));
+    }
+
+    /// Orbitron discharging every cell before answering an
+    /// `InternalStateRequest`.
+    struct Discharging(Orbitron);
+
+    impl PlanetAI for Discharging {
+        fn handle_sunray(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+            sunray: Sunray,
+        ) {
+            self.0.handle_sunray(state, generator, combinator, sunray);
+        }
+
+        fn handle_internal_state_req(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) -> DummyPlanetState {
+            self.0.discharge_all(state);
+            self.0
+                .handle_internal_state_req(state, generator, combinator)
+        }
+
+        fn handle_explorer_msg(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+            msg: ExplorerToPlanet,
+        ) -> Option<PlanetToExplorer> {
+            self.0
+                .handle_explorer_msg(state, generator, combinator, msg)
+        }
+
+        fn handle_asteroid(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) -> Option<Rocket> {
+            self.0.handle_asteroid(state, generator, combinator)
+        }
+
+        fn on_start(
+            &mut self,
+            state: &PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) {
+            self.0.on_start(state, generator, combinator);
+        }
+    }
+
+    /// Orbitron answering every `GenerateResourceRequest` with
+    /// [Orbitron::generate_any], whatever resource it names.
+    struct AnyGenerating(Orbitron);
+
+    impl PlanetAI for AnyGenerating {
+        fn handle_sunray(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+            sunray: Sunray,
+        ) {
+            self.0.handle_sunray(state, generator, combinator, sunray);
+        }
+
+        fn handle_internal_state_req(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) -> DummyPlanetState {
+            self.0
+                .handle_internal_state_req(state, generator, combinator)
+        }
+
+        fn handle_explorer_msg(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+            msg: ExplorerToPlanet,
+        ) -> Option<PlanetToExplorer> {
+            match msg {
+                ExplorerToPlanet::GenerateResourceRequest { explorer_id, .. } => {
+                    Some(PlanetToExplorer::GenerateResourceResponse {
+                        resource: self
+                            .0
+                            .generate_any(state, generator, combinator, explorer_id),
+                    })
+                }
+                msg => self
+                    .0
+                    .handle_explorer_msg(state, generator, combinator, msg),
+            }
+        }
+
+        fn handle_asteroid(
+            &mut self,
+            state: &mut PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) -> Option<Rocket> {
+            self.0.handle_asteroid(state, generator, combinator)
+        }
+
+        fn on_start(
+            &mut self,
+            state: &PlanetState,
+            generator: &Generator,
+            combinator: &Combinator,
+        ) {
+            self.0.on_start(state, generator, combinator);
+        }
+    }
+
+    /// Oxygen among 1000 resources generated by the planet's choice, with
+    /// `weights`.
+    fn oxygen_chosen(weights: HashMap<BasicResourceType, u32>) -> usize {
+        let ai = Orbitron::with_config(
+            testkit::CUSTOM_PLANET_ID,
+            OrbitronConfig {
+                generation_weights: weights,
+                ..Default::default()
+            },
+        );
+        let handle = ai.snapshot_handle();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            common_game::components::planet::Planet::new(
+                testkit::CUSTOM_PLANET_ID,
+                PlanetType::A,
+                Box::new(AnyGenerating(ai)),
+                vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+                vec![],
+                (rx_orch, tx_orch),
+                rx_expl,
+            )
+            .unwrap()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        // The request goes through the guards: no charge, no resource
+        assert!(explorer.generate(BasicResourceType::Carbon).is_none());
+        assert_eq!(
+            handle.get().refusal_reasons[&RefusalReason::NoChargedCell],
+            1
+        );
+
+        let mut oxygen = 0;
+        for _ in 0..1000 {
+            planet.sunray();
+            let resource = explorer.generate(BasicResourceType::Carbon).unwrap();
+            if resource.get_type() == BasicResourceType::Oxygen {
+                oxygen += 1;
+            }
+        }
+        assert_eq!(handle.get().generations_succeeded, 1000);
+        oxygen
+    }
+
+    #[test]
+    fn test_generate_any_follows_weights() {
+        let oxygen = oxygen_chosen(HashMap::from([
+            (BasicResourceType::Oxygen, 9),
+            (BasicResourceType::Hydrogen, 1),
+        ]));
+        assert!((850..950).contains(&oxygen), "got {oxygen} Oxygen");
+    }
+
+    #[test]
+    fn test_generate_any_is_uniform_without_weights() {
+        let oxygen = oxygen_chosen(HashMap::new());
+        assert!((420..580).contains(&oxygen), "got {oxygen} Oxygen");
+    }
+
+    #[test]
+    fn test_discharge_all_empties_every_cell() {
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            let ai = Discharging(Orbitron::new(testkit::CUSTOM_PLANET_ID));
+            common_game::components::planet::Planet::new(
+                testkit::CUSTOM_PLANET_ID,
+                PlanetType::D,
+                Box::new(ai),
+                vec![BasicResourceType::Hydrogen],
+                vec![],
+                (rx_orch, tx_orch),
+                rx_expl,
+            )
+            .unwrap()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        for _ in 0..3 {
+            planet.sunray();
+        }
+        assert_eq!(explorer.available_cells(), 3);
+
+        planet.send(OrchestratorToPlanet::InternalStateRequest);
+        planet.recv();
+        assert_eq!(explorer.available_cells(), 0);
+    }
+
+    /// Orbitron flagging explorers silent for more than 10 messages,
+    /// logging into the returned buffer.
+    fn registry_ai() -> (Orbitron, Arc<Mutex<Vec<CapturedEvent>>>) {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::with_config(
+            1,
+            OrbitronConfig {
+                explorer_silence_threshold: Some(10),
+                ..Default::default()
+            },
+        );
+        ai.set_test_logger(Arc::clone(&logs));
+        (ai, logs)
+    }
+
+    /// What the explorer handler does for every message.
+    fn see(ai: &mut Orbitron, explorer_id: ID, read_only: bool) {
+        ai.track_explorer(1, explorer_id, read_only);
+        ai.logical_time += 1;
+    }
+
+    fn reuse_warnings(logs: &Mutex<Vec<CapturedEvent>>) -> Vec<Payload> {
+        logs.lock()
+            .unwrap()
+            .iter()
+            .filter(|event| {
+                event.payload.get("event").map(String::as_str)
+                    == Some("explorer_id_reuse_suspected")
+            })
+            .map(|event| event.payload.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_explorer_returning_after_silence_flagged() {
+        let (mut ai, logs) = registry_ai();
+        for _ in 0..3 {
+            see(&mut ai, 7, true);
+        }
+        for _ in 0..20 {
+            see(&mut ai, 8, false);
+        }
+        see(&mut ai, 7, false);
+        // Flagged once, even after another silence
+        for _ in 0..20 {
+            see(&mut ai, 8, true);
+        }
+        see(&mut ai, 7, false);
+
+        let registry = ai.snapshot(1).explorers;
+        assert_eq!(registry.suspicious(), [7]);
+        let record = registry.get(7).unwrap();
+        assert_eq!(
+            (
+                record.first_seen,
+                record.last_seen,
+                record.messages,
+                record.consuming
+            ),
+            (1, 45, 5, 2)
+        );
+        let warnings = reuse_warnings(&logs);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0]["explorer"], "7");
+        assert_eq!(warnings[0]["silent_for"], "20");
+        assert_eq!(warnings[0]["first_seen"], "1");
+    }
+
+    #[test]
+    fn test_steady_explorer_not_flagged() {
+        let (mut ai, logs) = registry_ai();
+        // Explorer 7 switches to consuming requests without going silent,
+        // explorer 8 goes silent but already consumed before.
+        for step in 0..40 {
+            see(&mut ai, 7, step < 20);
+            if !(5..=30).contains(&step) {
+                see(&mut ai, 8, step % 2 == 0);
+            }
+        }
+        assert!(ai.explorer_registry().suspicious().is_empty());
+        assert_eq!(ai.explorer_registry().get(7).unwrap().messages, 40);
+        assert!(reuse_warnings(&logs).is_empty());
+    }
+
+    // Direct counterparts of threaded tests, on the parts of an idle planet
+
+    #[test]
+    fn test_direct_unknown_recipe_policies() {
+        let planet = fixtures::type_b();
+        let recipes = planet.combinator().all_available_recipes();
+        let mut ai = Orbitron::new(fixtures::FIXTURE_PLANET_ID);
+        assert_eq!(
+            ai.unsupported_recipe(ComplexResourceType::Diamond, &recipes)
+                .to_string(),
+            "There isn't a recipe for Diamond"
+        );
+        ai.set_config_for_test(OrbitronConfig {
+            unknown_recipe_policy: UnknownRecipePolicy::Structured,
+            ..Default::default()
+        });
+        assert_eq!(
+            ai.unsupported_recipe(ComplexResourceType::Diamond, &recipes)
+                .to_string(),
+            "UnsupportedRecipe requested=Diamond supported=[Water]"
+        );
+    }
+
+    #[test]
+    fn test_direct_type_b_doomed_by_asteroid() {
+        let planet = fixtures::type_b();
+        assert_eq!(
+            asteroid_survivability(planet.state()),
+            Survivability::Doomed {
+                reason: NO_ROCKETS_ERROR
+            }
+        );
+        assert!(!is_saturated(planet.state()));
+    }
+
+    #[test]
+    fn test_direct_describe_type_b() {
+        let planet = fixtures::type_b();
+        let description = describe(
+            planet.planet_type(),
+            planet.state(),
+            planet.generator(),
+            planet.combinator(),
+        );
+        assert_eq!(description.planet_type, PlanetType::B);
+        assert_eq!(
+            description.basic_recipes,
+            fixtures::gen_rules().iter().copied().collect()
+        );
+        assert_eq!(
+            description.complex_recipes,
+            fixtures::comb_rules().iter().copied().collect()
+        );
+        assert_eq!((description.total_cells, description.charged_cells), (1, 0));
+        assert!(!description.can_build_rockets);
+    }
+
+    #[test]
+    fn test_direct_failed_combinations_trigger_backoff() {
+        let mut ai = Orbitron::new(fixtures::FIXTURE_PLANET_ID);
+        for _ in 0..3 {
+            assert!(!ai.is_backing_off(7));
+            ai.record_combine_failure(fixtures::FIXTURE_PLANET_ID, 7);
+        }
+        assert!(ai.is_backing_off(7));
+        assert!(!ai.is_backing_off(8));
+
+        ai.clear_combine_failures(fixtures::FIXTURE_PLANET_ID, 7);
+        assert!(!ai.is_backing_off(7));
+    }
+
+    #[test]
+    fn test_direct_combines_throttled_per_tick() {
+        let mut ai = Orbitron::new(fixtures::FIXTURE_PLANET_ID);
+        ai.combines_this_tick = 1;
+        assert!(!ai.is_combine_throttled());
+        ai.set_config_for_test(OrbitronConfig {
+            max_combines_per_tick: 1,
+            ..Default::default()
+        });
+        assert!(ai.is_combine_throttled());
+        assert_eq!(ai.stats().config.max_combines_per_tick, 1);
+    }
+
+    #[test]
+    fn test_direct_explorer_access() {
+        let config = access_config(true);
+        let consuming = ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: 2,
+            resource: BasicResourceType::Hydrogen,
+        };
+        let read_only = ExplorerToPlanet::SupportedResourceRequest { explorer_id: 2 };
+        assert!(config.is_explorer_allowed(1));
+        assert!(!config.is_explorer_allowed(2) && !config.is_explorer_allowed(3));
+        assert!(!is_read_only(&consuming));
+        assert!(is_read_only(&read_only) && config.serve_read_only_to_disallowed);
+    }
+
+    #[test]
+    fn test_direct_lifecycle() {
+        let mut ai = Orbitron::new(fixtures::FIXTURE_PLANET_ID);
+        assert_eq!(ai.lifecycle(), Lifecycle::Created);
+        ai.start(fixtures::FIXTURE_PLANET_ID);
+        assert_eq!(ai.lifecycle(), Lifecycle::Running);
+        ai.stop(fixtures::FIXTURE_PLANET_ID);
+        assert_eq!(ai.stats().lifecycle, Lifecycle::Stopped);
+    }
+
+    #[test]
+    fn test_reset_stats() {
+        let mut ai = Orbitron::new(1);
+        ai.on_tick();
+        ai.refuse(RefusalReason::Paused, &mut Payload::new());
+        ai.asteroids_faced = 3;
+        ai.reset_stats();
+
+        let snapshot = ai.snapshot(1);
+        assert!(snapshot.refusal_reasons.is_empty());
+        assert_eq!(snapshot.asteroids_faced, 0);
+        assert_eq!(snapshot.tick, 1);
+    }
+
+    #[test]
+    fn test_restart_resets_transient_state() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::new(1);
+        ai.set_test_logger(Arc::clone(&logs));
+        ai.start(1);
+        ai.on_tick();
+        ai.batched.push((
+            7,
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 0 },
+        ));
+        ai.generations_this_tick = 2;
+        for _ in 0..3 {
+            ai.record_combine_failure(1, 7);
+        }
+        ai.defensive = Some(2);
+        ai.refuse(RefusalReason::RateLimited, &mut Payload::new());
+
+        ai.stop(1);
+        ai.start(1);
+        assert!(ai.batched.is_empty());
+        assert_eq!(ai.generations_this_tick, 0);
+        assert!(!ai.is_backing_off(7));
+        assert_eq!(ai.defensive, None);
+
+        let snapshot = ai.snapshot(1);
+        assert_eq!(snapshot.tick, 1);
+        assert_eq!(snapshot.refusal_reasons[&RefusalReason::RateLimited], 1);
+        let cleared: Vec<String> = logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| {
+                captured.payload.get("event").map(String::as_str) == Some("transient_state_reset")
+            })
+            .map(|captured| captured.payload["cleared"].clone())
+            .collect();
+        assert_eq!(
+            cleared,
+            ["batched_responses,backoff,defensive_mode,rate_limits"]
+        );
+    }
+
+    #[test]
+    fn test_metrics_prometheus() {
+        let mut ai = Orbitron::new(42);
+        ai.charge_events = HashMap::from([(0, 2), (3, 1)]);
+        ai.sunrays_wasted = 4;
+        ai.generations_succeeded = 2;
+        ai.generations_failed = 1;
+        ai.asteroids_survived = 1;
+
+        let metrics = ai.metrics_prometheus();
+        for line in [
+            "# TYPE orbitron_sunrays_charged_total counter",
+            "orbitron_sunrays_charged_total{planet=\"42\"} 3",
+            "orbitron_sunrays_wasted_total{planet=\"42\"} 4",
+            "orbitron_generations_succeeded_total{planet=\"42\"} 2",
+            "orbitron_generations_failed_total{planet=\"42\"} 1",
+            "orbitron_asteroids_survived_total{planet=\"42\"} 1",
+        ] {
+            assert!(metrics.lines().any(|l| l == line), "{line} missing");
+        }
+        assert_eq!(metrics.lines().filter(|l| !l.starts_with('#')).count(), 5);
+    }
+
+    #[test]
+    fn test_deflected_asteroid_damages_cells() {
+        let config = OrbitronConfig {
+            asteroid_damage: 1,
+            ..Default::default()
+        };
+        let (planet, handle) = rocket_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        for _ in 0..3 {
+            planet.sunray();
+        }
+
+        // One cell goes into the rocket, one is damaged
+        assert!(planet.asteroid().is_some());
+        assert_eq!(explorer.available_cells(), 1);
+        assert_eq!(handle.get().lifecycle, Lifecycle::Running);
+    }
+
+    /// Planet of type B, with one cell, logging into the returned buffer.
+    fn captured_planet(
+        config: OrbitronConfig,
+    ) -> (TestPlanet, SnapshotHandle, Arc<Mutex<Vec<CapturedEvent>>>) {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::with_config(testkit::CUSTOM_PLANET_ID, config);
+        ai.set_test_logger(Arc::clone(&logs));
+        let (planet, handle) = TestPlanet::custom_ai(
+            PlanetType::B,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![ComplexResourceType::Water],
+            ai,
+        );
+        (planet, handle, logs)
+    }
+
+    /// Warnings captured so far with the given `event` payload entry.
+    fn warnings(logs: &Mutex<Vec<CapturedEvent>>, event: &str) -> usize {
+        logs.lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| {
+                captured.channel == Channel::Warning
+                    && captured.payload.get("event").map(String::as_str) == Some(event)
+            })
+            .count()
+    }
+
+    /// Coalesced `SupportedResourceRequest` events logged so far.
+    fn coalesced_supported(logs: &Mutex<Vec<CapturedEvent>>) -> usize {
+        logs.lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| {
+                captured.payload.get("Message").map(String::as_str)
+                    == Some("Supported Resource Request")
+            })
+            .count()
+    }
+
+    #[test]
+    fn test_supported_requests_flushed_by_asteroid() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+        explorer.request(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 });
+        assert_eq!(coalesced_supported(&logs), 0);
+
+        // Fatal for this rocketless planet: no Sunray will follow
+        assert!(planet.asteroid().is_none());
+        assert_eq!(coalesced_supported(&logs), 1);
+    }
+
+    #[test]
+    fn test_supported_requests_flushed_on_drop() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::new(1);
+        ai.set_test_logger(Arc::clone(&logs));
+        ai.coalesce_supported_request(7, Payload::new());
+        assert_eq!(coalesced_supported(&logs), 0);
+
+        drop(ai);
+        assert_eq!(coalesced_supported(&logs), 1);
+    }
+
+    #[test]
+    fn test_supported_lists_logged_in_canonical_order() {
+        let logged = |key: &str, planet_type, basic, complex, msg: fn(ID) -> ExplorerToPlanet| {
+            let logs = Arc::new(Mutex::new(Vec::new()));
+            let mut ai = Orbitron::new(testkit::CUSTOM_PLANET_ID);
+            ai.set_test_logger(Arc::clone(&logs));
+            let (planet, _) = TestPlanet::custom_ai(planet_type, basic, complex, ai);
+            let explorer = planet.explorer(1);
+            planet.start();
+            explorer.request(msg(1));
+            explorer.request(msg(1));
+            planet.sunray();
+            let lists: Vec<String> = logs
+                .lock()
+                .unwrap()
+                .iter()
+                .filter_map(|captured| captured.payload.get(key).cloned())
+                .collect();
+            lists
+        };
+
+        let resources = logged(
+            "Supported Resources",
+            PlanetType::A,
+            vec![
+                BasicResourceType::Silicon,
+                BasicResourceType::Oxygen,
+                BasicResourceType::Carbon,
+                BasicResourceType::Hydrogen,
+            ],
+            vec![],
+            |explorer_id| ExplorerToPlanet::SupportedResourceRequest { explorer_id },
+        );
+        // Both requests of the tick share a single event
+        assert_eq!(resources, ["[Hydrogen, Oxygen, Carbon, Silicon]"]);
+
+        let combinations = logged(
+            "Supported Combinations",
+            PlanetType::C,
+            vec![BasicResourceType::Carbon],
+            vec![
+                ComplexResourceType::AIPartner,
+                ComplexResourceType::Robot,
+                ComplexResourceType::Water,
+                ComplexResourceType::Life,
+            ],
+            |explorer_id| ExplorerToPlanet::SupportedCombinationRequest { explorer_id },
+        );
+        assert_eq!(combinations, ["[Water, Life, Robot, AIPartner]"; 2]);
+    }
+
+    #[test]
+    fn test_supported_resource_requests_coalesced_per_tick() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+        for _ in 0..3 {
+            let answer =
+                explorer.request(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 });
+            assert!(matches!(
+                answer,
+                PlanetToExplorer::SupportedResourceResponse { .. }
+            ));
+        }
+        planet.sunray();
+
+        let coalesced: Vec<Payload> = logs
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| {
+                captured.payload.get("Message").map(String::as_str)
+                    == Some("Supported Resource Request")
+            })
+            .map(|captured| captured.payload.clone())
+            .collect();
+        assert_eq!(coalesced.len(), 1);
+        assert_eq!(coalesced[0]["count"], "3");
+        assert_eq!(coalesced[0]["explorers"], "[1, 1, 1]");
+        assert_eq!(coalesced[0]["Response"], "Supported Resource Response");
+    }
+
+    #[test]
+    fn test_silenced_explorer_traffic_still_logs_failures() {
+        let config = OrbitronConfig {
+            log_verbosity: LogVerbosity {
+                explorer_traffic: Verbosity::Off,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (planet, handle, logs) = captured_planet(config);
+        let explorer = planet.explorer(1);
+        planet.start();
+        let emitted = handle.get().events_emitted;
+        let captured = logs.lock().unwrap().len();
+
+        explorer.available_cells();
+        assert_eq!(handle.get().events_emitted, emitted);
+        assert_eq!(logs.lock().unwrap().len(), captured);
+
+        // No charged cell: the refusal is a failure
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(handle.get().events_emitted, emitted + 1);
+        let logs = logs.lock().unwrap();
+        assert_eq!(logs.len(), captured + 1);
+        assert_eq!(logs[captured].payload["Refusal Reason"], "no_charged_cell");
+    }
+
+    #[test]
+    fn test_enabled_event_types_drop_message_traffic() {
+        let only_internal = |orchestrator_traffic| OrbitronConfig {
+            enabled_event_types: HashSet::from([EventType::InternalPlanetAction]),
+            low_energy_threshold: 2,
+            log_verbosity: LogVerbosity {
+                orchestrator_traffic,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let (planet, _, logs) = captured_planet(only_internal(Verbosity::Info));
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        explorer.available_cells();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+
+        assert!(!logs.lock().unwrap().is_empty());
+        assert!(
+            logs.lock()
+                .unwrap()
+                .iter()
+                .all(|captured| captured.event_type == EventType::InternalPlanetAction)
+        );
+        assert_eq!(warnings(&logs, "low_energy"), 1);
+
+        // An enabled type still goes through the verbosity of its category
+        let (planet, _, logs) = captured_planet(only_internal(Verbosity::Off));
+        planet.start();
+        planet.sunray();
+        assert_eq!(warnings(&logs, "low_energy"), 0);
+    }
+
+    #[test]
+    fn test_unused_cell_decays() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig {
+            cell_decay_ticks: Some(2),
+            ..Default::default()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        // Charged at tick 1, still fine at tick 3
+        for _ in 0..3 {
+            planet.sunray();
+        }
+        assert_eq!(explorer.available_cells(), 1);
+        assert_eq!(warnings(&logs, "cell_decayed"), 0);
+
+        planet.sunray();
+        assert_eq!(explorer.available_cells(), 0);
+        assert_eq!(warnings(&logs, "cell_decayed"), 1);
+    }
+
+    #[test]
+    fn test_sunray_on_full_cells_is_wasted() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        planet.sunray();
+        assert_eq!(explorer.available_cells(), 1);
+        assert_eq!(warnings(&logs, "SunrayWasted"), 0);
+
+        planet.sunray();
+        assert_eq!(explorer.available_cells(), 1);
+        assert_eq!(warnings(&logs, "SunrayWasted"), 1);
+    }
+
+    #[cfg(feature = "dry-run")]
+    #[test]
+    fn test_dry_run_spends_no_cell() {
+        let (planet, handle) = TestPlanet::orbitron(OrbitronConfig {
+            dry_run: true,
+            ..Default::default()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(explorer.available_cells(), 1);
+
+        let snapshot = handle.get();
+        let generation = snapshot
+            .recent_events
+            .iter()
+            .find(|event| event.message == "Generate Resource Request")
+            .unwrap();
+        assert_eq!(generation.response.as_deref(), Some("would_serve Hydrogen"));
+        assert_eq!(snapshot.dry_run_decisions["would_serve Hydrogen"], 1);
+        assert_eq!(snapshot.generations_succeeded, 0);
+    }
+
+    #[cfg(feature = "dry-run")]
+    #[test]
+    fn test_dry_run_refuses_like_the_handler() {
+        let (planet, handle) = TestPlanet::orbitron(OrbitronConfig {
+            dry_run: true,
+            max_inflight: 0,
+            max_combines_per_tick: 0,
+            ..Default::default()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert_eq!(
+            combine_error(&explorer),
+            "dry run: would_refuse Water: RateLimited"
+        );
+
+        let decisions = handle.get().dry_run_decisions;
+        assert_eq!(decisions["would_refuse Hydrogen: RateLimited"], 1);
+        assert_eq!(decisions["would_refuse Water: RateLimited"], 1);
+    }
+
+    #[test]
+    fn test_low_energy_warned_when_dropping_below_threshold() {
+        let (planet, _, logs) = captured_planet(OrbitronConfig {
+            low_energy_threshold: 1,
+            ..Default::default()
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        // The planet starts without charge
+        planet.sunray();
+        assert_eq!(warnings(&logs, "low_energy"), 1);
+        planet.sunray();
+        assert_eq!(warnings(&logs, "low_energy"), 1);
+
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        planet.sunray();
+        assert_eq!(warnings(&logs, "low_energy"), 2);
+    }
+
+    #[test]
+    fn test_unreachable_orchestrator_stops_spending_cells() {
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::new(testkit::CUSTOM_PLANET_ID);
+        ai.set_test_logger(Arc::clone(&logs));
+        let link = ai.orchestrator_link();
+        let (planet, handle) = TestPlanet::custom_ai(
+            PlanetType::B,
+            vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen],
+            vec![ComplexResourceType::Water],
+            ai,
+        );
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+
+        link.mark_unreachable();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+        assert_eq!(explorer.available_cells(), 1);
+        assert_eq!(warnings(&logs, "orchestrator_unreachable"), 1);
+        let refusals = handle.get().refusal_reasons;
+        assert_eq!(refusals[&RefusalReason::OrchestratorUnreachable], 2);
+        assert!(!refusals.contains_key(&RefusalReason::Stopped));
+    }
+
+    #[test]
+    fn test_rounds_end_on_internal_state_requests() {
+        let (planet, handle, logs) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        let end_round = || {
+            planet.send(OrchestratorToPlanet::InternalStateRequest);
+            planet.recv();
+        };
+        planet.start();
+
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+        end_round();
+
+        assert!(explorer.generate(BasicResourceType::Hydrogen).is_none());
+        planet.sunray();
+        end_round();
+
+        // Every cell is charged already
+        planet.sunray();
+        end_round();
+
+        let counts: Vec<(u32, u64, u64, u64)> = handle
+            .get()
+            .round_stats
+            .iter()
+            .map(|stats| {
+                (
+                    stats.round,
+                    stats.produced,
+                    stats.refusals,
+                    stats.sunrays_absorbed,
+                )
+            })
+            .collect();
+        assert_eq!(counts, [(1, 1, 0, 1), (2, 0, 1, 1), (3, 0, 0, 0)]);
+
+        let logs = logs.lock().unwrap();
+        let last_rounds: Vec<&str> = logs
+            .iter()
+            .filter_map(|captured| captured.payload.get("Last Round"))
+            .map(String::as_str)
+            .collect();
+        assert_eq!(last_rounds[1], "round 2: produced 0, refused 1, absorbed 1");
+        assert!(
+            logs.iter()
+                .all(|captured| captured.payload.contains_key("round"))
+        );
+        assert_eq!(logs.last().unwrap().payload["round"], "3");
+    }
+
+    #[test]
+    fn test_round_robin_spreads_charges() {
+        // Each charge is spent right away, so common_game alone would keep
+        // charging the first cell
+        let charges = |charge_order| {
+            let (planet, handle) = TestPlanet::custom(
+                PlanetType::A,
+                vec![BasicResourceType::Hydrogen],
+                vec![],
+                OrbitronConfig {
+                    charge_order,
+                    ..Default::default()
+                },
+            );
+            let explorer = planet.explorer(1);
+            planet.start();
+            for _ in 0..3 {
+                planet.sunray();
+                assert!(explorer.generate(BasicResourceType::Hydrogen).is_some());
+            }
+            handle.get().charge_events
+        };
+
+        assert_eq!(charges(ChargeOrder::Default), HashMap::from([(0, 3)]));
+        assert_eq!(
+            charges(ChargeOrder::RoundRobin),
+            HashMap::from([(0, 1), (1, 1), (2, 1)])
+        );
+    }
+
+    /// Capacity entries of every Sunray log captured so far.
+    fn sunray_capacity(logs: &Mutex<Vec<CapturedEvent>>) -> Vec<[String; 4]> {
+        logs.lock()
+            .unwrap()
+            .iter()
+            .filter(|captured| captured.payload.contains_key("Energy Cell State"))
+            .map(|captured| {
+                [
+                    "charged_cells",
+                    "total_cells",
+                    "saturated",
+                    "sunrays_wasted",
+                ]
+                .map(|key| captured.payload[key].clone())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sunray_log_reports_saturation() {
+        // One cell: full after the first Sunray, the second is wasted
+        let (planet, _, logs) = captured_planet(OrbitronConfig::default());
+        planet.start();
+        planet.sunray();
+        planet.sunray();
+        assert_eq!(
+            sunray_capacity(&logs),
+            [
+                ["1", "1", "true", "0"].map(String::from),
+                ["1", "1", "true", "1"].map(String::from),
+            ]
+        );
+
+        // Five cells: room left after the first Sunray
+        let logs = Arc::new(Mutex::new(Vec::new()));
+        let mut ai = Orbitron::new(testkit::CUSTOM_PLANET_ID);
+        ai.set_test_logger(Arc::clone(&logs));
+        let (planet, _) =
+            TestPlanet::custom_ai(PlanetType::A, vec![BasicResourceType::Hydrogen], vec![], ai);
+        planet.start();
+        planet.sunray();
+        assert_eq!(
+            sunray_capacity(&logs),
+            [["1", "5", "false", "0"].map(String::from)]
+        );
+    }
+
+    #[test]
+    fn test_state_response_breaks_down_explorer_requests() {
+        let (planet, handle, logs) = captured_planet(OrbitronConfig::default());
+        let mut planet = SteppedPlanet::new(planet);
+        planet.step_orchestrator(OrchestratorToPlanet::StartPlanetAI);
+        planet.step_orchestrator(OrchestratorToPlanet::Sunray(testkit::sunray()));
+        for msg in [
+            ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 },
+            ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 },
+            ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 2 },
+            ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id: 2,
+                resource: BasicResourceType::Oxygen,
+            },
+        ] {
+            planet.step_explorer(msg);
+        }
+        planet.step_orchestrator(OrchestratorToPlanet::InternalStateRequest);
+
+        let logs = logs.lock().unwrap();
+        let state_log = logs
+            .iter()
+            .rev()
+            .find(|captured| captured.event_type == EventType::MessagePlanetToOrchestrator)
+            .unwrap();
+        assert_eq!(
+            state_log.payload["Explorer Requests"],
+            "Available Energy Cell Request: 2, Generate Resource Request: 1, \
+             Supported Resource Request: 1"
+        );
+        assert_eq!(handle.get().explorer_requests.values().sum::<u64>(), 4);
+    }
+
+    #[test]
+    fn test_charge_events_counted_per_cell() {
+        let (planet, handle, _) = captured_planet(OrbitronConfig::default());
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        // One Sunray fills the only cell, the next one is handed back
+        planet.sunray();
+        planet.sunray();
+        let snapshot = handle.get();
+        assert_eq!(snapshot.charge_events, HashMap::from([(0, 1)]));
+        assert_eq!(snapshot.sunrays_wasted, 1);
+
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        planet.sunray();
+        assert_eq!(handle.get().charge_events, HashMap::from([(0, 2)]));
+    }
+
+    #[test]
+    fn test_batched_responses_arrive_after_tick() {
+        let ai = Orbitron::with_config(
+            42,
+            OrbitronConfig {
+                batch_responses: true,
+                ..Default::default()
+            },
+        );
+        let senders = ai.explorer_senders();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let (batched, sender) = planet.docked_explorer(7);
+        senders.register(7, sender);
+        let direct = planet.explorer(8);
+        planet.start();
+        planet.sunray();
+
+        batched.send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 7 });
+        batched.send(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 7 });
+        batched.send(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: 7,
+            resource: BasicResourceType::Oxygen,
+        });
+        // Handled after the three above, and answered right away
+        assert_eq!(direct.available_cells(), 0);
+        assert!(batched.received().is_empty());
+
+        planet.sunray();
+        let received = batched.received();
+        assert_eq!(received.len(), 3);
+        assert!(matches!(
+            received[0],
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 }
+        ));
+        assert!(matches!(
+            received[1],
+            PlanetToExplorer::SupportedResourceResponse { .. }
+        ));
+        assert!(matches!(
+            received[2],
+            PlanetToExplorer::GenerateResourceResponse { resource: Some(_) }
+        ));
+    }
+
+    #[test]
+    fn test_batched_responses_flushed_by_asteroid() {
+        let ai = Orbitron::with_config(
+            42,
+            OrbitronConfig {
+                batch_responses: true,
+                ..Default::default()
+            },
+        );
+        let senders = ai.explorer_senders();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let (batched, sender) = planet.docked_explorer(7);
+        senders.register(7, sender);
+        let direct = planet.explorer(8);
+        planet.start();
+        planet.sunray();
+
+        batched.send(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: 7,
+            resource: BasicResourceType::Oxygen,
+        });
+        assert_eq!(direct.available_cells(), 0);
+        assert!(batched.received().is_empty());
+
+        // Fatal for this rocketless planet: no Sunray will follow
+        assert!(planet.asteroid().is_none());
+        assert!(matches!(
+            batched.received().as_slice(),
+            [PlanetToExplorer::GenerateResourceResponse { resource: Some(_) }]
+        ));
+    }
+
+    #[test]
+    fn test_batched_responses_flushed_on_drop() {
+        let mut ai = Orbitron::new(1);
+        let (sender, received) = crossbeam_channel::unbounded();
+        ai.explorer_senders().register(7, sender);
+        ai.batched.push((
+            7,
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 },
+        ));
+        assert!(received.is_empty());
+
+        drop(ai);
+        assert!(matches!(
+            received.try_recv(),
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_paused_generation_still_charges_cells() {
+        let ai = Orbitron::new(42);
+        let (pause, handle) = (ai.pause_handle(), ai.snapshot_handle());
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        pause.pause();
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+        assert_eq!(combine_error(&explorer), OrbitronError::Paused.to_string());
+        assert_eq!(explorer.available_cells(), 1);
+        let snapshot = handle.get();
+        assert!(snapshot.generation_paused);
+        assert_eq!(
+            snapshot.refusal_reasons.get(&RefusalReason::Paused),
+            Some(&2)
+        );
+
+        pause.resume();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+    }
+
+    struct RefuseAll;
+
+    impl OrbitronStrategy for RefuseAll {
+        fn serve_generate(&self, _: ID, _: BasicResourceType, _: u32) -> bool {
+            false
+        }
+
+        fn serve_combine(&self, _: ID, _: ComplexResourceType, _: u32) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn test_shadow_strategy_only_compared() {
+        let ai = Orbitron::with_shadow(42, Box::new(DefaultStrategy), Box::new(RefuseAll));
+        let handle = ai.snapshot_handle();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        for _ in 0..3 {
+            planet.sunray();
+            assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+        }
+        combine_error(&explorer);
+
+        let report = handle.get().shadow_report.unwrap();
+        assert_eq!(
+            report.generate,
+            ShadowTally {
+                agreed: 0,
+                disagreed: 3
+            }
+        );
+        assert_eq!(
+            report.combine,
+            ShadowTally {
+                agreed: 0,
+                disagreed: 1
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "generate 0/3 agreed, combine 0/1 agreed"
+        );
+    }
+
+    #[test]
+    fn test_declining_strategy_refuses() {
+        let ai = Orbitron::with_shadow(42, Box::new(RefuseAll), Box::new(RefuseAll));
+        let handle = ai.snapshot_handle();
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_none());
+        assert_eq!(
+            combine_error(&explorer),
+            OrbitronError::Declined.to_string()
+        );
+        assert_eq!(explorer.available_cells(), 1);
+        let snapshot = handle.get();
+        assert_eq!(
+            snapshot.refusal_reasons.get(&RefusalReason::Declined),
+            Some(&2)
+        );
+        assert_eq!(snapshot.shadow_report.unwrap().generate.agreed, 1);
+    }
+
+    #[test]
+    fn test_actor_helpers() {
+        assert_eq!(planet_actor(4), Participant::new(ActorType::Planet, 4));
+        assert_eq!(
+            orchestrator_actor(),
+            Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)
+        );
+        assert_eq!(explorer_actor(4), Participant::new(ActorType::Explorer, 4));
+    }
+
+    #[test]
+    fn test_explorer_exchange_logs_same_actors() {
+        let (incoming_sink, incoming) = crossbeam_channel::unbounded();
+        let (outgoing_sink, outgoing) = crossbeam_channel::unbounded();
+        let mut ai = Orbitron::new(42);
+        ai.route_logs(EventType::MessageExplorerToPlanet, incoming_sink);
+        ai.route_logs(EventType::MessagePlanetToExplorer, outgoing_sink);
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(3);
+        planet.start();
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+
+        let request = incoming.try_recv().unwrap();
+        let response = outgoing.try_recv().unwrap();
+        assert_eq!(request.sender, Some(explorer_actor(3)));
+        assert_eq!(request.sender, response.receiver);
+        assert_eq!(request.receiver, response.sender);
+    }
+
+    #[test]
+    fn test_logs_routed_by_event_type() {
+        let (explorer_sink, explorer_logs) = crossbeam_channel::unbounded();
+        let (orchestrator_sink, orchestrator_logs) = crossbeam_channel::unbounded();
+        let mut ai = Orbitron::new(42);
+        ai.route_logs(EventType::MessageExplorerToPlanet, explorer_sink);
+        ai.route_logs(EventType::MessageOrchestratorToPlanet, orchestrator_sink);
+        let planet = TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            crate::orbitron_planet(rx_orch, tx_orch, rx_expl, 42, ai)
+        });
+        let explorer = planet.explorer(1);
+        planet.start();
+        planet.sunray();
+        assert!(explorer.generate(BasicResourceType::Oxygen).is_some());
+
+        let explorer_events: Vec<LogEvent> = explorer_logs.try_iter().collect();
+        let orchestrator_events: Vec<LogEvent> = orchestrator_logs.try_iter().collect();
+        assert!(!explorer_events.is_empty());
+        assert!(!orchestrator_events.is_empty());
+        assert!(
+            explorer_events
+                .iter()
+                .all(|event| event.event_type == EventType::MessageExplorerToPlanet)
+        );
+        assert!(
+            orchestrator_events
+                .iter()
+                .all(|event| event.event_type == EventType::MessageOrchestratorToPlanet)
+        );
+    }
+
+    #[test]
+    fn test_queued_messages_reserve_cells() {
+        // Two queued requests, one charged cell
+        assert_eq!(unreserved_cells(1, 2), 0);
+        // One queued request, three charged cells
+        assert_eq!(unreserved_cells(3, 1), 2);
+        assert_eq!(unreserved_cells(3, 0), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "got Stopped")]
+    fn test_unexpected_response_names_variant() {
+        let (planet, _) = TestPlanet::orbitron(OrbitronConfig::default());
+        // Not started, so the planet answers `Stopped` instead of `SunrayAck`
+        planet.sunray();
     }
 }
@@ -0,0 +1,170 @@
+//! # Orbitron – Recipes
+//!
+//! [GENERATION] and [COMBINATION] map every resource of `common_game` to the
+//! [Generator] or [Combinator] method making it. Generation and combination
+//! dispatch through these tables only, and the planet's rules are checked
+//! against them at compile time (see [generates_all] and [combines_all]),
+//! so a recipe can't be advertised without being handled.
+use common_game::components::energy_cell::EnergyCell;
+use common_game::components::resource::{
+    BasicResource, BasicResourceType, Combinator, ComplexResource, ComplexResourceRequest,
+    ComplexResourceType, Generator, GenericResource,
+};
+
+use crate::ai::handlers::explorer::{
+    BASIC_RESOURCES, COMPLEX_RESOURCES, request_inputs, request_type,
+};
+
+/// Makes a basic resource with a charged cell.
+pub(crate) type MakeBasic = fn(&Generator, &mut EnergyCell) -> Result<BasicResource, String>;
+
+/// Combines the inputs of a request with a charged cell, handing them back
+/// on failure.
+pub(crate) type MakeComplex =
+    fn(
+        &Combinator,
+        ComplexResourceRequest,
+        &mut EnergyCell,
+    ) -> Result<ComplexResource, (String, GenericResource, GenericResource)>;
+
+macro_rules! generation {
+    ($resource:ident, $make:ident) => {
+        (BasicResourceType::$resource, |generator, cell| {
+            generator.$make(cell).map(|made| made.to_basic())
+        })
+    };
+}
+
+macro_rules! combination {
+    ($recipe:ident, $make:ident) => {
+        (ComplexResourceType::$recipe, |combinator, request, cell| {
+            match request {
+                ComplexResourceRequest::$recipe(input_1, input_2) => combinator
+                    .$make(input_1, input_2, cell)
+                    .map(|made| made.to_complex())
+                    .map_err(|(err, input_1, input_2)| {
+                        (err, input_1.to_generic(), input_2.to_generic())
+                    }),
+                // Entries are only looked up by the request's own recipe
+                other => {
+                    let recipe = request_type(&other);
+                    let (input_1, input_2) = request_inputs(other);
+                    Err((
+                        format!(
+                            "{recipe:?} is not made with {:?}",
+                            ComplexResourceType::$recipe
+                        ),
+                        input_1,
+                        input_2,
+                    ))
+                }
+            }
+        })
+    };
+}
+
+/// How each basic resource is generated.
+pub(crate) const GENERATION: [(BasicResourceType, MakeBasic); 4] = [
+    generation!(Hydrogen, make_hydrogen),
+    generation!(Oxygen, make_oxygen),
+    generation!(Carbon, make_carbon),
+    generation!(Silicon, make_silicon),
+];
+
+/// How each complex resource is combined.
+pub(crate) const COMBINATION: [(ComplexResourceType, MakeComplex); 6] = [
+    combination!(Water, make_water),
+    combination!(Diamond, make_diamond),
+    combination!(Life, make_life),
+    combination!(Robot, make_robot),
+    combination!(Dolphin, make_dolphin),
+    combination!(AIPartner, make_aipartner),
+];
+
+// Every resource of common_game has an entry
+const _: () = assert!(generates_all(&BASIC_RESOURCES) && combines_all(&COMPLEX_RESOURCES));
+
+/// Table entry generating `resource`.
+pub(crate) fn maker(resource: BasicResourceType) -> Option<MakeBasic> {
+    GENERATION
+        .iter()
+        .find(|(handled, _)| *handled == resource)
+        .map(|(_, make)| *make)
+}
+
+/// Table entry combining `recipe`.
+pub(crate) fn combiner(recipe: ComplexResourceType) -> Option<MakeComplex> {
+    COMBINATION
+        .iter()
+        .find(|(handled, _)| *handled == recipe)
+        .map(|(_, make)| *make)
+}
+
+/// Whether [GENERATION] handles every resource of `rules`.
+pub(crate) const fn generates_all(rules: &[BasicResourceType]) -> bool {
+    let mut i = 0;
+    while i < rules.len() {
+        let mut found = false;
+        let mut j = 0;
+        while j < GENERATION.len() {
+            found |= GENERATION[j].0 as u8 == rules[i] as u8;
+            j += 1;
+        }
+        if !found {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Whether [COMBINATION] handles every recipe of `rules`.
+pub(crate) const fn combines_all(rules: &[ComplexResourceType]) -> bool {
+    let mut i = 0;
+    while i < rules.len() {
+        let mut found = false;
+        let mut j = 0;
+        while j < COMBINATION.len() {
+            found |= COMBINATION[j].0 as u8 == rules[i] as u8;
+            j += 1;
+        }
+        if !found {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+    use crate::testkit::lab;
+
+    #[test]
+    fn test_advertised_recipes_are_handled() {
+        let (generator, combinator) = lab();
+        let generated: HashSet<_> = GENERATION.iter().map(|(resource, _)| *resource).collect();
+        let combined: HashSet<_> = COMBINATION.iter().map(|(recipe, _)| *recipe).collect();
+        assert_eq!(generated, generator.all_available_recipes());
+        assert_eq!(combined, combinator.all_available_recipes());
+        assert_eq!(generated, HashSet::from(BASIC_RESOURCES));
+        assert_eq!(combined, HashSet::from(COMPLEX_RESOURCES));
+
+        let metadata = crate::planet_metadata();
+        assert!(
+            metadata
+                .gen_rules
+                .iter()
+                .all(|rule| generated.contains(rule))
+        );
+        assert!(
+            metadata
+                .comb_rules
+                .iter()
+                .all(|rule| combined.contains(rule))
+        );
+    }
+}
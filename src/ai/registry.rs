@@ -0,0 +1,120 @@
+//! # Orbitron – Explorer registry
+//!
+//! Explorer ids are plain numbers chosen outside the planet, so nothing
+//! stops two different explorers from using the same one, for instance when
+//! an explorer restarts and its id is handed out again. The
+//! [ExplorerRegistry] remembers when each id was first and last seen and
+//! flags the ids whose traffic looks like it changed hands.
+use std::collections::HashMap;
+
+use common_game::utils::ID;
+
+/// What the planet knows about one explorer id. Sequence numbers count
+/// every message handled by the AI, from `1`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExplorerRecord {
+    /// Sequence number of the first message from this id.
+    pub first_seen: u64,
+    /// Sequence number of the latest message from this id.
+    pub last_seen: u64,
+    /// Messages received from this id.
+    pub messages: u64,
+    /// Generation and combination requests among them.
+    pub consuming: u64,
+    /// Whether the id is suspected of being reused by another explorer.
+    pub suspicious: bool,
+}
+
+/// First-seen and last-seen data of every tracked explorer id, at most
+/// [OrbitronConfig::max_tracked_explorers](super::config::OrbitronConfig::max_tracked_explorers)
+/// of them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExplorerRegistry {
+    records: HashMap<ID, ExplorerRecord>,
+}
+
+impl ExplorerRegistry {
+    /// Records message `seq` from `explorer_id`.
+    ///
+    /// Returns the number of messages the id had been silent for when this
+    /// message makes it suspicious: it was silent for more than `silence`
+    /// messages, had only sent read-only requests, and comes back with a
+    /// consuming one. An id is only flagged once.
+    pub(crate) fn record(
+        &mut self,
+        explorer_id: ID,
+        seq: u64,
+        read_only: bool,
+        silence: Option<u64>,
+    ) -> Option<u64> {
+        let record = self.records.entry(explorer_id).or_insert(ExplorerRecord {
+            first_seen: seq,
+            last_seen: seq,
+            ..Default::default()
+        });
+        let silent_for = seq.saturating_sub(record.last_seen).saturating_sub(1);
+        let flagged = !record.suspicious
+            && !read_only
+            && record.messages > 0
+            && record.consuming == 0
+            && silence.is_some_and(|silence| silent_for > silence);
+        record.last_seen = seq;
+        record.messages = record.messages.saturating_add(1);
+        if !read_only {
+            record.consuming = record.consuming.saturating_add(1);
+        }
+        record.suspicious |= flagged;
+        flagged.then_some(silent_for)
+    }
+
+    /// Forgets `explorer_id`.
+    pub(crate) fn remove(&mut self, explorer_id: ID) -> Option<ExplorerRecord> {
+        self.records.remove(&explorer_id)
+    }
+
+    /// Ids last seen before sequence number `seq`, sorted.
+    pub(crate) fn seen_before(&self, seq: u64) -> Vec<ID> {
+        let mut ids: Vec<ID> = self
+            .records
+            .iter()
+            .filter(|(_, record)| record.last_seen < seq)
+            .map(|(explorer_id, _)| *explorer_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Id seen least recently, with the sequence number it was last seen at.
+    pub(crate) fn least_recently_seen(&self) -> Option<(ID, u64)> {
+        self.records
+            .iter()
+            .map(|(explorer_id, record)| (*explorer_id, record.last_seen))
+            .min_by_key(|(_, last_seen)| *last_seen)
+    }
+
+    /// Record of `explorer_id`, if it is tracked.
+    pub fn get(&self, explorer_id: ID) -> Option<&ExplorerRecord> {
+        self.records.get(&explorer_id)
+    }
+
+    /// Number of tracked explorer ids.
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Ids currently flagged as suspicious, sorted.
+    pub fn suspicious(&self) -> Vec<ID> {
+        let mut ids: Vec<ID> = self
+            .records
+            .iter()
+            .filter(|(_, record)| record.suspicious)
+            .map(|(explorer_id, _)| *explorer_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+}
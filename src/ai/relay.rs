@@ -0,0 +1,70 @@
+//! # Orbitron – Orchestrator relay
+//!
+//! The planet loop owns its orchestrator sender and only notices a hang-up
+//! at its next send, so until then the AI would keep spending cells on
+//! explorers for a game that is gone. The [Relay] sits between the loop and
+//! the orchestrator: it forwards every message from its own thread and
+//! raises the [OrchestratorLink] as soon as one can't be delivered, which
+//! the AI sees at the very next explorer message.
+use std::thread::JoinHandle;
+
+use common_game::protocols::orchestrator_planet::PlanetToOrchestrator;
+use crossbeam_channel::{Sender, bounded, select};
+
+use super::orbitron::OrchestratorLink;
+
+/// Thread forwarding the planet's messages to the orchestrator; stopped
+/// and joined when dropped.
+///
+/// The planet sends through a channel that holds nothing, so each send
+/// waits for the relay and a bounded `to_orchestrator` still holds the loop
+/// once full. Dropping the relay waits for the message it is forwarding,
+/// so the last answer of a loop is delivered before its planet is gone.
+#[derive(Debug)]
+pub(crate) struct Relay {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Relay {
+    /// Spawns the relay, returning it with the sender the planet must use.
+    ///
+    /// Once a message can't be delivered `link` is raised and the relay
+    /// hangs up too, so the loop still fails at its next send.
+    pub(crate) fn spawn(
+        to_orchestrator: Sender<PlanetToOrchestrator>,
+        link: OrchestratorLink,
+    ) -> (Self, Sender<PlanetToOrchestrator>) {
+        let (relay, from_planet) = bounded::<PlanetToOrchestrator>(0);
+        let (stop, stopped) = bounded::<()>(0);
+        let thread = std::thread::spawn(move || {
+            loop {
+                select! {
+                    recv(from_planet) -> msg => {
+                        let Ok(msg) = msg else { return };
+                        if to_orchestrator.send(msg).is_err() {
+                            link.mark_unreachable();
+                            return;
+                        }
+                    }
+                    recv(stopped) -> _ => return,
+                }
+            }
+        });
+        let relay_thread = Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        };
+        (relay_thread, relay)
+    }
+}
+
+impl Drop for Relay {
+    fn drop(&mut self) {
+        // Disconnecting the channel ends the thread's wait
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! # Orbitron – Deterministic RNG
+//!
+//! Every randomized decision of the AI goes through [Rng], a small
+//! xorshift64* generator seeded from [OrbitronConfig::rng_seed], or from the
+//! planet id when no seed is set. Two AIs built with the same seed and fed the
+//! same messages behave identically.
+//!
+//! [OrbitronConfig::rng_seed]: super::config::OrbitronConfig::rng_seed
+
+/// Seeded xorshift64* pseudo-random generator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        // splitmix64 scrambles the seed so that small seeds still give
+        // well-mixed, non-zero states
+        let mut z = seed.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^= z >> 31;
+        Self {
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    /// Current state, saved in checkpoints to resume the same sequence.
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Resumes from a state returned by [Rng::state].
+    #[cfg(feature = "checkpoint")]
+    pub(crate) fn from_state(state: u64) -> Self {
+        // xorshift never leaves zero, so a zero state can't come from `state`
+        Self {
+            state: if state == 0 { 1 } else { state },
+        }
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a value in `0..bound`. `bound` must be non-zero.
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(7);
+        let mut b = Rng::new(7);
+        for _ in 0..100 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_below_stays_in_bounds() {
+        let mut rng = Rng::new(0);
+        for _ in 0..1000 {
+            assert!(rng.below(3) < 3);
+        }
+    }
+}
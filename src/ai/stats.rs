@@ -0,0 +1,652 @@
+//! # Orbitron – Statistics
+//!
+//! Counters, histories and the [OrbitronSnapshot] the AI publishes after
+//! every handled message.
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+use common_game::logging::*;
+use common_game::utils::ID;
+
+use super::config::{LogCategory, OrbitronConfig};
+use super::error::OrbitronError;
+use super::handlers::explorer::{COMPLEX_RESOURCES, GC_INTERVAL};
+use super::logging::planet_actor;
+use super::orbitron::{CellView, Lifecycle, Orbitron, charged_cells};
+use super::registry::ExplorerRegistry;
+use super::strategy::ShadowReport;
+
+/// Adds one to a lifetime counter, stopping at `u64::MAX` instead of wrapping.
+pub(crate) fn increment(counter: &mut u64) {
+    *counter = counter.saturating_add(1);
+}
+
+/// Why an explorer request was refused.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub enum RefusalReason {
+    /// No energy cell was charged.
+    NoChargedCell,
+    /// Cells are being kept for the planet's own survival.
+    ReservePolicy,
+    /// The explorer or the planet exceeded a request limit.
+    RateLimited,
+    /// The AI is not running.
+    Stopped,
+    /// The explorer is not allowed on this planet.
+    NotAllowed,
+    /// Generation was paused through a [PauseHandle].
+    Paused,
+    /// The [OrbitronStrategy] declined the request.
+    Declined,
+    /// Serving the request would widen the gap between Hydrogen and Oxygen,
+    /// see [OrbitronConfig::balance_for_water].
+    ProductionImbalance,
+    /// The orchestrator can no longer be reached, see [OrchestratorLink].
+    OrchestratorUnreachable,
+}
+
+impl RefusalReason {
+    pub const ALL: [RefusalReason; 9] = [
+        RefusalReason::NoChargedCell,
+        RefusalReason::ReservePolicy,
+        RefusalReason::RateLimited,
+        RefusalReason::Stopped,
+        RefusalReason::NotAllowed,
+        RefusalReason::Paused,
+        RefusalReason::Declined,
+        RefusalReason::ProductionImbalance,
+        RefusalReason::OrchestratorUnreachable,
+    ];
+
+    /// Reason recorded when a request is turned down with `err`, `None`
+    /// for errors that are failures rather than refusals.
+    pub(crate) fn for_error(err: &OrbitronError) -> Option<Self> {
+        match err {
+            OrbitronError::NoChargedCell | OrbitronError::NoCells => Some(Self::NoChargedCell),
+            OrbitronError::Defensive => Some(Self::ReservePolicy),
+            OrbitronError::BackedOff | OrbitronError::RateLimited => Some(Self::RateLimited),
+            OrbitronError::Stopped => Some(Self::Stopped),
+            OrbitronError::NotAllowed => Some(Self::NotAllowed),
+            OrbitronError::Paused => Some(Self::Paused),
+            OrbitronError::Declined => Some(Self::Declined),
+            OrbitronError::ProductionImbalance(_) => Some(Self::ProductionImbalance),
+            _ => None,
+        }
+    }
+
+    /// Reason string used in log payloads.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RefusalReason::NoChargedCell => "no_charged_cell",
+            RefusalReason::ReservePolicy => "reserve_policy",
+            RefusalReason::RateLimited => "rate_limited",
+            RefusalReason::Stopped => "stopped",
+            RefusalReason::NotAllowed => "not_allowed",
+            RefusalReason::Paused => "paused",
+            RefusalReason::Declined => "declined",
+            RefusalReason::ProductionImbalance => "production_imbalance",
+            RefusalReason::OrchestratorUnreachable => "orchestrator_unreachable",
+        }
+    }
+}
+
+/// Figures compared between two `InternalStateRequest`s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct StateSummary {
+    charged_cells: u32,
+    lifecycle: Lifecycle,
+    defensive_mode: bool,
+    sunrays_absorbed: u64,
+    explorer_messages: u64,
+    asteroids_faced: u64,
+    asteroids_survived: u64,
+}
+
+impl StateSummary {
+    pub(crate) fn fields(&self) -> [(&'static str, String); 7] {
+        [
+            ("charged_cells", self.charged_cells.to_string()),
+            ("lifecycle", self.lifecycle.to_string()),
+            ("defensive_mode", self.defensive_mode.to_string()),
+            ("sunrays_absorbed", self.sunrays_absorbed.to_string()),
+            ("explorer_messages", self.explorer_messages.to_string()),
+            ("asteroids_faced", self.asteroids_faced.to_string()),
+            ("asteroids_survived", self.asteroids_survived.to_string()),
+        ]
+    }
+}
+
+/// A message handled by the AI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EventRecord {
+    /// Name of the message received.
+    pub message: String,
+    /// Name of the answer, `None` when there was none.
+    pub response: Option<String>,
+    /// Tick during which the message was handled.
+    pub tick: u64,
+}
+
+/// Sender of a handled message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    OrchestratorToPlanet,
+    ExplorerToPlanet,
+}
+
+/// A message handled by the AI, as kept for the post-mortem dump.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// Position of the message among all the handled ones, from `1`.
+    pub seq: u64,
+    /// `corr_id` found in the log events of the same exchange.
+    pub corr_id: u64,
+    pub direction: Direction,
+    /// Name of the message received.
+    pub message: String,
+    /// Name of the answer, `-` when there was none.
+    pub outcome: String,
+}
+
+/// What happened during one round, see [OrbitronConfig::round_boundary].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "checkpoint", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundStats {
+    /// Number of the round, from `1`.
+    pub round: u32,
+    /// Basic and complex resources handed to explorers.
+    pub produced: u64,
+    /// Explorer requests refused.
+    pub refusals: u64,
+    /// Sunrays that charged a cell.
+    pub sunrays_absorbed: u64,
+}
+
+/// `round 2: produced 1, refused 0, absorbed 3`.
+impl fmt::Display for RoundStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "round {}: produced {}, refused {}, absorbed {}",
+            self.round, self.produced, self.refusals, self.sunrays_absorbed
+        )
+    }
+}
+
+/// Point-in-time view of the AI's internal bookkeeping.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrbitronSnapshot {
+    pub planet_id: ID,
+    pub lifecycle: Lifecycle,
+    pub defensive_mode: bool,
+    /// See [PauseHandle].
+    pub generation_paused: bool,
+    pub tick: u64,
+    pub escrowed: usize,
+    /// See [Orbitron::inventory_summary].
+    pub inventory: HashMap<BasicResourceType, u32>,
+    pub complex_generated: HashMap<ComplexResourceType, u64>,
+    /// Explorer messages handled, by request name.
+    pub explorer_requests: HashMap<String, u64>,
+    pub refusal_reasons: HashMap<RefusalReason, u64>,
+    pub asteroids_faced: u64,
+    pub asteroids_survived: u64,
+    /// Tick at which the last asteroid hit, `None` before the first one.
+    pub last_asteroid_tick: Option<u64>,
+    pub last_asteroid_event: Payload,
+    /// Explorer messages queued when the snapshot was published.
+    pub pending_explorers: usize,
+    pub recent_events: Vec<EventRecord>,
+    /// See [OrbitronConfig::flag_duplicate_sunrays].
+    pub suspected_duplicate_sunrays: u64,
+    /// Messages breaking the expected protocol flow, such as contacting the
+    /// planet before `StartPlanetAI`.
+    pub protocol_anomalies: u64,
+    /// Latest alternative suggested to each explorer for a resource the
+    /// planet can't generate; the protocol has no room for it in the answer.
+    pub suggestions: HashMap<ID, BasicResourceType>,
+    /// Explorers and escrowed resources evicted because their collection
+    /// was full.
+    pub memory_pressure_events: u64,
+    /// See [Orbitron::explorer_registry].
+    pub explorers: ExplorerRegistry,
+    /// See [Orbitron::round_stats].
+    pub round_stats: Vec<RoundStats>,
+    /// Sunrays absorbed, by cell index; a Sunray charges one cell whole.
+    pub charge_events: HashMap<usize, u64>,
+    /// Sunrays handed back unused because every cell was charged.
+    pub sunrays_wasted: u64,
+    /// Basic resources generated for explorers.
+    pub generations_succeeded: u64,
+    /// Generations attempted that produced nothing, for lack of a charged
+    /// cell or because the resource isn't supported.
+    pub generations_failed: u64,
+    /// Errors returned by the generator on a charged cell.
+    pub generator_errors: u64,
+    /// Generations that succeeded on another cell after a generator error,
+    /// see [OrbitronConfig::retry_on_generator_error].
+    pub generator_retries_succeeded: u64,
+    /// Decisions taken in dry run, e.g. `would_serve Hydrogen`, see
+    /// [OrbitronConfig::dry_run].
+    #[cfg(feature = "dry-run")]
+    pub dry_run_decisions: HashMap<String, u64>,
+    /// Messages that reached the AI once the planet was destroyed; they are
+    /// answered negatively, or not at all.
+    pub messages_after_destruction: u64,
+    /// Log events emitted, after [OrbitronConfig::log_verbosity] filtered
+    /// them.
+    pub events_emitted: u64,
+    /// See [Orbitron::shadow_report].
+    pub shadow_report: Option<ShadowReport>,
+    /// See [Orbitron::config].
+    pub config: OrbitronConfig,
+}
+
+/// One line, e.g.
+/// `Orbitron #42 [Running] tick 7, produced Water:1, asteroids survived 1/1, refused rate_limited:2`.
+impl fmt::Display for OrbitronSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Orbitron #{} [{}", self.planet_id, self.lifecycle)?;
+        if self.defensive_mode {
+            f.write_str(", defensive")?;
+        }
+        if self.generation_paused {
+            f.write_str(", paused")?;
+        }
+        write!(f, "] tick {}", self.tick)?;
+
+        let produced: Vec<String> = COMPLEX_RESOURCES
+            .iter()
+            .filter_map(|resource| {
+                self.complex_generated
+                    .get(resource)
+                    .map(|count| format!("{resource:?}:{count}"))
+            })
+            .collect();
+        if produced.is_empty() {
+            f.write_str(", produced nothing")?;
+        } else {
+            write!(f, ", produced {}", produced.join(" "))?;
+        }
+
+        write!(
+            f,
+            ", asteroids survived {}/{}",
+            self.asteroids_survived, self.asteroids_faced
+        )?;
+        if self.escrowed > 0 {
+            write!(f, ", {} in escrow", self.escrowed)?;
+        }
+
+        let refused: Vec<String> = RefusalReason::ALL
+            .iter()
+            .filter_map(|reason| {
+                self.refusal_reasons
+                    .get(reason)
+                    .map(|count| format!("{}:{count}", reason.as_str()))
+            })
+            .collect();
+        if refused.is_empty() {
+            f.write_str(", refused none")
+        } else {
+            write!(f, ", refused {}", refused.join(" "))
+        }
+    }
+}
+
+/// Shared handle to the latest [OrbitronSnapshot] published by an [Orbitron].
+///
+/// The AI is moved into the [Planet](common_game::components::planet::Planet)
+/// when the planet is built, so this handle is the only way to observe its
+/// bookkeeping from the outside. It is refreshed after every handled message.
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotHandle(Arc<Mutex<OrbitronSnapshot>>);
+
+impl SnapshotHandle {
+    /// Returns a copy of the most recently published snapshot.
+    pub fn get(&self) -> OrbitronSnapshot {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Orbitron {
+    /// Builds a snapshot of the current bookkeeping.
+    pub fn snapshot(&self, planet_id: ID) -> OrbitronSnapshot {
+        OrbitronSnapshot {
+            planet_id,
+            lifecycle: self.lifecycle,
+            defensive_mode: self.defensive.is_some(),
+            generation_paused: self.generation_paused.is_paused(),
+            tick: self.tick,
+            escrowed: self.escrow.len(),
+            inventory: self.inventory_summary(),
+            complex_generated: self.complex_generated.clone(),
+            explorer_requests: self.explorer_requests.clone(),
+            refusal_reasons: self.refusal_reasons.clone(),
+            asteroids_faced: self.asteroids_faced,
+            asteroids_survived: self.asteroids_survived,
+            last_asteroid_tick: self.last_asteroid_tick,
+            last_asteroid_event: self.last_asteroid_event.clone(),
+            pending_explorers: self.pending_explorer_count(),
+            recent_events: self.recent_events.clone(),
+            suspected_duplicate_sunrays: self.suspected_duplicate_sunrays,
+            protocol_anomalies: self.protocol_anomalies,
+            suggestions: self.suggestions.clone(),
+            memory_pressure_events: self.memory_pressure_events,
+            explorers: self.explorers.clone(),
+            round_stats: self.round_stats(),
+            charge_events: self.charge_events.clone(),
+            sunrays_wasted: self.sunrays_wasted,
+            generations_succeeded: self.generations_succeeded,
+            generations_failed: self.generations_failed,
+            generator_errors: self.generator_errors,
+            generator_retries_succeeded: self.generator_retries_succeeded,
+            #[cfg(feature = "dry-run")]
+            dry_run_decisions: self.dry_run_decisions.clone(),
+            messages_after_destruction: self.messages_after_destruction,
+            events_emitted: self.events_emitted.get(),
+            shadow_report: self.shadow_report(),
+            config: self.config(),
+        }
+    }
+
+    /// Last [OrbitronConfig::post_mortem_history] handled messages, oldest
+    /// first, as dumped to the log on asteroids.
+    pub fn recent_history(&self) -> Vec<HistoryEntry> {
+        self.history.iter().cloned().collect()
+    }
+
+    /// Most recently handled messages, oldest first.
+    pub fn recent_events(&self) -> &[EventRecord] {
+        &self.recent_events
+    }
+
+    /// First-seen and last-seen data of the tracked explorers, with the ids
+    /// suspected of being reused, see
+    /// [OrbitronConfig::explorer_silence_threshold].
+    pub fn explorer_registry(&self) -> &ExplorerRegistry {
+        &self.explorers
+    }
+
+    /// Statistics of the ended rounds, oldest first, at most
+    /// [OrbitronConfig::round_history] of them.
+    pub fn round_stats(&self) -> Vec<RoundStats> {
+        self.rounds.iter().cloned().collect()
+    }
+
+    /// Ends the round in progress and starts the next one.
+    pub(crate) fn end_round(&mut self) {
+        let next = self.current_round.saturating_add(1);
+        let ended = std::mem::replace(
+            &mut self.round,
+            RoundStats {
+                round: next,
+                ..Default::default()
+            },
+        );
+        self.current_round = next;
+        self.sunrays_this_round = 0;
+        if self.config.round_history == 0 {
+            return;
+        }
+        if self.rounds.len() >= self.config.round_history {
+            self.rounds.pop_front();
+        }
+        self.rounds.push_back(ended);
+    }
+
+    /// Number of messages handled since the logical time `entry_time`.
+    pub(crate) fn age_of(&self, entry_time: u64) -> u64 {
+        self.logical_time.saturating_sub(entry_time)
+    }
+
+    /// Counts a handled message, beats the watchdog heartbeat, runs
+    /// [Orbitron::gc] every [GC_INTERVAL] messages and appends it
+    /// to both histories, dropping their oldest entry once
+    /// [OrbitronConfig::post_mortem_history] or
+    /// [OrbitronConfig::event_history] is reached.
+    pub(crate) fn record(
+        &mut self,
+        direction: Direction,
+        message: impl Into<String>,
+        response: Option<String>,
+    ) {
+        increment(&mut self.logical_time);
+        self.heartbeat.beat();
+        if self.logical_time.is_multiple_of(GC_INTERVAL) {
+            self.gc();
+        }
+        let message = message.into();
+        if self.config.post_mortem_history > 0 {
+            if self.history.len() >= self.config.post_mortem_history {
+                self.history.pop_front();
+            }
+            self.history.push_back(HistoryEntry {
+                seq: self.logical_time,
+                corr_id: self.corr_id,
+                direction,
+                message: message.clone(),
+                outcome: response.clone().unwrap_or_else(|| "-".into()),
+            });
+        }
+        let capacity = self.config.event_history;
+        if capacity == 0 {
+            return;
+        }
+        if self.recent_events.len() >= capacity {
+            self.recent_events.remove(0);
+        }
+        self.recent_events.push(EventRecord {
+            message,
+            response,
+            tick: self.tick,
+        });
+    }
+
+    /// Refreshes the snapshot visible through [SnapshotHandle].
+    ///
+    /// Called once per handled message, so it also warns about an explorer
+    /// backlog above [OrbitronConfig::explorer_backlog_warning].
+    pub(crate) fn publish(&self, planet_id: ID) {
+        let snapshot = self.snapshot(planet_id);
+        let threshold = self.config.explorer_backlog_warning;
+        if threshold > 0 && snapshot.pending_explorers > threshold {
+            // LOG explorer backlog
+            let mut payload = Payload::new();
+            payload.insert("Message".into(), "Explorer backlog".into());
+            payload.insert(
+                "Pending Explorer Messages".into(),
+                snapshot.pending_explorers.to_string(),
+            );
+            self.emit(
+                LogCategory::ExplorerTraffic,
+                LogEvent::self_directed(
+                    planet_actor(planet_id),
+                    EventType::InternalPlanetAction,
+                    Channel::Warning,
+                    payload,
+                ),
+            );
+        }
+        *self.published.0.lock().unwrap() = snapshot;
+    }
+
+    /// Fields that differ from the last call, as `name: old→new`; the first
+    /// call reports every field as `name: →value`.
+    pub(crate) fn state_changes(&mut self, cells: &impl CellView) -> Vec<String> {
+        let current = StateSummary {
+            charged_cells: charged_cells(cells),
+            lifecycle: self.lifecycle,
+            defensive_mode: self.defensive.is_some(),
+            sunrays_absorbed: self.tick,
+            explorer_messages: self.explorer_msgs,
+            asteroids_faced: self.asteroids_faced,
+            asteroids_survived: self.asteroids_survived,
+        };
+        let previous = self.last_state_summary.replace(current);
+
+        match previous {
+            None => current
+                .fields()
+                .iter()
+                .map(|(name, value)| format!("{name}: →{value}"))
+                .collect(),
+            Some(previous) => previous
+                .fields()
+                .iter()
+                .zip(current.fields())
+                .filter(|((_, old), (_, new))| old != new)
+                .map(|((name, old), (_, new))| format!("{name}: {old}→{new}"))
+                .collect(),
+        }
+    }
+
+    /// Counts a refused request and records the reason in its log payload.
+    pub(crate) fn refuse(&mut self, reason: RefusalReason, payload: &mut Payload) {
+        increment(self.refusal_reasons.entry(reason).or_insert(0));
+        increment(&mut self.round.refusals);
+        payload.insert("Refusal Reason".into(), reason.as_str().into());
+    }
+
+    /// Lifetime figures logged when the AI is stopped.
+    pub(crate) fn summary_payload(&self, planet_id: ID) -> Payload {
+        let mut payload = Payload::new();
+        payload.insert("Summary".into(), self.snapshot(planet_id).to_string());
+        let refusals: Vec<String> = RefusalReason::ALL
+            .iter()
+            .filter_map(|reason| {
+                self.refusal_reasons
+                    .get(reason)
+                    .map(|count| format!("{}: {count}", reason.as_str()))
+            })
+            .collect();
+        payload.insert("Refusals".into(), refusals.join(", "));
+        self.insert_asteroid_counts(&mut payload);
+        #[cfg(feature = "metrics")]
+        payload.insert("Timings".into(), self.timing_report());
+        if let Some(report) = self.shadow_report() {
+            payload.insert("Shadow".into(), report.to_string());
+        }
+        payload
+    }
+
+    /// How long the Sunray, explorer and Asteroid handlers took, bucketed
+    /// per message name. Only available with the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn timing_report(&self) -> String {
+        self.timings.report()
+    }
+
+    /// Lifetime counters in the Prometheus text exposition format, labelled
+    /// with the planet id, e.g. `orbitron_sunrays_charged_total{planet="42"} 3`.
+    ///
+    /// Built from the counters directly, so it doesn't depend on the log
+    /// settings.
+    pub fn metrics_prometheus(&self) -> String {
+        let sunrays_charged: u64 = self.charge_events.values().sum();
+        let metrics = [
+            (
+                "orbitron_sunrays_charged_total",
+                "Sunrays that charged a cell.",
+                sunrays_charged,
+            ),
+            (
+                "orbitron_sunrays_wasted_total",
+                "Sunrays handed back because every cell was charged.",
+                self.sunrays_wasted,
+            ),
+            (
+                "orbitron_generations_succeeded_total",
+                "Basic resources generated for explorers.",
+                self.generations_succeeded,
+            ),
+            (
+                "orbitron_generations_failed_total",
+                "Generations attempted that produced nothing.",
+                self.generations_failed,
+            ),
+            (
+                "orbitron_asteroids_survived_total",
+                "Asteroids deflected with a rocket.",
+                self.asteroids_survived,
+            ),
+        ];
+        let mut out = String::new();
+        for (name, help, value) in metrics {
+            out.push_str(&format!("# HELP {name} {help}\n"));
+            out.push_str(&format!("# TYPE {name} counter\n"));
+            out.push_str(&format!(
+                "{name}{{planet=\"{}\"}} {value}\n",
+                self.planet_id
+            ));
+        }
+        out
+    }
+
+    /// Clears the lifetime statistics: products, explorer requests, refusals,
+    /// asteroid history, suspected duplicate Sunrays, protocol anomalies,
+    /// memory pressure events, charge events, generations, messages after
+    /// destruction, log events emitted, round statistics and the shadow
+    /// report.
+    ///
+    /// Counters the AI decides on, such as the tick and the round, are left
+    /// alone.
+    pub fn reset_stats(&mut self) {
+        self.complex_generated.clear();
+        self.explorer_requests.clear();
+        self.refusal_reasons.clear();
+        self.asteroids_faced = 0;
+        self.asteroids_survived = 0;
+        self.last_asteroid_tick = None;
+        self.suspected_duplicate_sunrays = 0;
+        self.protocol_anomalies = 0;
+        self.memory_pressure_events = 0;
+        self.charge_events.clear();
+        self.sunrays_wasted = 0;
+        self.generations_succeeded = 0;
+        self.generations_failed = 0;
+        self.generator_errors = 0;
+        self.generator_retries_succeeded = 0;
+        self.rounds.clear();
+        self.round = RoundStats {
+            round: self.current_round,
+            ..Default::default()
+        };
+        #[cfg(feature = "dry-run")]
+        self.dry_run_decisions.clear();
+        self.messages_after_destruction = 0;
+        self.events_emitted.set(0);
+        self.shadow_report = ShadowReport::default();
+    }
+
+    /// Resources currently held by the planet, by type.
+    ///
+    /// [PlanetState] doesn't keep generated resources: they are handed to the
+    /// explorer that asked for them. The only ones the planet holds are those
+    /// in escrow (see [OrbitronConfig::escrow_capacity]), so with escrow
+    /// disabled this is always empty.
+    pub fn inventory_summary(&self) -> HashMap<BasicResourceType, u32> {
+        let mut inventory = HashMap::new();
+        for (_, resource, _) in &self.escrow {
+            *inventory.entry(*resource).or_insert(0) += 1;
+        }
+        inventory
+    }
+
+    /// Asteroids faced and survived, and the tick of the last one.
+    pub(crate) fn insert_asteroid_counts(&self, payload: &mut Payload) {
+        payload.insert("Asteroids Faced".into(), self.asteroids_faced.to_string());
+        payload.insert(
+            "Asteroids Survived".into(),
+            self.asteroids_survived.to_string(),
+        );
+        payload.insert(
+            "Last Asteroid Tick".into(),
+            self.last_asteroid_tick
+                .map_or("never".into(), |tick| tick.to_string()),
+        );
+    }
+}
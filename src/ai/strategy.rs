@@ -0,0 +1,86 @@
+//! # Orbitron – Strategies
+//!
+//! An [OrbitronStrategy] takes the last say on explorer requests that passed
+//! every guard of the AI (access, defensive mode, caps, back-off). The stock
+//! [DefaultStrategy] serves all of them.
+//!
+//! A second strategy can run in shadow mode next to the primary one (see
+//! [Orbitron::with_shadow](super::orbitron::Orbitron::with_shadow)): it is
+//! asked the same questions, its answers are only compared with the primary
+//! ones in a [ShadowReport]. Strategies get plain values, never the planet
+//! state, so a shadow can't change anything.
+use std::fmt;
+
+use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+use common_game::utils::ID;
+
+/// Decides whether to spend a cell on an explorer request.
+pub trait OrbitronStrategy: Send {
+    /// Whether to generate `resource` for `explorer_id`.
+    fn serve_generate(
+        &self,
+        explorer_id: ID,
+        resource: BasicResourceType,
+        charged_cells: u32,
+    ) -> bool;
+
+    /// Whether to combine `recipe` for `explorer_id`.
+    fn serve_combine(
+        &self,
+        explorer_id: ID,
+        recipe: ComplexResourceType,
+        charged_cells: u32,
+    ) -> bool;
+}
+
+/// Serves every request that reaches it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultStrategy;
+
+impl OrbitronStrategy for DefaultStrategy {
+    fn serve_generate(&self, _: ID, _: BasicResourceType, _: u32) -> bool {
+        true
+    }
+
+    fn serve_combine(&self, _: ID, _: ComplexResourceType, _: u32) -> bool {
+        true
+    }
+}
+
+/// Answers of a shadow strategy compared with the primary ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShadowTally {
+    pub agreed: u64,
+    pub disagreed: u64,
+}
+
+impl ShadowTally {
+    pub(crate) fn add(&mut self, primary: bool, shadow: bool) {
+        let counter = if primary == shadow {
+            &mut self.agreed
+        } else {
+            &mut self.disagreed
+        };
+        *counter = counter.saturating_add(1);
+    }
+}
+
+/// Agreement of a shadow strategy with the primary one, per decision type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShadowReport {
+    pub generate: ShadowTally,
+    pub combine: ShadowTally,
+}
+
+/// e.g. `generate 3/4 agreed, combine 0/1 agreed`.
+impl fmt::Display for ShadowReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tally = |t: &ShadowTally| format!("{}/{} agreed", t.agreed, t.agreed + t.disagreed);
+        write!(
+            f,
+            "generate {}, combine {}",
+            tally(&self.generate),
+            tally(&self.combine)
+        )
+    }
+}
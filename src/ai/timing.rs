@@ -0,0 +1,98 @@
+//! # Orbitron – Handler Timing
+//!
+//! Only built with the `metrics` feature. Every handled message is timed and
+//! counted in a small fixed histogram, one per message name, readable with
+//! [Orbitron::timing_report](super::orbitron::Orbitron::timing_report).
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Upper bounds of every bucket but the last, which takes the rest.
+const BUCKET_LIMITS: [Duration; 3] = [
+    Duration::from_micros(10),
+    Duration::from_micros(100),
+    Duration::from_millis(1),
+];
+
+/// Labels of the buckets, in order.
+const BUCKET_LABELS: [&str; 4] = ["<10us", "<100us", "<1ms", ">=1ms"];
+
+/// Handler durations, bucketed by message name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Timings(Arc<Mutex<BTreeMap<String, [u64; 4]>>>);
+
+impl Timings {
+    /// Starts timing `message`; the duration is recorded when the returned
+    /// timer is dropped, so early returns are covered too.
+    pub(crate) fn start(&self, message: impl Into<String>) -> Timer {
+        Timer {
+            timings: self.clone(),
+            message: message.into(),
+            started: Instant::now(),
+        }
+    }
+
+    pub(crate) fn record(&self, message: String, elapsed: Duration) {
+        let bucket = BUCKET_LIMITS
+            .iter()
+            .position(|limit| elapsed < *limit)
+            .unwrap_or(BUCKET_LIMITS.len());
+        self.0.lock().unwrap().entry(message).or_default()[bucket] += 1;
+    }
+
+    /// Counts per bucket, by message name.
+    pub(crate) fn counts(&self) -> BTreeMap<String, [u64; 4]> {
+        self.0.lock().unwrap().clone()
+    }
+
+    /// One `name: bucket=count ...` entry per message, separated by `; `.
+    pub(crate) fn report(&self) -> String {
+        let entries: Vec<String> = self
+            .counts()
+            .iter()
+            .map(|(message, counts)| {
+                let buckets: Vec<String> = BUCKET_LABELS
+                    .iter()
+                    .zip(counts)
+                    .map(|(label, count)| format!("{label}={count}"))
+                    .collect();
+                format!("{message}: {}", buckets.join(" "))
+            })
+            .collect();
+        entries.join("; ")
+    }
+}
+
+/// Records the time elapsed since its creation when dropped.
+pub(crate) struct Timer {
+    timings: Timings,
+    message: String,
+    started: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        self.timings
+            .record(std::mem::take(&mut self.message), self.started.elapsed());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durations_land_in_their_bucket() {
+        let timings = Timings::default();
+        timings.record("Sunray".into(), Duration::from_micros(5));
+        timings.record("Sunray".into(), Duration::from_micros(50));
+        timings.record("Sunray".into(), Duration::from_micros(500));
+        timings.record("Sunray".into(), Duration::from_millis(5));
+        timings.record("Asteroid".into(), Duration::from_millis(1));
+
+        assert_eq!(
+            timings.report(),
+            "Asteroid: <10us=0 <100us=0 <1ms=0 >=1ms=1; Sunray: <10us=1 <100us=1 <1ms=1 >=1ms=1"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! # Orbitron – Watchdog
+//!
+//! The planet loop belongs to `common_game`: if it ever blocks, for instance
+//! on a full outbound channel, the AI is simply never called again. The
+//! [Watchdog] watches from its own thread a [Heartbeat] the AI beats after
+//! every handled message, and reports a stall once the heartbeat is older
+//! than the timeout while messages are still waiting.
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{RecvTimeoutError, Sender, bounded};
+
+/// Time of the last message handled by the AI.
+#[derive(Debug, Clone)]
+pub(crate) struct Heartbeat(Arc<Mutex<Instant>>);
+
+impl Default for Heartbeat {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(Instant::now())))
+    }
+}
+
+impl Heartbeat {
+    pub(crate) fn beat(&self) {
+        *self.0.lock().unwrap() = Instant::now();
+    }
+
+    pub(crate) fn elapsed(&self) -> Duration {
+        self.0.lock().unwrap().elapsed()
+    }
+}
+
+/// Thread checking a [Heartbeat]; stopped and joined when dropped.
+///
+/// The thread waits on a stop channel between checks, so dropping the
+/// watchdog wakes it at once instead of waiting out the check interval.
+#[derive(Debug)]
+pub(crate) struct Watchdog {
+    stop: Option<Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Watchdog {
+    /// Calls `on_stall` with the heartbeat's age once it exceeds `timeout`
+    /// while `is_busy` holds. A stall is reported once, until the next beat.
+    pub(crate) fn spawn(
+        timeout: Duration,
+        heartbeat: Heartbeat,
+        is_busy: impl Fn() -> bool + Send + 'static,
+        on_stall: impl Fn(Duration) + Send + 'static,
+    ) -> Self {
+        let (stop, stopped) = bounded::<()>(0);
+        let thread = std::thread::spawn(move || {
+            let mut reported = false;
+            while stopped.recv_timeout(timeout / 4) == Err(RecvTimeoutError::Timeout) {
+                let elapsed = heartbeat.elapsed();
+                if elapsed <= timeout {
+                    reported = false;
+                } else if !reported && is_busy() {
+                    on_stall(elapsed);
+                    reported = true;
+                }
+            }
+        });
+        Self {
+            stop: Some(stop),
+            thread: Some(thread),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        // Disconnecting the channel ends the thread's wait
+        self.stop.take();
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -11,7 +11,7 @@
 //! The resulting configuration is passed to [`Planet::new`], which returns a
 //! fully-initialized [`Planet`] instance or reports configuration errors.
 #![allow(rustdoc::private_intra_doc_links)]
-use common_game::components::planet::{Planet, PlanetType};
+use common_game::components::planet::{Planet, PlanetAI, PlanetType};
 use common_game::components::resource::{BasicResourceType, ComplexResourceType};
 use common_game::logging::*;
 use common_game::protocols::orchestrator_planet::*;
@@ -20,9 +20,66 @@ use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
 
 mod ai;
-use ai::orbitron::Orbitron;
+#[cfg(test)]
+mod testkit;
+
+#[cfg(feature = "checkpoint")]
+pub use ai::checkpoint::{CHECKPOINT_VERSION, Checkpoint, Counters};
+pub use ai::config::{
+    ChargeOrder, EVENT_TYPES, LogCategory, LogVerbosity, OrbitronConfig, RoundBoundary,
+    UnknownRecipePolicy, Verbosity, config_payload,
+};
+pub use ai::error::{OrbitronError, error_code};
+#[cfg(any(test, feature = "test-util"))]
+pub use ai::handlers::{
+    asteroid::process_asteroid,
+    explorer::process_explorer_msg,
+    orchestrator::{process_internal_state_req, process_sunray},
+};
+#[cfg(any(test, feature = "test-util"))]
+pub use ai::orbitron::CapturedEvent;
+pub use ai::orbitron::{
+    Direction, EventRecord, ExplorerRecord, ExplorerRegistry, ExplorerSenders, HistoryEntry,
+    Lifecycle, Orbitron, OrbitronBuilder, OrbitronSnapshot, OrchestratorLink, PauseHandle,
+    PlanetDescription, RefusalReason, RoundStats, SnapshotHandle, Survivability, TappedMessage,
+    asteroid_survivability, is_saturated,
+};
+pub use ai::strategy::{DefaultStrategy, OrbitronStrategy, ShadowReport, ShadowTally};
+
+use ai::logging::{emit_global, orchestrator_actor, planet_actor};
+
+/// Identifies this planet implementation for the orchestrator's registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanetMetadata {
+    pub ai_name: &'static str,
+    /// Version of this crate.
+    pub version: &'static str,
+    pub planet_type: PlanetType,
+    pub gen_rules: Vec<BasicResourceType>,
+    pub comb_rules: Vec<ComplexResourceType>,
+}
+
+/// Returns the implementation, version, type and rules of the Orbitron planet.
+///
+/// The AI logs the same data the first time it is started.
+pub fn planet_metadata() -> PlanetMetadata {
+    PlanetMetadata {
+        ai_name: "Orbitron",
+        version: env!("CARGO_PKG_VERSION"),
+        planet_type: PlanetType::B,
+        gen_rules: GEN_RULES.to_vec(),
+        comb_rules: COMB_RULES.to_vec(),
+    }
+}
+
+/// Basic resources this planet can generate on its own.
+const GEN_RULES: [BasicResourceType; 2] = [BasicResourceType::Hydrogen, BasicResourceType::Oxygen];
+/// Complex resources that can be formed from combinations.
+const COMB_RULES: [ComplexResourceType; 1] = [ComplexResourceType::Water];
 
-const ORCHESTRATOR_ID: ID = 0;
+// A rule without a recipe table entry would be advertised but never served
+const _: () =
+    assert!(ai::recipes::generates_all(&GEN_RULES) && ai::recipes::combines_all(&COMB_RULES));
 
 /// Creates and initializes an Orbitron planet.
 ///
@@ -40,26 +97,79 @@ const ORCHESTRATOR_ID: ID = 0;
 /// - [`Orbitron`] as the AI controlling this planet  
 ///
 /// The function returns a fully constructed [`Planet`] instance.  
+///
+/// # Explorer channel
+/// The message loop driving the planet belongs to `common_game`, so the AI
+/// never receives from `from_explorer` directly; it only keeps a clone to
+/// report the backlog (see [`Orbitron::pending_explorer_count`]). Once every explorer sender has been
+/// dropped the planet keeps serving the orchestrator; this is covered by
+/// `test_planet_serves_orchestrator_after_explorer_channel_closed`.
+///
+/// # Channel capacity
+/// This crate has no binary: capacities, and what happens when a channel
+/// fills up, are chosen by the caller. Messages to the orchestrator go
+/// through [`Orbitron::relay_to_orchestrator`], whose channel holds nothing, so
+/// with a bounded channel a full `to_orchestrator` still holds the planet
+/// loop until the orchestrator drains it.
 pub fn create_planet(
     from_orchestrator: Receiver<OrchestratorToPlanet>,
     to_orchestrator: Sender<PlanetToOrchestrator>,
     from_explorer: Receiver<ExplorerToPlanet>,
     planet_id: ID,
 ) -> Planet {
-    let planet_type = PlanetType::B;
-    // Basic resources this planet can generate on its own.
-    let gen_rules = vec![BasicResourceType::Hydrogen, BasicResourceType::Oxygen];
-    // Complex resources that can be formed from combinations.
-    let comb_rules = vec![ComplexResourceType::Water];
+    create_planet_with_config(
+        from_orchestrator,
+        to_orchestrator,
+        from_explorer,
+        planet_id,
+        OrbitronConfig::default(),
+    )
+}
+
+/// Creates an Orbitron planet whose AI uses the given [`OrbitronConfig`].
+///
+/// Apart from the AI configuration, the planet is identical to the one
+/// returned by [`create_planet`].
+pub fn create_planet_with_config(
+    from_orchestrator: Receiver<OrchestratorToPlanet>,
+    to_orchestrator: Sender<PlanetToOrchestrator>,
+    from_explorer: Receiver<ExplorerToPlanet>,
+    planet_id: ID,
+    config: OrbitronConfig,
+) -> Planet {
     // AI logic controlling the planet's behavior.
-    let ai: Box<Orbitron> = Box::new(Orbitron::new(planet_id));
+    let ai = Orbitron::with_config(planet_id, config);
+
+    orbitron_planet(
+        from_orchestrator,
+        to_orchestrator,
+        from_explorer,
+        planet_id,
+        ai,
+    )
+}
+
+/// Creates a planet with the Orbitron type and rules, controlled by `ai`.
+///
+/// Lets callers supply an [Orbitron] built with [OrbitronBuilder], or any
+/// other [PlanetAI]. Unlike [create_planet], an injected Orbitron doesn't
+/// watch the explorer backlog unless [Orbitron::watch_explorer_queue] was
+/// called on it with a clone of `from_explorer`.
+pub fn create_planet_with_ai(
+    from_orchestrator: Receiver<OrchestratorToPlanet>,
+    to_orchestrator: Sender<PlanetToOrchestrator>,
+    from_explorer: Receiver<ExplorerToPlanet>,
+    planet_id: ID,
+    ai: Box<dyn PlanetAI>,
+) -> Planet {
+    let metadata = planet_metadata();
 
     let planet = Planet::new(
         planet_id,
-        planet_type,
+        metadata.planet_type,
         ai,
-        gen_rules,
-        comb_rules,
+        metadata.gen_rules,
+        metadata.comb_rules,
         (from_orchestrator, to_orchestrator),
         from_explorer,
     )
@@ -70,18 +180,111 @@ pub fn create_planet(
     payload.insert("gen_rules".into(), "Hydrogen, Oxygen".into());
     payload.insert("comb_rules".into(), "Water".into());
     payload.insert("Message".into(), "New planet orbitron created".into());
-    LogEvent::new(
-        Some(Participant::new(ActorType::Orchestrator, ORCHESTRATOR_ID)),
-        Some(Participant::new(ActorType::Planet, planet_id)),
+    emit_global(LogEvent::new(
+        Some(orchestrator_actor()),
+        Some(planet_actor(planet_id)),
         EventType::MessageOrchestratorToPlanet,
         Channel::Info,
         payload,
-    )
-    .emit();
+    ));
 
     planet
 }
 
+/// Describes the capabilities of `planet` in a single call.
+///
+/// The planet/explorer protocol is defined by `common_game` and can't carry
+/// a new request, so explorers sharing a process with the planet (and the
+/// orchestrator) use this instead of the three separate queries.
+pub fn describe_planet(planet: &Planet) -> PlanetDescription {
+    ai::orbitron::describe(
+        planet.planet_type(),
+        planet.state(),
+        planet.generator(),
+        planet.combinator(),
+    )
+}
+
+/// How a planet's run loop ended, see [run_planet].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The loop returned normally, after a `KillPlanet`.
+    Completed { planet_id: ID },
+    /// The loop failed, e.g. because the orchestrator hung up.
+    Crashed { planet_id: ID, error: String },
+}
+
+impl RunOutcome {
+    /// Process exit code for a binary running a single planet.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            RunOutcome::Completed { .. } => 0,
+            RunOutcome::Crashed { .. } => 1,
+        }
+    }
+}
+
+/// Runs `planet` until its loop returns, logging a crash as an error.
+pub fn run_planet(planet: Planet) -> RunOutcome {
+    run_planet_with_link(planet, &OrchestratorLink::default())
+}
+
+/// Like [run_planet], also raising `link` when the loop fails.
+///
+/// `link` should come from [Orbitron::orchestrator_link] on the AI of
+/// `planet`, so the AI stops spending cells for an orchestrator that is gone.
+pub fn run_planet_with_link(mut planet: Planet, link: &OrchestratorLink) -> RunOutcome {
+    let planet_id = planet.id();
+    let Err(error) = planet.run() else {
+        return RunOutcome::Completed { planet_id };
+    };
+    link.mark_unreachable();
+
+    let mut payload = Payload::new();
+    payload.insert("event".into(), "PlanetCrashed".into());
+    payload.insert("error".into(), error.clone());
+    emit_global(LogEvent::self_directed(
+        planet_actor(planet_id),
+        EventType::InternalPlanetAction,
+        Channel::Error,
+        payload,
+    ));
+    RunOutcome::Crashed { planet_id, error }
+}
+
+/// Drives `planet` from async code, resolving once [Planet::run] returns.
+///
+/// The message handling is the one of [Planet::run] itself: the loop runs on
+/// tokio's blocking pool, so the planet answers exactly as it does on its own
+/// thread. The planet state the AI works on can only be built by
+/// `common_game`, which rules out a second, truly async loop.
+///
+/// Must be called from within a tokio runtime.
+#[cfg(feature = "tokio")]
+pub fn run_async(mut planet: Planet) -> impl Future<Output = Result<(), String>> {
+    let run = tokio::task::spawn_blocking(move || planet.run());
+    async move { run.await.map_err(|err| err.to_string())? }
+}
+
+/// Wires the Orbitron rules around an already constructed AI.
+fn orbitron_planet(
+    from_orchestrator: Receiver<OrchestratorToPlanet>,
+    to_orchestrator: Sender<PlanetToOrchestrator>,
+    from_explorer: Receiver<ExplorerToPlanet>,
+    planet_id: ID,
+    mut ai: Orbitron,
+) -> Planet {
+    ai.watch_explorer_queue(from_explorer.clone());
+    let to_orchestrator = ai.relay_to_orchestrator(to_orchestrator);
+    create_planet_with_ai(
+        from_orchestrator,
+        to_orchestrator,
+        from_explorer,
+        planet_id,
+        Box::new(ai),
+    )
+}
+
 // Test for create planet sections
 #[cfg(test)]
 mod tests {
@@ -89,16 +292,19 @@ mod tests {
     use common_game::components::planet::PlanetState;
     use common_game::components::resource::{Combinator, Generator};
     use crossbeam_channel::unbounded;
+    use std::collections::HashSet;
 
-    // Helper function to create test channels
-    fn setup_test_channels() -> (
+    type TestChannels = (
         Receiver<OrchestratorToPlanet>,
         Sender<PlanetToOrchestrator>,
         Receiver<ExplorerToPlanet>,
         Sender<OrchestratorToPlanet>,
         Receiver<PlanetToOrchestrator>,
         Sender<ExplorerToPlanet>,
-    ) {
+    );
+
+    // Helper function to create test channels
+    fn setup_test_channels() -> TestChannels {
         let (tx_orch_to_planet, rx_orch_to_planet) = unbounded::<OrchestratorToPlanet>();
         let (tx_planet_to_orch, rx_planet_to_orch) = unbounded::<PlanetToOrchestrator>();
         let (tx_expl_to_planet, rx_expl_to_planet) = unbounded::<ExplorerToPlanet>();
@@ -185,8 +391,7 @@ mod tests {
     fn planet_create() -> Planet {
         let (rx_orch, tx_orch, rx_expl, _, _, _) = setup_test_channels();
         let planet_id = 42;
-        let planet = create_planet(rx_orch, tx_orch, rx_expl, planet_id);
-        planet
+        create_planet(rx_orch, tx_orch, rx_expl, planet_id)
     }
     #[test]
     fn test_supported_resource_request() {
@@ -235,4 +440,209 @@ mod tests {
             _ => panic!("Unexpected response"),
         }
     }
+    #[test]
+    fn test_planet_serves_orchestrator_after_explorer_channel_closed() {
+        let (mut planet, _) = testkit::TestPlanet::orbitron(OrbitronConfig::default());
+        planet.start();
+        planet.close_explorer_channel();
+
+        planet.sunray();
+        planet.send(OrchestratorToPlanet::InternalStateRequest);
+        match planet.recv() {
+            PlanetToOrchestrator::InternalStateResponse { planet_state, .. } => {
+                assert_eq!(planet_state.charged_cells_count, 1);
+            }
+            other => panic!("expected InternalStateResponse, got {other:?}"),
+        }
+    }
+    #[test]
+    fn test_describe_planet_matches_individual_queries() {
+        let planet = planet_create();
+        let description = describe_planet(&planet);
+
+        assert_eq!(description.planet_type, PlanetType::B);
+        assert_eq!(description.total_cells, planet.state().cells_count());
+        assert!(!description.can_build_rockets);
+        for msg in [
+            ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 },
+            ExplorerToPlanet::SupportedCombinationRequest { explorer_id: 1 },
+            ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 },
+        ] {
+            match handle_explorer_msg(planet.state(), planet.generator(), planet.combinator(), msg)
+            {
+                Some(PlanetToExplorer::SupportedResourceResponse { resource_list }) => {
+                    assert_eq!(resource_list, description.basic_recipes);
+                }
+                Some(PlanetToExplorer::SupportedCombinationResponse { combination_list }) => {
+                    assert_eq!(combination_list, description.complex_recipes);
+                }
+                Some(PlanetToExplorer::AvailableEnergyCellResponse { available_cells }) => {
+                    assert_eq!(available_cells, description.charged_cells);
+                }
+                _ => panic!("Unexpected response"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_planet_metadata_version_matches_manifest() {
+        let manifest = include_str!("../Cargo.toml");
+        let version = manifest
+            .lines()
+            .find_map(|line| line.strip_prefix("version = "))
+            .unwrap()
+            .trim_matches('"');
+
+        let metadata = planet_metadata();
+        assert_eq!(metadata.ai_name, "Orbitron");
+        assert_eq!(metadata.version, version);
+        assert_eq!(metadata.planet_type, PlanetType::B);
+    }
+
+    #[test]
+    fn test_create_planet_with_injected_ai() {
+        let config = OrbitronConfig {
+            denied_explorers: HashSet::from([1]),
+            ..Default::default()
+        };
+        let ai = OrbitronBuilder::new(42).config(config).build();
+        let handle = ai.snapshot_handle();
+        let planet = testkit::TestPlanet::spawn(|rx_orch, tx_orch, rx_expl| {
+            create_planet_with_ai(rx_orch, tx_orch, rx_expl, 42, Box::new(ai))
+        });
+        let denied = planet.explorer(1);
+        let allowed = planet.explorer(2);
+        planet.start();
+        planet.sunray();
+
+        assert!(denied.generate(BasicResourceType::Oxygen).is_none());
+        assert!(allowed.generate(BasicResourceType::Oxygen).is_some());
+        assert_eq!(
+            handle.get().refusal_reasons.get(&RefusalReason::NotAllowed),
+            Some(&1)
+        );
+    }
+
+    #[test]
+    fn test_run_planet_completed_on_kill() {
+        let (rx_orch, tx_orch, rx_expl, to_planet, _from_planet, _tx_expl) = setup_test_channels();
+        let planet = create_planet(rx_orch, tx_orch, rx_expl, 7);
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+
+        let outcome = run_planet(planet);
+        assert_eq!(outcome, RunOutcome::Completed { planet_id: 7 });
+        assert_eq!(outcome.exit_code(), 0);
+    }
+
+    #[test]
+    fn test_run_planet_crashed_without_orchestrator() {
+        let (rx_orch, tx_orch, rx_expl, to_planet, _from_planet, _tx_expl) = setup_test_channels();
+        let planet = create_planet(rx_orch, tx_orch, rx_expl, 7);
+        drop(to_planet);
+
+        let outcome = run_planet(planet);
+        assert!(matches!(outcome, RunOutcome::Crashed { planet_id: 7, .. }));
+        assert_eq!(outcome.exit_code(), 1);
+    }
+
+    #[test]
+    fn test_dropped_orchestrator_ends_run_and_raises_link() {
+        let (rx_orch, tx_orch, rx_expl, to_planet, from_planet, tx_expl) = setup_test_channels();
+        let ai = Orbitron::new(7);
+        let link = ai.orchestrator_link();
+        let planet = orbitron_planet(rx_orch, tx_orch, rx_expl, 7, ai);
+        let (done_tx, done) = unbounded();
+        let thread_link = link.clone();
+        std::thread::spawn(move || {
+            let _ = done_tx.send(run_planet_with_link(planet, &thread_link));
+        });
+
+        let (to_explorer, from_planet_to_explorer) = unbounded();
+        for msg in [
+            OrchestratorToPlanet::StartPlanetAI,
+            OrchestratorToPlanet::IncomingExplorerRequest {
+                explorer_id: 1,
+                new_sender: to_explorer,
+            },
+            OrchestratorToPlanet::Sunray(testkit::sunray()),
+        ] {
+            to_planet.send(msg).unwrap();
+            from_planet.recv_timeout(testkit::TIMEOUT).unwrap();
+        }
+        drop(from_planet);
+
+        // The answer can't be delivered: the relay raises the link while
+        // the loop keeps running
+        to_planet
+            .send(OrchestratorToPlanet::InternalStateRequest)
+            .unwrap();
+        let deadline = std::time::Instant::now() + testkit::TIMEOUT;
+        while !link.is_unreachable() {
+            assert!(std::time::Instant::now() < deadline, "link never raised");
+            std::thread::yield_now();
+        }
+        tx_expl
+            .send(ExplorerToPlanet::GenerateResourceRequest {
+                explorer_id: 1,
+                resource: BasicResourceType::Hydrogen,
+            })
+            .unwrap();
+        assert!(matches!(
+            from_planet_to_explorer.recv_timeout(testkit::TIMEOUT),
+            Ok(PlanetToExplorer::GenerateResourceResponse { resource: None })
+        ));
+        tx_expl
+            .send(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: 1 })
+            .unwrap();
+        assert!(matches!(
+            from_planet_to_explorer.recv_timeout(testkit::TIMEOUT),
+            Ok(PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 1 })
+        ));
+
+        // The loop itself fails at its next send
+        to_planet
+            .send(OrchestratorToPlanet::Sunray(testkit::sunray()))
+            .unwrap();
+        let outcome = done.recv_timeout(testkit::TIMEOUT).unwrap();
+        assert!(matches!(outcome, RunOutcome::Crashed { planet_id: 7, .. }));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_run_async_acks_sunray() {
+        let (rx_orch, tx_orch, rx_expl, to_planet, from_planet, _tx_expl) = setup_test_channels();
+        let planet = create_planet(rx_orch, tx_orch, rx_expl, 1);
+        let run = run_async(planet);
+
+        to_planet.send(OrchestratorToPlanet::StartPlanetAI).unwrap();
+        to_planet
+            .send(OrchestratorToPlanet::Sunray(testkit::sunray()))
+            .unwrap();
+        to_planet.send(OrchestratorToPlanet::KillPlanet).unwrap();
+        assert_eq!(run.await, Ok(()));
+
+        let responses: Vec<_> = from_planet.try_iter().collect();
+        assert!(matches!(
+            responses.as_slice(),
+            [
+                PlanetToOrchestrator::StartPlanetAIResult { .. },
+                PlanetToOrchestrator::SunrayAck { planet_id: 1 },
+                PlanetToOrchestrator::KillPlanetResult { .. },
+            ]
+        ));
+    }
+
+    #[test]
+    fn test_standard_game_matches_golden() {
+        let (planet, snapshot) = testkit::TestPlanet::orbitron(OrbitronConfig::default());
+        let trace = testkit::trace_game(planet, &snapshot, testkit::standard_game());
+        testkit::assert_golden("standard_game", &trace);
+    }
+
+    #[test]
+    fn test_stopped_game_matches_golden() {
+        let (planet, snapshot) = testkit::TestPlanet::orbitron(OrbitronConfig::default());
+        let trace = testkit::trace_game(planet, &snapshot, testkit::stopped_game());
+        testkit::assert_golden("stopped_game", &trace);
+    }
 }
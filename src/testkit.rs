@@ -0,0 +1,615 @@
+//! Test harness driving a real Orbitron [`Planet`] over its channels.
+//!
+//! [`TestPlanet`] runs the planet loop on its own thread and exposes small
+//! blocking helpers that send one message and wait for the matching reply,
+//! so tests read as a linear script of orchestrator and explorer actions.
+//!
+//! [`SteppedPlanet`] goes one step further: every orchestrator or explorer
+//! message is a single call returning the planet's answer, so a whole game
+//! is a sequence of `step` calls with plain assertions. The planet state can
+//! only be built by `common_game`, so the loop still runs on a thread; each
+//! step just waits for its answer before returning.
+//!
+//! [`trace_game`] plays a whole script on a stepped planet and reduces it to
+//! a decision trace, which [`assert_golden`] compares with a checked-in file.
+//!
+//! Tests that don't need the loop at all use the parts of an idle planet
+//! from [`fixtures`].
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use common_game::components::asteroid::Asteroid;
+use common_game::components::energy_cell::EnergyCell;
+use common_game::components::forge::Forge;
+use common_game::components::planet::{Planet, PlanetType};
+use common_game::components::resource::{
+    BasicResource, BasicResourceType, Combinator, ComplexResource, ComplexResourceRequest,
+    ComplexResourceType, Generator, GenericResource,
+};
+use common_game::components::rocket::Rocket;
+use common_game::components::sunray::Sunray;
+use common_game::protocols::orchestrator_planet::*;
+use common_game::protocols::planet_explorer::*;
+use common_game::utils::ID;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+
+use crate::{Orbitron, OrbitronConfig, SnapshotHandle, orbitron_planet};
+
+pub(crate) mod fixtures;
+
+/// How long a helper waits for the planet to answer before failing the test.
+pub(crate) const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Id of the planets spawned by [TestPlanet::custom].
+pub(crate) const CUSTOM_PLANET_ID: ID = 43;
+
+/// Receives from `$rx` within [`TIMEOUT`] and matches the message against
+/// `$pattern`, evaluating to `$out` (or `()`).
+///
+/// Fails the test naming the variant actually received, or the timeout.
+macro_rules! expect_response {
+    ($rx:expr, $pattern:pat) => {
+        $crate::testkit::expect_response!($rx, $pattern => ())
+    };
+    ($rx:expr, $pattern:pat => $out:expr) => {
+        match $rx.recv_timeout($crate::testkit::TIMEOUT) {
+            Ok($pattern) => $out,
+            Ok(other) => panic!(
+                "expected {}, got {}",
+                stringify!($pattern),
+                $crate::testkit::variant_name(&other)
+            ),
+            Err(err) => panic!("expected {}, got nothing: {err}", stringify!($pattern)),
+        }
+    };
+}
+pub(crate) use expect_response;
+
+/// Name of the enum variant of `msg`, read from its `Debug` output.
+pub(crate) fn variant_name(msg: &impl std::fmt::Debug) -> String {
+    format!("{msg:?}")
+        .chars()
+        .take_while(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// The forge is a process-wide singleton, so every test shares this one.
+static FORGE: LazyLock<Mutex<Forge>> =
+    LazyLock::new(|| Mutex::new(Forge::new().expect("forge already created")));
+
+pub(crate) fn sunray() -> Sunray {
+    FORGE.lock().unwrap().generate_sunray()
+}
+
+pub(crate) fn asteroid() -> Asteroid {
+    FORGE.lock().unwrap().generate_asteroid()
+}
+
+/// A standalone energy cell, already charged.
+pub(crate) fn charged_cell() -> EnergyCell {
+    let mut cell = EnergyCell::new();
+    cell.charge(sunray());
+    cell
+}
+
+/// A generator and combinator knowing every recipe, used to forge the
+/// resources explorers bring along in their requests.
+pub(crate) fn lab() -> (Generator, Combinator) {
+    let mut generator = Generator::new();
+    for resource in [
+        BasicResourceType::Hydrogen,
+        BasicResourceType::Oxygen,
+        BasicResourceType::Carbon,
+        BasicResourceType::Silicon,
+    ] {
+        generator.add(resource).unwrap();
+    }
+    let mut combinator = Combinator::new();
+    for resource in [
+        ComplexResourceType::Water,
+        ComplexResourceType::Diamond,
+        ComplexResourceType::Life,
+        ComplexResourceType::Robot,
+        ComplexResourceType::Dolphin,
+        ComplexResourceType::AIPartner,
+    ] {
+        combinator.add(resource).unwrap();
+    }
+    (generator, combinator)
+}
+
+/// A planet running on a background thread, seen from the orchestrator side.
+pub(crate) struct TestPlanet {
+    to_planet: Sender<OrchestratorToPlanet>,
+    from_planet: Receiver<PlanetToOrchestrator>,
+    explorer_tx: Option<Sender<ExplorerToPlanet>>,
+    thread: Option<JoinHandle<Result<(), String>>>,
+}
+
+/// An explorer docked on a [`TestPlanet`].
+pub(crate) struct TestExplorer {
+    pub(crate) id: ID,
+    to_planet: Sender<ExplorerToPlanet>,
+    from_planet: Receiver<PlanetToExplorer>,
+}
+
+impl TestPlanet {
+    /// Spawns a type B Orbitron planet using `config`.
+    ///
+    /// The returned [`SnapshotHandle`] observes the AI running inside the planet.
+    pub(crate) fn orbitron(config: OrbitronConfig) -> (Self, SnapshotHandle) {
+        let id = 42;
+        let ai = Orbitron::with_config(id, config);
+        let handle = ai.snapshot_handle();
+        let planet = Self::spawn(|rx_orch, tx_orch, rx_expl| {
+            orbitron_planet(rx_orch, tx_orch, rx_expl, id, ai)
+        });
+        (planet, handle)
+    }
+
+    /// Spawns a planet of any type and rule set, controlled by an Orbitron AI.
+    pub(crate) fn custom(
+        planet_type: PlanetType,
+        gen_rules: Vec<BasicResourceType>,
+        comb_rules: Vec<ComplexResourceType>,
+        config: OrbitronConfig,
+    ) -> (Self, SnapshotHandle) {
+        let ai = Orbitron::with_config(CUSTOM_PLANET_ID, config);
+        Self::custom_ai(planet_type, gen_rules, comb_rules, ai)
+    }
+
+    /// Like [TestPlanet::custom], with an AI prepared by the caller; the AI
+    /// must be built for planet [CUSTOM_PLANET_ID].
+    pub(crate) fn custom_ai(
+        planet_type: PlanetType,
+        gen_rules: Vec<BasicResourceType>,
+        comb_rules: Vec<ComplexResourceType>,
+        ai: Orbitron,
+    ) -> (Self, SnapshotHandle) {
+        let id = CUSTOM_PLANET_ID;
+        let handle = ai.snapshot_handle();
+        let planet = Self::spawn(|rx_orch, tx_orch, rx_expl| {
+            Planet::new(
+                id,
+                planet_type,
+                Box::new(ai),
+                gen_rules,
+                comb_rules,
+                (rx_orch, tx_orch),
+                rx_expl,
+            )
+            .unwrap()
+        });
+        (planet, handle)
+    }
+
+    /// Spawns an arbitrary planet built by `build` from fresh channels.
+    pub(crate) fn spawn(
+        build: impl FnOnce(
+            Receiver<OrchestratorToPlanet>,
+            Sender<PlanetToOrchestrator>,
+            Receiver<ExplorerToPlanet>,
+        ) -> Planet,
+    ) -> Self {
+        let (to_planet, rx_orch) = unbounded::<OrchestratorToPlanet>();
+        let (tx_orch, from_planet) = unbounded::<PlanetToOrchestrator>();
+        let (explorer_tx, rx_expl) = unbounded::<ExplorerToPlanet>();
+
+        let mut planet = build(rx_orch, tx_orch, rx_expl);
+        let thread = std::thread::spawn(move || planet.run());
+
+        Self {
+            to_planet,
+            from_planet,
+            explorer_tx: Some(explorer_tx),
+            thread: Some(thread),
+        }
+    }
+
+    pub(crate) fn send(&self, msg: OrchestratorToPlanet) {
+        self.to_planet.send(msg).expect("planet thread is gone");
+    }
+
+    pub(crate) fn recv(&self) -> PlanetToOrchestrator {
+        self.from_planet
+            .recv_timeout(TIMEOUT)
+            .expect("planet did not answer the orchestrator in time")
+    }
+
+    pub(crate) fn start(&self) {
+        self.send(OrchestratorToPlanet::StartPlanetAI);
+        match self.recv() {
+            PlanetToOrchestrator::StartPlanetAIResult { .. } => {}
+            other => panic!("expected StartPlanetAIResult, got {other:?}"),
+        }
+    }
+
+    pub(crate) fn sunray(&self) {
+        self.send(OrchestratorToPlanet::Sunray(sunray()));
+        expect_response!(self.from_planet, PlanetToOrchestrator::SunrayAck { .. });
+    }
+
+    pub(crate) fn asteroid(&self) -> Option<Rocket> {
+        self.send(OrchestratorToPlanet::Asteroid(asteroid()));
+        expect_response!(
+            self.from_planet,
+            PlanetToOrchestrator::AsteroidAck { rocket, .. } => rocket
+        )
+    }
+
+    /// Drops the orchestrator's explorer sender, disconnecting the explorer
+    /// channel once every docked [`TestExplorer`] is gone too.
+    pub(crate) fn close_explorer_channel(&mut self) {
+        self.explorer_tx = None;
+    }
+
+    /// Docks a new explorer on the planet.
+    pub(crate) fn explorer(&self, explorer_id: ID) -> TestExplorer {
+        self.docked_explorer(explorer_id).0
+    }
+
+    /// Like [TestPlanet::explorer], also returning a sender to the explorer,
+    /// for an AI answering it on its own.
+    pub(crate) fn docked_explorer(
+        &self,
+        explorer_id: ID,
+    ) -> (TestExplorer, Sender<PlanetToExplorer>) {
+        let (new_sender, from_planet) = unbounded::<PlanetToExplorer>();
+        self.send(OrchestratorToPlanet::IncomingExplorerRequest {
+            explorer_id,
+            new_sender: new_sender.clone(),
+        });
+        match self.recv() {
+            PlanetToOrchestrator::IncomingExplorerResponse { .. } => {}
+            other => panic!("expected IncomingExplorerResponse, got {other:?}"),
+        }
+
+        let explorer = TestExplorer {
+            id: explorer_id,
+            to_planet: self
+                .explorer_tx
+                .clone()
+                .expect("explorer channel was closed"),
+            from_planet,
+        };
+        (explorer, new_sender)
+    }
+}
+
+impl Drop for TestPlanet {
+    fn drop(&mut self) {
+        let _ = self.to_planet.send(OrchestratorToPlanet::KillPlanet);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl TestExplorer {
+    /// Sends `msg` without waiting for an answer.
+    pub(crate) fn send(&self, msg: ExplorerToPlanet) {
+        self.to_planet.send(msg).expect("planet thread is gone");
+    }
+
+    /// Answers already received, oldest first.
+    pub(crate) fn received(&self) -> Vec<PlanetToExplorer> {
+        self.from_planet.try_iter().collect()
+    }
+
+    /// Sends `msg` and waits for the planet's answer.
+    pub(crate) fn request(&self, msg: ExplorerToPlanet) -> PlanetToExplorer {
+        self.to_planet.send(msg).expect("planet thread is gone");
+        self.from_planet
+            .recv_timeout(TIMEOUT)
+            .expect("planet did not answer the explorer in time")
+    }
+
+    pub(crate) fn generate(&self, resource: BasicResourceType) -> Option<BasicResource> {
+        match self.request(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: self.id,
+            resource,
+        }) {
+            PlanetToExplorer::GenerateResourceResponse { resource } => resource,
+            other => panic!("expected GenerateResourceResponse, got {other:?}"),
+        }
+    }
+
+    pub(crate) fn available_cells(&self) -> u32 {
+        match self.request(ExplorerToPlanet::AvailableEnergyCellRequest {
+            explorer_id: self.id,
+        }) {
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells } => available_cells,
+            other => panic!("expected AvailableEnergyCellResponse, got {other:?}"),
+        }
+    }
+
+    pub(crate) fn combine(
+        &self,
+        msg: ComplexResourceRequest,
+    ) -> Result<ComplexResource, (String, GenericResource, GenericResource)> {
+        match self.request(ExplorerToPlanet::CombineResourceRequest {
+            explorer_id: self.id,
+            msg,
+        }) {
+            PlanetToExplorer::CombineResourceResponse { complex_response } => complex_response,
+            other => panic!("expected CombineResourceResponse, got {other:?}"),
+        }
+    }
+}
+
+/// A [`TestPlanet`] driven one message at a time.
+pub(crate) struct SteppedPlanet {
+    planet: TestPlanet,
+    /// Answer channels of the explorers docked so far.
+    explorers: HashMap<ID, Receiver<PlanetToExplorer>>,
+}
+
+impl SteppedPlanet {
+    pub(crate) fn new(planet: TestPlanet) -> Self {
+        Self {
+            planet,
+            explorers: HashMap::new(),
+        }
+    }
+
+    /// Sends `msg` from the orchestrator and returns the planet's answer.
+    pub(crate) fn step_orchestrator(
+        &mut self,
+        msg: OrchestratorToPlanet,
+    ) -> Option<PlanetToOrchestrator> {
+        self.planet.send(msg);
+        self.planet.from_planet.recv_timeout(TIMEOUT).ok()
+    }
+
+    /// Sends `msg` from its explorer, docking the explorer first if needed,
+    /// and returns the planet's answer.
+    pub(crate) fn step_explorer(&mut self, msg: ExplorerToPlanet) -> Option<PlanetToExplorer> {
+        let explorer_id = msg.explorer_id();
+        if !self.explorers.contains_key(&explorer_id) {
+            let (new_sender, from_planet) = unbounded::<PlanetToExplorer>();
+            let docked = self.step_orchestrator(OrchestratorToPlanet::IncomingExplorerRequest {
+                explorer_id,
+                new_sender,
+            });
+            assert!(
+                matches!(
+                    docked,
+                    Some(PlanetToOrchestrator::IncomingExplorerResponse { .. })
+                ),
+                "explorer {explorer_id} could not dock: {docked:?}"
+            );
+            self.explorers.insert(explorer_id, from_planet);
+        }
+
+        self.planet
+            .explorer_tx
+            .as_ref()
+            .expect("explorer channel was closed")
+            .send(msg)
+            .expect("planet thread is gone");
+        self.explorers[&explorer_id].recv_timeout(TIMEOUT).ok()
+    }
+}
+
+/// One message of a scripted game, see [trace_game].
+pub(crate) enum Step {
+    Orchestrator(OrchestratorToPlanet),
+    Explorer(ExplorerToPlanet),
+}
+
+/// Plays `steps` on `planet` and returns one line per step: its number, the
+/// message, the answer and the reasons of any refusal, read from `snapshot`.
+///
+/// Only decisions are kept: planet and explorer ids, timestamps and the
+/// order of unordered sets never show up, so the trace of a given script
+/// only changes when the AI decides differently.
+pub(crate) fn trace_game(
+    planet: TestPlanet,
+    snapshot: &SnapshotHandle,
+    steps: Vec<Step>,
+) -> String {
+    let mut planet = SteppedPlanet::new(planet);
+    let mut trace = String::new();
+    for (seq, step) in steps.into_iter().enumerate() {
+        let refused_before = snapshot.get().refusal_reasons;
+        let (message, outcome) = match step {
+            Step::Orchestrator(msg) => {
+                let message = variant_name(&msg);
+                let outcome = planet
+                    .step_orchestrator(msg)
+                    .map_or("no answer".into(), |response| {
+                        orchestrator_outcome(&response)
+                    });
+                (message, outcome)
+            }
+            Step::Explorer(msg) => {
+                let message = explorer_message(&msg);
+                let outcome = planet
+                    .step_explorer(msg)
+                    .map_or("no answer".into(), |response| explorer_outcome(&response));
+                (message, outcome)
+            }
+        };
+
+        let refused_after = snapshot.get().refusal_reasons;
+        let mut refused: Vec<&str> = refused_after
+            .iter()
+            .filter(|(reason, count)| refused_before.get(reason) < Some(count))
+            .map(|(reason, _)| reason.as_str())
+            .collect();
+        refused.sort_unstable();
+
+        trace.push_str(&format!("{:02} {message} -> {outcome}", seq + 1));
+        if !refused.is_empty() {
+            trace.push_str(&format!(" [refused: {}]", refused.join(", ")));
+        }
+        trace.push('\n');
+    }
+    trace
+}
+
+fn explorer_message(msg: &ExplorerToPlanet) -> String {
+    match msg {
+        ExplorerToPlanet::GenerateResourceRequest { resource, .. } => {
+            format!("GenerateResourceRequest {resource:?}")
+        }
+        ExplorerToPlanet::CombineResourceRequest { msg, .. } => {
+            format!("CombineResourceRequest {}", variant_name(msg))
+        }
+        other => variant_name(other),
+    }
+}
+
+fn orchestrator_outcome(response: &PlanetToOrchestrator) -> String {
+    match response {
+        PlanetToOrchestrator::AsteroidAck { rocket, .. } => {
+            format!("AsteroidAck rocket={}", rocket.is_some())
+        }
+        PlanetToOrchestrator::InternalStateResponse { planet_state, .. } => format!(
+            "InternalStateResponse charged={}/{} rocket={}",
+            planet_state.charged_cells_count,
+            planet_state.energy_cells.len(),
+            planet_state.has_rocket
+        ),
+        other => variant_name(other),
+    }
+}
+
+fn explorer_outcome(response: &PlanetToExplorer) -> String {
+    let sorted = |names: Vec<String>| {
+        let mut names = names;
+        names.sort_unstable();
+        names.join(",")
+    };
+    match response {
+        PlanetToExplorer::SupportedResourceResponse { resource_list } => format!(
+            "SupportedResourceResponse [{}]",
+            sorted(resource_list.iter().map(|r| format!("{r:?}")).collect())
+        ),
+        PlanetToExplorer::SupportedCombinationResponse { combination_list } => format!(
+            "SupportedCombinationResponse [{}]",
+            sorted(combination_list.iter().map(|r| format!("{r:?}")).collect())
+        ),
+        PlanetToExplorer::GenerateResourceResponse { resource } => match resource {
+            Some(resource) => format!("GenerateResourceResponse {:?}", resource.get_type()),
+            None => "GenerateResourceResponse none".into(),
+        },
+        PlanetToExplorer::CombineResourceResponse { complex_response } => match complex_response {
+            Ok(resource) => format!("CombineResourceResponse {:?}", resource.get_type()),
+            Err((error, ..)) => format!("CombineResourceResponse error {error:?}"),
+        },
+        PlanetToExplorer::AvailableEnergyCellResponse { available_cells } => {
+            format!("AvailableEnergyCellResponse {available_cells}")
+        }
+        other => variant_name(other),
+    }
+}
+
+/// Compares `trace` with `tests/golden/<name>.txt`, or overwrites the file
+/// with it when `UPDATE_GOLDEN=1` is set.
+pub(crate) fn assert_golden(name: &str, trace: &str) {
+    let path = format!("{}/tests/golden/{name}.txt", env!("CARGO_MANIFEST_DIR"));
+    if std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1") {
+        std::fs::write(&path, trace).unwrap_or_else(|err| panic!("can't write {path}: {err}"));
+        return;
+    }
+    let golden =
+        std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"));
+    let mut golden_lines = golden.lines();
+    for (line, actual) in trace.lines().enumerate() {
+        let expected = golden_lines.next();
+        assert_eq!(
+            Some(actual),
+            expected,
+            "{path}:{} differs, rerun with UPDATE_GOLDEN=1 if the change is intended",
+            line + 1
+        );
+    }
+    assert_eq!(
+        golden_lines.next(),
+        None,
+        "{path} has more lines than the trace, rerun with UPDATE_GOLDEN=1 if the change is intended"
+    );
+}
+
+/// The canonical 30-message game behind `tests/golden/standard_game.txt`,
+/// for the type B planet of [TestPlanet::orbitron].
+pub(crate) fn standard_game() -> Vec<Step> {
+    use OrchestratorToPlanet as O;
+    let (generator, _) = lab();
+    let generate = |explorer_id, resource| {
+        Step::Explorer(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id,
+            resource,
+        })
+    };
+    let cells =
+        |explorer_id| Step::Explorer(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id });
+    let combine = |msg| {
+        Step::Explorer(ExplorerToPlanet::CombineResourceRequest {
+            explorer_id: 1,
+            msg,
+        })
+    };
+    let water = ComplexResourceRequest::Water(
+        generator.make_hydrogen(&mut charged_cell()).unwrap(),
+        generator.make_oxygen(&mut charged_cell()).unwrap(),
+    );
+    let diamond = ComplexResourceRequest::Diamond(
+        generator.make_carbon(&mut charged_cell()).unwrap(),
+        generator.make_carbon(&mut charged_cell()).unwrap(),
+    );
+
+    vec![
+        Step::Orchestrator(O::StartPlanetAI),
+        Step::Explorer(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 }),
+        Step::Explorer(ExplorerToPlanet::SupportedCombinationRequest { explorer_id: 1 }),
+        cells(1),
+        generate(1, BasicResourceType::Hydrogen),
+        Step::Orchestrator(O::Sunray(sunray())),
+        cells(1),
+        generate(1, BasicResourceType::Hydrogen),
+        Step::Orchestrator(O::Sunray(sunray())),
+        generate(1, BasicResourceType::Carbon),
+        generate(1, BasicResourceType::Oxygen),
+        Step::Orchestrator(O::Sunray(sunray())),
+        combine(water),
+        combine(diamond),
+        Step::Orchestrator(O::Sunray(sunray())),
+        Step::Orchestrator(O::Sunray(sunray())),
+        Step::Orchestrator(O::InternalStateRequest),
+        generate(2, BasicResourceType::Oxygen),
+        cells(2),
+        Step::Orchestrator(O::StopPlanetAI),
+        Step::Orchestrator(O::Sunray(sunray())),
+        generate(2, BasicResourceType::Hydrogen),
+        Step::Orchestrator(O::StartPlanetAI),
+        Step::Orchestrator(O::Sunray(sunray())),
+        generate(3, BasicResourceType::Hydrogen),
+        Step::Orchestrator(O::Sunray(sunray())),
+        Step::Orchestrator(O::Asteroid(asteroid())),
+        generate(3, BasicResourceType::Oxygen),
+        Step::Orchestrator(O::InternalStateRequest),
+        Step::Orchestrator(O::KillPlanet),
+    ]
+}
+
+/// A game played on a planet that is never started, behind
+/// `tests/golden/stopped_game.txt`.
+pub(crate) fn stopped_game() -> Vec<Step> {
+    use OrchestratorToPlanet as O;
+    vec![
+        Step::Orchestrator(O::Sunray(sunray())),
+        Step::Explorer(ExplorerToPlanet::SupportedResourceRequest { explorer_id: 1 }),
+        Step::Explorer(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: 1,
+            resource: BasicResourceType::Hydrogen,
+        }),
+        Step::Orchestrator(O::InternalStateRequest),
+        Step::Orchestrator(O::Asteroid(asteroid())),
+        Step::Orchestrator(O::StopPlanetAI),
+        Step::Orchestrator(O::KillPlanet),
+    ]
+}
@@ -0,0 +1,62 @@
+//! Canonical Type B planet parts for direct unit tests.
+//!
+//! `common_game` only builds a [PlanetState] inside a [Planet], and hands it
+//! out read-only, so [type_b] builds a whole planet with [create_planet]
+//! and never runs it: its state, generator and combinator are exactly the
+//! ones an Orbitron planet starts with. Each call builds fresh parts, as
+//! none of them can be cloned; the rules are read once from
+//! [planet_metadata](crate::planet_metadata).
+use std::sync::LazyLock;
+
+use common_game::components::planet::{Planet, PlanetState, PlanetType};
+use common_game::components::resource::{
+    BasicResourceType, Combinator, ComplexResourceType, Generator,
+};
+use common_game::utils::ID;
+use crossbeam_channel::unbounded;
+
+use crate::{PlanetMetadata, create_planet};
+
+static METADATA: LazyLock<PlanetMetadata> = LazyLock::new(crate::planet_metadata);
+
+/// Id of the planets built by [type_b].
+pub(crate) const FIXTURE_PLANET_ID: ID = 44;
+
+/// A freshly created Orbitron planet, never run.
+pub(crate) struct TypeB(Planet);
+
+/// Builds a fresh Type B planet, with every cell empty.
+pub(crate) fn type_b() -> TypeB {
+    let (_, rx_orch) = unbounded();
+    let (tx_orch, _) = unbounded();
+    let (_, rx_expl) = unbounded();
+    TypeB(create_planet(rx_orch, tx_orch, rx_expl, FIXTURE_PLANET_ID))
+}
+
+impl TypeB {
+    pub(crate) fn planet_type(&self) -> PlanetType {
+        self.0.planet_type()
+    }
+
+    pub(crate) fn state(&self) -> &PlanetState {
+        self.0.state()
+    }
+
+    pub(crate) fn generator(&self) -> &Generator {
+        self.0.generator()
+    }
+
+    pub(crate) fn combinator(&self) -> &Combinator {
+        self.0.combinator()
+    }
+}
+
+/// Generation rules of the Orbitron planet.
+pub(crate) fn gen_rules() -> &'static [BasicResourceType] {
+    &METADATA.gen_rules
+}
+
+/// Combination rules of the Orbitron planet.
+pub(crate) fn comb_rules() -> &'static [ComplexResourceType] {
+    &METADATA.comb_rules
+}
@@ -0,0 +1,216 @@
+//! Orchestrators and explorers breaking the expected message order, played
+//! against the real threaded planet. Whatever they send, the planet must
+//! answer negatively within [TIMEOUT], or not at all once it is gone, and
+//! its thread must end without panicking.
+use std::sync::{LazyLock, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use common_game::components::forge::Forge;
+use common_game::components::resource::{
+    BasicResource, BasicResourceType, ComplexResourceRequest, GenericResource,
+};
+use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestrator};
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+use common_game::utils::ID;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+
+const TIMEOUT: Duration = Duration::from_secs(2);
+
+/// A process may only have one forge.
+static FORGE: LazyLock<Mutex<Forge>> =
+    LazyLock::new(|| Mutex::new(Forge::new().expect("forge already created")));
+
+/// An Orbitron planet running on its own thread.
+struct Running {
+    to_planet: Sender<OrchestratorToPlanet>,
+    from_planet: Receiver<PlanetToOrchestrator>,
+    explorer_tx: Sender<ExplorerToPlanet>,
+    thread: JoinHandle<Result<(), String>>,
+}
+
+/// An explorer docked on a [Running] planet.
+struct Explorer {
+    id: ID,
+    to_planet: Sender<ExplorerToPlanet>,
+    from_planet: Receiver<PlanetToExplorer>,
+}
+
+impl Running {
+    fn spawn(planet_id: ID) -> Self {
+        let (to_planet, from_orchestrator) = unbounded();
+        let (to_orchestrator, from_planet) = unbounded();
+        let (explorer_tx, from_explorers) = unbounded();
+        let thread = std::thread::spawn(move || {
+            orbitron::create_planet(
+                from_orchestrator,
+                to_orchestrator,
+                from_explorers,
+                planet_id,
+            )
+            .run()
+        });
+        Self {
+            to_planet,
+            from_planet,
+            explorer_tx,
+            thread,
+        }
+    }
+
+    fn request(&self, msg: OrchestratorToPlanet) -> PlanetToOrchestrator {
+        self.to_planet.send(msg).expect("planet thread is gone");
+        self.from_planet
+            .recv_timeout(TIMEOUT)
+            .expect("planet did not answer the orchestrator in time")
+    }
+
+    fn start(&self) {
+        let answer = self.request(OrchestratorToPlanet::StartPlanetAI);
+        assert!(matches!(
+            answer,
+            PlanetToOrchestrator::StartPlanetAIResult { .. }
+        ));
+    }
+
+    fn sunray(&self) {
+        let sunray = FORGE.lock().unwrap().generate_sunray();
+        let answer = self.request(OrchestratorToPlanet::Sunray(sunray));
+        assert!(matches!(answer, PlanetToOrchestrator::SunrayAck { .. }));
+    }
+
+    fn dock(&self, id: ID) -> Explorer {
+        let (new_sender, from_planet) = unbounded();
+        let answer = self.request(OrchestratorToPlanet::IncomingExplorerRequest {
+            explorer_id: id,
+            new_sender,
+        });
+        assert!(matches!(
+            answer,
+            PlanetToOrchestrator::IncomingExplorerResponse { res: Ok(()), .. }
+        ));
+        Explorer {
+            id,
+            to_planet: self.explorer_tx.clone(),
+            from_planet,
+        }
+    }
+
+    /// Kills the planet and checks its thread ended cleanly.
+    fn kill(self) -> Sender<OrchestratorToPlanet> {
+        let answer = self.request(OrchestratorToPlanet::KillPlanet);
+        assert!(matches!(
+            answer,
+            PlanetToOrchestrator::KillPlanetResult { .. }
+        ));
+        let result = self.thread.join().expect("planet thread panicked");
+        assert_eq!(result, Ok(()));
+        self.to_planet
+    }
+}
+
+impl Explorer {
+    fn request(&self, msg: ExplorerToPlanet) -> PlanetToExplorer {
+        self.to_planet.send(msg).expect("planet thread is gone");
+        self.from_planet
+            .recv_timeout(TIMEOUT)
+            .expect("planet did not answer the explorer in time")
+    }
+
+    fn generate(&self, resource: BasicResourceType) -> BasicResource {
+        match self.request(ExplorerToPlanet::GenerateResourceRequest {
+            explorer_id: self.id,
+            resource,
+        }) {
+            PlanetToExplorer::GenerateResourceResponse {
+                resource: Some(resource),
+            } => resource,
+            other => panic!("expected a generated {resource:?}, got {other:?}"),
+        }
+    }
+}
+
+#[test]
+fn asteroid_before_start() {
+    let planet = Running::spawn(1);
+    let asteroid = FORGE.lock().unwrap().generate_asteroid();
+
+    // The loop answers itself, the AI never sees the asteroid
+    let answer = planet.request(OrchestratorToPlanet::Asteroid(asteroid));
+    assert!(
+        matches!(answer, PlanetToOrchestrator::Stopped { .. }),
+        "unexpected answer {answer:?}"
+    );
+    planet.start();
+    planet.kill();
+}
+
+#[test]
+fn combination_before_any_sunray() {
+    // The inputs come from another planet: this one never got a Sunray
+    let source = Running::spawn(2);
+    source.start();
+    let supplier = source.dock(20);
+    source.sunray();
+    let BasicResource::Hydrogen(hydrogen) = supplier.generate(BasicResourceType::Hydrogen) else {
+        panic!("expected Hydrogen");
+    };
+    source.sunray();
+    let BasicResource::Oxygen(oxygen) = supplier.generate(BasicResourceType::Oxygen) else {
+        panic!("expected Oxygen");
+    };
+    source.kill();
+
+    let planet = Running::spawn(3);
+    planet.start();
+    let explorer = planet.dock(30);
+    match explorer.request(ExplorerToPlanet::CombineResourceRequest {
+        explorer_id: explorer.id,
+        msg: ComplexResourceRequest::Water(hydrogen, oxygen),
+    }) {
+        PlanetToExplorer::CombineResourceResponse {
+            complex_response: Err((_, input_1, input_2)),
+        } => {
+            // Both inputs come back to the explorer
+            assert!(matches!(
+                input_1,
+                GenericResource::BasicResources(BasicResource::Hydrogen(_))
+            ));
+            assert!(matches!(
+                input_2,
+                GenericResource::BasicResources(BasicResource::Oxygen(_))
+            ));
+        }
+        other => panic!("expected a refused combination, got {other:?}"),
+    }
+    planet.kill();
+}
+
+#[test]
+fn kill_planet_twice() {
+    let planet = Running::spawn(4);
+    planet.start();
+    let to_planet = planet.kill();
+
+    // The loop is gone with its receiver, so the second kill can't be sent
+    assert!(to_planet.send(OrchestratorToPlanet::KillPlanet).is_err());
+}
+
+#[test]
+fn explorer_messages_after_kill() {
+    let planet = Running::spawn(5);
+    planet.start();
+    let explorer = planet.dock(50);
+    planet.kill();
+
+    let sent = explorer
+        .to_planet
+        .send(ExplorerToPlanet::AvailableEnergyCellRequest {
+            explorer_id: explorer.id,
+        });
+    assert!(sent.is_err());
+    assert_eq!(
+        explorer.from_planet.recv_timeout(TIMEOUT).unwrap_err(),
+        RecvTimeoutError::Disconnected
+    );
+}